@@ -0,0 +1,17 @@
+use std::path::Path;
+
+use elax_index::{build_part, read_part_assets, VectorPrecision};
+
+/// Merge every row in `parts` into a single new part named `output`.
+/// Duplicate ids across input parts are kept as separate rows — callers
+/// doing delete-aware compaction need to dedupe before calling this.
+pub fn run(parts_dir: &Path, parts: &[String], output: &str) -> anyhow::Result<()> {
+    let mut rows = Vec::new();
+    for part_name in parts {
+        rows.extend(read_part_assets(parts_dir, part_name)?);
+    }
+
+    let meta = build_part(parts_dir, output, &rows, VectorPrecision::F32)?;
+    println!("compacted {} parts into '{output}' ({} rows)", parts.len(), meta.count());
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use std::fs;
+use std::path::Path;
+
+use elax_core::Document;
+use elax_index::{build_part, Row, VectorPrecision};
+
+/// Load NDJSON `Document`s from `input` and write them out as a new part.
+pub fn run(parts_dir: &Path, part_name: &str, input: &Path) -> anyhow::Result<()> {
+    let text = fs::read_to_string(input)?;
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let doc: Document = serde_json::from_str(line)?;
+        rows.push(Row::new(doc.id, doc.vector));
+    }
+
+    let meta = build_part(parts_dir, part_name, &rows, VectorPrecision::F32)?;
+    println!("imported {} rows into part '{part_name}'", meta.count());
+    Ok(())
+}
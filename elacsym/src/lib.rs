@@ -0,0 +1,17 @@
+//! Public, semver-stable facade for embedding elacsym in a Rust
+//! application without running `elax-api`'s HTTP server. The internal
+//! crates (`elax-core`, `elax-index`, ...) evolve with the rest of this
+//! workspace and aren't meant to be depended on directly — this crate
+//! re-exports the pieces of `elax-core` an embedder needs plus a couple of
+//! typed request builders, and is the only part of the workspace this
+//! project commits to keeping source-compatible across patch releases.
+
+mod request;
+
+pub use elax_core::{
+    AttrOrder, CoreError, Document, EmbeddingHook, FilterExpr, NamespaceRegistry, NullsOrder, QueryCursor, QueryFilter,
+    QueryPage, SortDirection, TieredNamespace,
+};
+pub use request::{QueryRequestBuilder, WriteBatchBuilder};
+
+pub type Result<T> = std::result::Result<T, CoreError>;
@@ -0,0 +1,115 @@
+//! `elacsym`: administration and data-loading CLI. Talks directly to a
+//! part directory on disk — the same layout the server reads and writes —
+//! rather than going through the HTTP API, since most of these are
+//! operator-side maintenance tasks run next to the data.
+
+mod compact;
+mod import;
+mod query;
+mod recall;
+mod replay;
+mod snapshot;
+mod wal;
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "elacsym", about = "elacsym administration CLI")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Load NDJSON documents into a new part.
+    Import {
+        #[arg(long)]
+        parts_dir: PathBuf,
+        #[arg(long)]
+        part_name: String,
+        #[arg(long)]
+        input: PathBuf,
+    },
+    /// Run an ad-hoc vector query against a set of parts.
+    Query {
+        #[arg(long)]
+        parts_dir: PathBuf,
+        /// Comma-separated part names to search.
+        #[arg(long, value_delimiter = ',')]
+        parts: Vec<String>,
+        /// Comma-separated query vector, e.g. "1.0,0.0,0.5".
+        #[arg(long, value_delimiter = ',')]
+        vector: Vec<f32>,
+        #[arg(long, default_value_t = 10)]
+        top_k: usize,
+    },
+    /// Compute IVF recall against a ground-truth query file.
+    Recall {
+        #[arg(long)]
+        parts_dir: PathBuf,
+        #[arg(long)]
+        part_name: String,
+        #[arg(long)]
+        queries: PathBuf,
+        #[arg(long, default_value_t = 8)]
+        nlist: usize,
+        #[arg(long, default_value_t = 2)]
+        nprobe: usize,
+    },
+    /// Replay a sampled query log (written by `elax_core::QueryLog::save`)
+    /// against a set of parts, for an apples-to-apples recall/latency
+    /// comparison before and after a config or version change.
+    Replay {
+        #[arg(long)]
+        parts_dir: PathBuf,
+        #[arg(long)]
+        part_name: String,
+        #[arg(long)]
+        query_log: PathBuf,
+        #[arg(long, default_value_t = 8)]
+        nlist: usize,
+        #[arg(long, default_value_t = 2)]
+        nprobe: usize,
+    },
+    /// Merge several parts' rows into a single new part.
+    Compact {
+        #[arg(long)]
+        parts_dir: PathBuf,
+        #[arg(long, value_delimiter = ',')]
+        parts: Vec<String>,
+        #[arg(long)]
+        output: String,
+    },
+    /// Export or import a part directory as a plain directory copy.
+    Snapshot {
+        #[command(subcommand)]
+        action: snapshot::SnapshotAction,
+    },
+    /// Inspect a write-ahead log file.
+    Wal {
+        #[command(subcommand)]
+        action: wal::WalAction,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Import { parts_dir, part_name, input } => import::run(&parts_dir, &part_name, &input),
+        Command::Query { parts_dir, parts, vector, top_k } => {
+            query::run(&parts_dir, &parts, &vector, top_k)
+        }
+        Command::Recall { parts_dir, part_name, queries, nlist, nprobe } => {
+            recall::run(&parts_dir, &part_name, &queries, nlist, nprobe)
+        }
+        Command::Replay { parts_dir, part_name, query_log, nlist, nprobe } => {
+            replay::run(&parts_dir, &part_name, &query_log, nlist, nprobe)
+        }
+        Command::Compact { parts_dir, parts, output } => compact::run(&parts_dir, &parts, &output),
+        Command::Snapshot { action } => snapshot::run(action),
+        Command::Wal { action } => wal::run(action),
+    }
+}
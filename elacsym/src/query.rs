@@ -0,0 +1,16 @@
+use std::path::Path;
+
+use elax_index::search_namespace;
+
+/// Run an ad-hoc vector query against `parts`, printing ranked results as
+/// JSON lines.
+pub fn run(parts_dir: &Path, parts: &[String], vector: &[f32], top_k: usize) -> anyhow::Result<()> {
+    let results = search_namespace(vector, top_k, &[], parts_dir, parts)?;
+    for row in results {
+        println!("{}", serde_json::to_string(&serde_json::json!({
+            "id": row.id,
+            "score": row.score,
+        }))?);
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use std::fs;
+use std::path::Path;
+
+use elax_index::{debug_recall, read_part_assets, IvfIndex};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct QueryRecord {
+    query: Vec<f32>,
+    truth: Vec<String>,
+}
+
+/// Build an `IvfIndex` from `part_name`'s rows and report its recall at
+/// `nprobe` against the ground-truth queries in `queries` (NDJSON, one
+/// `{"query": [...], "truth": [...]}` per line).
+pub fn run(parts_dir: &Path, part_name: &str, queries: &Path, nlist: usize, nprobe: usize) -> anyhow::Result<()> {
+    let rows = read_part_assets(parts_dir, part_name)?;
+    let index = IvfIndex::build(rows, nlist);
+
+    let text = fs::read_to_string(queries)?;
+    let parsed: Vec<(Vec<f32>, Vec<String>)> = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str::<QueryRecord>(l).map(|r| (r.query, r.truth)))
+        .collect::<Result<_, _>>()?;
+
+    let recall = debug_recall(&index, &parsed, nprobe);
+    println!("recall@nprobe={nprobe}: {recall:.4}");
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use elax_core::QueryLog;
+use elax_index::{read_part_assets, IvfIndex};
+
+/// Build an `IvfIndex` from `part_name`'s rows and replay every entry in
+/// `query_log` (the JSON an `elax_core::QueryLog::save` call produced)
+/// that kept its raw vector, printing each one's result ids alongside its
+/// replay latency and the latency recorded when it was first sampled —
+/// diffing two runs of this command across a config or version change is
+/// the before/after comparison.
+pub fn run(parts_dir: &Path, part_name: &str, query_log: &Path, nlist: usize, nprobe: usize) -> anyhow::Result<()> {
+    let rows = read_part_assets(parts_dir, part_name)?;
+    let index = IvfIndex::build(rows, nlist);
+
+    let text = fs::read_to_string(query_log)?;
+    let log: QueryLog = serde_json::from_str(&text)?;
+
+    let skipped = log.entries.iter().filter(|entry| entry.vector.is_none()).count();
+    if skipped > 0 {
+        eprintln!("skipping {skipped} entries with no stored vector");
+    }
+
+    for entry in &log.entries {
+        let Some(vector) = entry.vector.as_ref() else { continue };
+        let start = Instant::now();
+        let hits = index.probe(vector, nprobe);
+        let replay_latency_micros = start.elapsed().as_micros() as u64;
+        println!(
+            "{}",
+            serde_json::to_string(&serde_json::json!({
+                "query_hash": entry.query_hash,
+                "hits": hits.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+                "replay_latency_micros": replay_latency_micros,
+                "recorded_latency_micros": entry.latency_micros,
+            }))?
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! Typed request builders over [`elax_core::NamespaceRegistry`]'s
+//! `query_by_filter`/`apply_write_batch`, for an embedder that would
+//! rather build a request value than remember each method's positional
+//! argument order. Blocking: every method here runs the call synchronously
+//! on the calling thread, the same as the rest of this workspace (there's
+//! no async runtime anywhere in it).
+
+use elax_core::{AttrOrder, Document, FilterExpr, NamespaceRegistry, QueryCursor, QueryFilter, QueryPage};
+
+/// Builds a [`NamespaceRegistry::query_by_filter`] call one piece at a
+/// time. `run` consumes the builder and blocks until the registry answers.
+#[derive(Debug, Clone)]
+pub struct QueryRequestBuilder {
+    namespace: String,
+    filter: QueryFilter,
+    order_by: Vec<AttrOrder>,
+    cursor: Option<QueryCursor>,
+    limit: usize,
+}
+
+impl QueryRequestBuilder {
+    /// A request against `namespace` with no filter, no ordering, and a
+    /// default page size of 50 — callers narrow it down with the other
+    /// builder methods before calling `run`.
+    pub fn new(namespace: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            filter: QueryFilter::default(),
+            order_by: Vec::new(),
+            cursor: None,
+            limit: 50,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: FilterExpr) -> Self {
+        self.filter.expr = Some(filter);
+        self
+    }
+
+    pub fn with_similar_to(mut self, query_vector: Vec<f32>, min_score: f32) -> Self {
+        self.filter.similar_to = Some((query_vector, min_score));
+        self
+    }
+
+    pub fn with_order_by(mut self, order_by: Vec<AttrOrder>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    /// Resume from the cursor a previous `run` returned in
+    /// [`QueryPage::next_cursor`].
+    pub fn after(mut self, cursor: QueryCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn run(self, registry: &NamespaceRegistry) -> elax_core::error::Result<QueryPage> {
+        registry.query_by_filter(&self.namespace, &self.filter, &self.order_by, self.cursor.as_ref(), self.limit)
+    }
+}
+
+/// Builds a [`NamespaceRegistry::apply_write_batch`] call, accumulating
+/// documents one at a time rather than requiring the caller to assemble a
+/// `Vec<Document>` up front.
+#[derive(Debug, Clone)]
+pub struct WriteBatchBuilder {
+    namespace: String,
+    docs: Vec<Document>,
+}
+
+impl WriteBatchBuilder {
+    pub fn new(namespace: impl Into<String>) -> Self {
+        Self { namespace: namespace.into(), docs: Vec::new() }
+    }
+
+    pub fn push(mut self, doc: Document) -> Self {
+        self.docs.push(doc);
+        self
+    }
+
+    /// Apply every pushed document, in order, returning one `Result` per
+    /// document in the same order they were pushed — the same
+    /// partial-failure shape as
+    /// [`NamespaceRegistry::apply_write_batch`] itself.
+    pub fn run(self, registry: &mut NamespaceRegistry) -> Vec<elax_core::error::Result<String>> {
+        registry.apply_write_batch(&self.namespace, self.docs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elax_core::NamespaceConfig;
+
+    fn registry_with_docs(namespace: &str, docs: Vec<Document>) -> NamespaceRegistry {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(namespace, NamespaceConfig::default());
+        for doc in docs {
+            registry.apply_write(namespace, doc).unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn query_request_builder_runs_a_filtered_query() {
+        let registry = registry_with_docs(
+            "docs",
+            vec![
+                Document::new("a", vec![1.0, 0.0]),
+                Document::new("b", vec![0.0, 1.0]),
+            ],
+        );
+
+        let page = QueryRequestBuilder::new("docs").with_limit(1).run(&registry).unwrap();
+        assert_eq!(page.rows.len(), 1);
+    }
+
+    #[test]
+    fn write_batch_builder_applies_every_pushed_document() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure("docs", NamespaceConfig::default());
+
+        let results = WriteBatchBuilder::new("docs")
+            .push(Document::new("a", vec![1.0, 0.0]))
+            .push(Document::new("b", vec![0.0, 1.0]))
+            .run(&mut registry);
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert!(registry.exists("docs", "a").unwrap());
+        assert!(registry.exists("docs", "b").unwrap());
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Copy a part directory out to a snapshot directory.
+    Export {
+        #[arg(long)]
+        source: PathBuf,
+        #[arg(long)]
+        dest: PathBuf,
+    },
+    /// Restore a snapshot directory back into a part directory.
+    Import {
+        #[arg(long)]
+        source: PathBuf,
+        #[arg(long)]
+        dest: PathBuf,
+    },
+}
+
+pub fn run(action: SnapshotAction) -> anyhow::Result<()> {
+    match action {
+        SnapshotAction::Export { source, dest } | SnapshotAction::Import { source, dest } => {
+            copy_dir_recursive(&source, &dest)?;
+            println!("copied '{}' to '{}'", source.display(), dest.display());
+            Ok(())
+        }
+    }
+}
+
+fn copy_dir_recursive(source: &Path, dest: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum WalAction {
+    /// Summarize a WAL file's records.
+    ///
+    /// Reads the NDJSON record stream used by `import`/`compact`, one JSON
+    /// value per non-blank line; the binary framed log written by
+    /// `elax_store::wal` is not understood by this subcommand.
+    Inspect {
+        #[arg(long)]
+        path: PathBuf,
+    },
+}
+
+pub fn run(action: WalAction) -> anyhow::Result<()> {
+    match action {
+        WalAction::Inspect { path } => inspect(&path),
+    }
+}
+
+fn inspect(path: &std::path::Path) -> anyhow::Result<()> {
+    let text = fs::read_to_string(path)?;
+    let mut count = 0usize;
+    let mut first: Option<String> = None;
+    let mut last: Option<String> = None;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        serde_json::from_str::<serde_json::Value>(line)?;
+        if first.is_none() {
+            first = Some(line.to_string());
+        }
+        last = Some(line.to_string());
+        count += 1;
+    }
+
+    println!("records: {count}");
+    if let Some(first) = first {
+        println!("first: {first}");
+    }
+    if let Some(last) = last {
+        println!("last: {last}");
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Structured per-request access logging, with an aggregation view by API
+//! key for multi-tenant chargeback. Sits alongside [`crate::auth::AuthMetrics`]
+//! as request-level accounting, but keeps every entry (rather than just
+//! running totals) since the aggregation is sliced by a key that isn't
+//! known until the entry is recorded.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// One request's cost, as seen from [`crate::server::ApiServer`]. `route`
+/// and `namespace` are the deployment-facing labels (the exact path, and
+/// the `/v2/tenants/:tenant/namespaces/:namespace/...` segment when the
+/// path has one); `candidates_scored` and `cache_hit` default to their
+/// zero values at the generic server-level hook since [`crate::http::Response`]
+/// doesn't carry them — a handler with that detail can record a fuller
+/// entry itself once it's wired through.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessLogEntry {
+    pub namespace: Option<String>,
+    pub route: String,
+    pub api_key: Option<String>,
+    pub latency_micros: u64,
+    pub candidates_scored: usize,
+    pub bytes_returned: usize,
+    pub cache_hit: bool,
+}
+
+/// Per-API-key rollup across every entry recorded under that key, for
+/// chargeback in a multi-tenant deployment. Requests with no API key (a
+/// JWT bearer token, or a public route) roll up under `None`, the same key
+/// `AccessLogEntry::api_key` carries.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiKeyUsage {
+    pub request_count: u64,
+    pub total_latency_micros: u64,
+    pub total_candidates_scored: usize,
+    pub total_bytes_returned: usize,
+    pub cache_hits: u64,
+}
+
+/// An append-only log of [`AccessLogEntry`]s, guarded by a mutex since
+/// requests are handled from multiple threads with no async runtime to
+/// serialize them. Unbounded for now, the same trade-off
+/// [`elax_core::QueryLog`] makes before its own sampling kicks in.
+#[derive(Default)]
+pub struct AccessLog {
+    entries: Mutex<Vec<AccessLogEntry>>,
+}
+
+impl AccessLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, entry: AccessLogEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    pub fn entries(&self) -> Vec<AccessLogEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Roll every recorded entry up by `api_key`, for a chargeback
+    /// endpoint to serve directly.
+    pub fn usage_by_api_key(&self) -> HashMap<Option<String>, ApiKeyUsage> {
+        let mut usage: HashMap<Option<String>, ApiKeyUsage> = HashMap::new();
+        for entry in self.entries.lock().unwrap().iter() {
+            let aggregate = usage.entry(entry.api_key.clone()).or_default();
+            aggregate.request_count += 1;
+            aggregate.total_latency_micros += entry.latency_micros;
+            aggregate.total_candidates_scored += entry.candidates_scored;
+            aggregate.total_bytes_returned += entry.bytes_returned;
+            if entry.cache_hit {
+                aggregate.cache_hits += 1;
+            }
+        }
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(api_key: Option<&str>, latency_micros: u64, cache_hit: bool) -> AccessLogEntry {
+        AccessLogEntry {
+            namespace: Some("docs".into()),
+            route: "/v2/tenants/acme/namespaces/docs/query".into(),
+            api_key: api_key.map(String::from),
+            latency_micros,
+            candidates_scored: 10,
+            bytes_returned: 256,
+            cache_hit,
+        }
+    }
+
+    #[test]
+    fn records_entries_in_insertion_order() {
+        let log = AccessLog::new();
+        log.record(entry(Some("k1"), 10, false));
+        log.record(entry(Some("k2"), 20, true));
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].api_key, Some("k1".to_string()));
+        assert_eq!(entries[1].latency_micros, 20);
+    }
+
+    #[test]
+    fn usage_by_api_key_aggregates_per_key() {
+        let log = AccessLog::new();
+        log.record(entry(Some("k1"), 10, true));
+        log.record(entry(Some("k1"), 30, false));
+        log.record(entry(Some("k2"), 5, true));
+
+        let usage = log.usage_by_api_key();
+        let k1 = usage.get(&Some("k1".to_string())).unwrap();
+        assert_eq!(k1.request_count, 2);
+        assert_eq!(k1.total_latency_micros, 40);
+        assert_eq!(k1.total_candidates_scored, 20);
+        assert_eq!(k1.cache_hits, 1);
+
+        let k2 = usage.get(&Some("k2".to_string())).unwrap();
+        assert_eq!(k2.request_count, 1);
+        assert_eq!(k2.cache_hits, 1);
+    }
+
+    #[test]
+    fn requests_without_an_api_key_roll_up_under_none() {
+        let log = AccessLog::new();
+        log.record(entry(None, 15, false));
+        let usage = log.usage_by_api_key();
+        assert_eq!(usage.get(&None).unwrap().request_count, 1);
+    }
+}
@@ -0,0 +1,265 @@
+//! Static API key and JWT bearer authentication, with per-key scopes and
+//! per-namespace allowlists enforced before a request reaches its handler.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use crate::error::ApiError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Read,
+    Write,
+    Admin,
+}
+
+/// One statically-configured API key.
+#[derive(Debug, Clone)]
+pub struct ApiKeyEntry {
+    pub key: String,
+    pub scopes: Vec<Scope>,
+    /// `None` means "allowed for every namespace".
+    pub namespace_allowlist: Option<Vec<String>>,
+}
+
+/// Claims carried by a JWT bearer token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtClaims {
+    pub scopes: Vec<Scope>,
+    #[serde(default)]
+    pub namespaces: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    pub api_keys: Vec<ApiKeyEntry>,
+    pub jwt_secret: Option<String>,
+}
+
+/// The identity and grants resolved from a request's credentials.
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub scopes: Vec<Scope>,
+    pub namespace_allowlist: Option<Vec<String>>,
+    /// The API key that authenticated this request, so per-key usage can be
+    /// aggregated for chargeback (see `crate::access_log`). `None` for a JWT
+    /// bearer token, which has no single key to bill against.
+    pub api_key: Option<String>,
+}
+
+impl AuthContext {
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope) || self.scopes.contains(&Scope::Admin)
+    }
+
+    pub fn can_access(&self, namespace: &str) -> bool {
+        match &self.namespace_allowlist {
+            Some(allowed) => allowed.iter().any(|n| n == namespace),
+            None => true,
+        }
+    }
+}
+
+/// Authenticate a request from its `Authorization` header. `401` if the
+/// header is missing/unrecognized, otherwise the resolved grants (further
+/// scope/namespace checks are the caller's responsibility, surfaced as
+/// `403` — see `crate::server`'s `authorize`, called right after this on
+/// every non-public route).
+pub fn authenticate(
+    config: &AuthConfig,
+    headers: &HashMap<String, String>,
+) -> Result<AuthContext, ApiError> {
+    let header = headers
+        .get("authorization")
+        .ok_or_else(|| ApiError::Unauthorized("missing Authorization header".into()))?;
+
+    if let Some(key) = header.strip_prefix("ApiKey ") {
+        let entry = config
+            .api_keys
+            .iter()
+            .find(|e| bool::from(e.key.as_bytes().ct_eq(key.as_bytes())))
+            .ok_or_else(|| ApiError::Unauthorized("unknown API key".into()))?;
+        return Ok(AuthContext {
+            scopes: entry.scopes.clone(),
+            namespace_allowlist: entry.namespace_allowlist.clone(),
+            api_key: Some(entry.key.clone()),
+        });
+    }
+
+    if let Some(token) = header.strip_prefix("Bearer ") {
+        let secret = config
+            .jwt_secret
+            .as_deref()
+            .ok_or_else(|| ApiError::Unauthorized("JWT auth not configured".into()))?;
+        let claims = verify_jwt(token, secret)
+            .ok_or_else(|| ApiError::Unauthorized("invalid JWT".into()))?;
+        return Ok(AuthContext {
+            scopes: claims.scopes,
+            namespace_allowlist: claims.namespaces,
+            api_key: None,
+        });
+    }
+
+    Err(ApiError::Unauthorized("unrecognized credentials".into()))
+}
+
+/// Minimal HS256 JWT verification: `base64url(header).base64url(payload)`
+/// signed with HMAC-SHA256 over the same string.
+fn verify_jwt(token: &str, secret: &str) -> Option<JwtClaims> {
+    let mut parts = token.split('.');
+    let header_b64 = parts.next()?;
+    let payload_b64 = parts.next()?;
+    let signature_b64 = parts.next()?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let signing_input = format!("{header_b64}.{payload_b64}");
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+    mac.update(signing_input.as_bytes());
+    let signature = base64url_decode(signature_b64)?;
+    mac.verify_slice(&signature).ok()?;
+
+    let payload_bytes = base64url_decode(payload_b64)?;
+    serde_json::from_slice(&payload_bytes).ok()
+}
+
+const BASE64URL_ALPHABET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Only used by tests to assemble a signed token by hand; production
+/// verification no longer needs to re-encode a signature to compare it
+/// (see [`verify_jwt`]'s use of `Mac::verify_slice`).
+#[cfg(test)]
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(BASE64URL_ALPHABET[(n >> 18 & 0x3f) as usize] as char);
+        out.push(BASE64URL_ALPHABET[(n >> 12 & 0x3f) as usize] as char);
+        if chunk.len() > 1 {
+            out.push(BASE64URL_ALPHABET[(n >> 6 & 0x3f) as usize] as char);
+        }
+        if chunk.len() > 2 {
+            out.push(BASE64URL_ALPHABET[(n & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64url_decode(s: &str) -> Option<Vec<u8>> {
+    let decode_char = |c: u8| BASE64URL_ALPHABET.iter().position(|&a| a == c).map(|p| p as u32);
+    let chars: Vec<u8> = s.bytes().collect();
+    let mut out = Vec::new();
+    for chunk in chars.chunks(4) {
+        let vals: Vec<u32> = chunk.iter().map(|&c| decode_char(c)).collect::<Option<_>>()?;
+        let n = vals.iter().enumerate().fold(0u32, |acc, (i, v)| acc | (v << (18 - 6 * i)));
+        out.push((n >> 16) as u8);
+        if vals.len() > 2 {
+            out.push((n >> 8) as u8);
+        }
+        if vals.len() > 3 {
+            out.push(n as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Counters for the auth layer, surfaced alongside the rest of the API's
+/// metrics.
+#[derive(Default)]
+pub struct AuthMetrics {
+    pub authenticated: AtomicU64,
+    pub rejected: AtomicU64,
+}
+
+impl AuthMetrics {
+    pub fn record(&self, result: &Result<AuthContext, ApiError>) {
+        match result {
+            Ok(_) => self.authenticated.fetch_add(1, Ordering::Relaxed),
+            Err(_) => self.rejected.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(auth: &str) -> HashMap<String, String> {
+        HashMap::from([("authorization".to_string(), auth.to_string())])
+    }
+
+    #[test]
+    fn static_api_key_resolves_scopes_and_allowlist() {
+        let config = AuthConfig {
+            api_keys: vec![ApiKeyEntry {
+                key: "secret".into(),
+                scopes: vec![Scope::Read],
+                namespace_allowlist: Some(vec!["docs".into()]),
+            }],
+            jwt_secret: None,
+        };
+        let ctx = authenticate(&config, &headers("ApiKey secret")).unwrap();
+        assert!(ctx.has_scope(Scope::Read));
+        assert!(ctx.can_access("docs"));
+        assert!(!ctx.can_access("other"));
+    }
+
+    #[test]
+    fn unknown_api_key_is_unauthorized() {
+        let config = AuthConfig::default();
+        assert!(authenticate(&config, &headers("ApiKey nope")).is_err());
+    }
+
+    #[test]
+    fn jwt_round_trips_through_sign_and_verify() {
+        let secret = "jwt-secret";
+        let claims = JwtClaims {
+            scopes: vec![Scope::Write],
+            namespaces: None,
+        };
+        let header_b64 = base64url_encode(br#"{"alg":"HS256"}"#);
+        let payload_b64 = base64url_encode(&serde_json::to_vec(&claims).unwrap());
+        let signing_input = format!("{header_b64}.{payload_b64}");
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(signing_input.as_bytes());
+        let signature_b64 = base64url_encode(&mac.finalize().into_bytes());
+        let token = format!("{signing_input}.{signature_b64}");
+
+        let config = AuthConfig {
+            api_keys: vec![],
+            jwt_secret: Some(secret.into()),
+        };
+        let ctx = authenticate(&config, &headers(&format!("Bearer {token}"))).unwrap();
+        assert!(ctx.has_scope(Scope::Write));
+    }
+
+    #[test]
+    fn jwt_with_a_tampered_signature_is_rejected() {
+        let secret = "jwt-secret";
+        let claims = JwtClaims { scopes: vec![Scope::Write], namespaces: None };
+        let header_b64 = base64url_encode(br#"{"alg":"HS256"}"#);
+        let payload_b64 = base64url_encode(&serde_json::to_vec(&claims).unwrap());
+        let signing_input = format!("{header_b64}.{payload_b64}");
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(signing_input.as_bytes());
+        let mut signature = mac.finalize().into_bytes().to_vec();
+        signature[0] ^= 0xff;
+        let token = format!("{signing_input}.{}", base64url_encode(&signature));
+
+        let config = AuthConfig { api_keys: vec![], jwt_secret: Some(secret.into()) };
+        assert!(authenticate(&config, &headers(&format!("Bearer {token}"))).is_err());
+    }
+}
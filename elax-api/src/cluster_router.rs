@@ -0,0 +1,120 @@
+//! Cluster-aware routing so that multiple `elax-api` instances can each own
+//! a disjoint set of namespaces, proxying requests for namespaces they don't
+//! own to whichever node does.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use elax_core::error::Result;
+use elax_core::router::{NodeId, RouterState};
+
+/// Where a request for a given namespace should be served.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteDecision {
+    /// This node owns the namespace; handle it locally.
+    Local,
+    /// Another node owns the namespace; proxy there.
+    Proxy(NodeId),
+    /// No node is assigned yet; fall back to hash-based placement among
+    /// `peers`.
+    Unassigned(NodeId),
+}
+
+/// Routes namespace requests to the owning node, consulting a
+/// manifest-driven [`RouterState`] first and falling back to hashing the
+/// namespace modulo the known peer count when a namespace has no explicit
+/// assignment.
+pub struct ClusterRouter {
+    self_node: NodeId,
+    state: RouterState,
+    peers: Vec<NodeId>,
+}
+
+impl ClusterRouter {
+    pub fn new(self_node: NodeId, peers: Vec<NodeId>) -> Self {
+        Self {
+            self_node,
+            state: RouterState::default(),
+            peers,
+        }
+    }
+
+    pub fn state(&self) -> &RouterState {
+        &self.state
+    }
+
+    /// Decide which node should serve `namespace`.
+    pub fn route(&self, namespace: &str) -> RouteDecision {
+        if let Some(owner) = self.state.owner_of(namespace) {
+            return if owner == &self.self_node {
+                RouteDecision::Local
+            } else {
+                RouteDecision::Proxy(owner.clone())
+            };
+        }
+        let fallback = self.hash_peer(namespace);
+        if fallback == self.self_node {
+            RouteDecision::Local
+        } else {
+            RouteDecision::Unassigned(fallback)
+        }
+    }
+
+    /// Consistent-ish hash placement used until a namespace has a manifest
+    /// assignment.
+    fn hash_peer(&self, namespace: &str) -> NodeId {
+        if self.peers.is_empty() {
+            return self.self_node.clone();
+        }
+        let mut hasher = DefaultHasher::new();
+        namespace.hash(&mut hasher);
+        let idx = (hasher.finish() as usize) % self.peers.len();
+        self.peers[idx].clone()
+    }
+
+    /// Hand off ownership of `namespace` to `new_owner`, bumping the epoch
+    /// so stale routers reject the old assignment.
+    pub fn handoff(&mut self, namespace: &str, new_owner: NodeId) {
+        self.state.reassign(namespace, new_owner);
+    }
+
+    /// Adopt a `RouterState` observed from the manifest/peer gossip, only if
+    /// it is not stale relative to what we already know.
+    pub fn adopt_state(&mut self, observed: &RouterState) -> Result<()> {
+        self.state.merge_if_newer(observed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str) -> NodeId {
+        NodeId::new(id)
+    }
+
+    #[test]
+    fn unassigned_namespace_falls_back_to_hashing() {
+        let router = ClusterRouter::new(node("a"), vec![node("a"), node("b")]);
+        let decision = router.route("docs");
+        assert!(matches!(
+            decision,
+            RouteDecision::Local | RouteDecision::Unassigned(_)
+        ));
+    }
+
+    #[test]
+    fn explicit_assignment_wins_over_hashing() {
+        let mut router = ClusterRouter::new(node("a"), vec![node("a"), node("b")]);
+        router.handoff("docs", node("b"));
+        assert_eq!(router.route("docs"), RouteDecision::Proxy(node("b")));
+    }
+
+    #[test]
+    fn stale_state_is_rejected() {
+        let mut router = ClusterRouter::new(node("a"), vec![node("a"), node("b")]);
+        router.handoff("docs", node("b"));
+        let stale = RouterState::default();
+        assert!(router.adopt_state(&stale).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! gzip/zstd negotiation and byte-level (de)compression for request and
+//! response bodies. [`Request`](crate::http::Request)/[`Response`](crate::http::Response)
+//! carry bodies as already-parsed JSON, so these functions work on raw
+//! bytes instead — the socket-adapting layer this crate doesn't own yet
+//! (see [`crate::http`]'s module doc on the binary that adapts requests to
+//! real connections) is expected to call [`decode`] on the wire bytes of an
+//! incoming request before JSON-parsing them, and [`encode`] on a
+//! serialized response body once [`negotiate_encoding`] has picked a
+//! encoding.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::error::ApiError;
+
+/// The transport-level compression applied to a body, independent of
+/// [`crate::format::ResponseFormat`] (which picks the body's shape, not its
+/// wire compression).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentEncoding {
+    #[default]
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    /// The `Content-Encoding` header value to send alongside a body encoded
+    /// this way, or `None` for `Identity` (omit the header entirely).
+    pub fn header_value(&self) -> Option<&'static str> {
+        match self {
+            ContentEncoding::Identity => None,
+            ContentEncoding::Gzip => Some("gzip"),
+            ContentEncoding::Zstd => Some("zstd"),
+        }
+    }
+}
+
+/// Negotiate the response encoding from an `Accept-Encoding` header,
+/// preferring zstd over gzip when a client offers both, and falling back to
+/// no compression if the header is absent or names neither.
+pub fn negotiate_encoding(headers: &HashMap<String, String>) -> ContentEncoding {
+    let Some(accept_encoding) = headers.get("accept-encoding") else {
+        return ContentEncoding::Identity;
+    };
+    let offered: Vec<&str> = accept_encoding
+        .split(',')
+        .map(|token| token.split(';').next().unwrap_or("").trim())
+        .collect();
+    if offered.contains(&"zstd") {
+        ContentEncoding::Zstd
+    } else if offered.contains(&"gzip") {
+        ContentEncoding::Gzip
+    } else {
+        ContentEncoding::Identity
+    }
+}
+
+/// The encoding an incoming request body was compressed under, read from
+/// its `Content-Encoding` header — `Identity` if absent or unrecognized.
+pub fn request_encoding(headers: &HashMap<String, String>) -> ContentEncoding {
+    match headers.get("content-encoding").map(String::as_str) {
+        Some("gzip") => ContentEncoding::Gzip,
+        Some("zstd") => ContentEncoding::Zstd,
+        _ => ContentEncoding::Identity,
+    }
+}
+
+/// Compress `bytes` under `encoding`, or return them unchanged for
+/// `Identity`.
+pub fn encode(bytes: &[u8], encoding: ContentEncoding) -> Vec<u8> {
+    match encoding {
+        ContentEncoding::Identity => bytes.to_vec(),
+        ContentEncoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes).expect("writing to an in-memory buffer cannot fail");
+            encoder.finish().expect("finishing an in-memory gzip stream cannot fail")
+        }
+        ContentEncoding::Zstd => zstd::encode_all(bytes, 0).expect("zstd-encoding an in-memory buffer cannot fail"),
+    }
+}
+
+/// Decompress `bytes` that were encoded under `encoding`. There's no
+/// separate path for big write payloads — `bytes` is whatever the
+/// transport layer already buffered off the wire, so the same decoder
+/// handles a one-line upsert and a bulk import alike.
+pub fn decode(bytes: &[u8], encoding: ContentEncoding) -> Result<Vec<u8>, ApiError> {
+    match encoding {
+        ContentEncoding::Identity => Ok(bytes.to_vec()),
+        ContentEncoding::Gzip => {
+            let mut decoder = GzDecoder::new(bytes);
+            let mut out = Vec::new();
+            decoder
+                .read_to_end(&mut out)
+                .map_err(|err| ApiError::Internal(format!("gzip decode failed: {err}")))?;
+            Ok(out)
+        }
+        ContentEncoding::Zstd => {
+            zstd::decode_all(bytes).map_err(|err| ApiError::Internal(format!("zstd decode failed: {err}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(accept_encoding: &str) -> HashMap<String, String> {
+        HashMap::from([("accept-encoding".to_string(), accept_encoding.to_string())])
+    }
+
+    #[test]
+    fn defaults_to_identity_without_an_accept_encoding_header() {
+        assert_eq!(negotiate_encoding(&HashMap::new()), ContentEncoding::Identity);
+    }
+
+    #[test]
+    fn zstd_is_preferred_when_both_are_offered() {
+        assert_eq!(negotiate_encoding(&headers("gzip, zstd")), ContentEncoding::Zstd);
+    }
+
+    #[test]
+    fn gzip_is_picked_when_zstd_is_not_offered() {
+        assert_eq!(negotiate_encoding(&headers("gzip, deflate")), ContentEncoding::Gzip);
+    }
+
+    #[test]
+    fn gzip_round_trips() {
+        let payload = b"the quick brown fox jumps over the lazy dog".repeat(16);
+        let compressed = encode(&payload, ContentEncoding::Gzip);
+        assert!(compressed.len() < payload.len());
+        assert_eq!(decode(&compressed, ContentEncoding::Gzip).unwrap(), payload);
+    }
+
+    #[test]
+    fn zstd_round_trips() {
+        let payload = b"the quick brown fox jumps over the lazy dog".repeat(16);
+        let compressed = encode(&payload, ContentEncoding::Zstd);
+        assert!(compressed.len() < payload.len());
+        assert_eq!(decode(&compressed, ContentEncoding::Zstd).unwrap(), payload);
+    }
+
+    #[test]
+    fn identity_is_a_no_op() {
+        let payload = b"untouched".to_vec();
+        assert_eq!(encode(&payload, ContentEncoding::Identity), payload);
+        assert_eq!(decode(&payload, ContentEncoding::Identity).unwrap(), payload);
+    }
+}
@@ -0,0 +1,180 @@
+//! Scatter-gather query coordination across the shards of one namespace,
+//! for a topology where a namespace's parts are split across multiple
+//! nodes rather than owned wholesale by one — [`crate::cluster_router::ClusterRouter`]
+//! covers that simpler single-owner case. Fans a query out to every shard
+//! concurrently, gathers each shard's own top-k, and re-merges into one
+//! global top-k by raw score — safe here (unlike
+//! [`elax_core::search_across`]'s min-max normalization) because every
+//! shard scores with the same metric over disjoint parts of the same
+//! namespace, so the scores are already comparable.
+
+use std::thread;
+use std::time::Instant;
+
+use elax_core::router::NodeId;
+
+use crate::error::ApiError;
+
+/// One vector hit returned by a shard, before merging.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardHit {
+    pub id: String,
+    pub score: f32,
+}
+
+/// Per-shard timing recorded by [`QueryCoordinator::scatter_gather`], so a
+/// caller can see which shard (if any) is dragging down tail latency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardTiming {
+    pub node: NodeId,
+    pub elapsed_ms: u64,
+    pub hit_count: usize,
+}
+
+/// The merged global top-k plus per-shard diagnostics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScatterGatherResult {
+    pub hits: Vec<ShardHit>,
+    pub shard_timings: Vec<ShardTiming>,
+}
+
+/// The hook a deployment implements to actually reach a shard — over HTTP
+/// to that node's own `elax-api` instance in production (the same
+/// `std::net`-only style as [`elax_core::HttpEmbedder`]), or a
+/// deterministic stub in tests.
+pub trait ShardQuery: Send + Sync {
+    fn query_shard(&self, node: &NodeId, query: &[f32], top_k: usize) -> Result<Vec<ShardHit>, ApiError>;
+}
+
+/// Coordinates a single query across `shards`, talking to each one through
+/// `client`.
+pub struct QueryCoordinator<'a> {
+    shards: &'a [NodeId],
+    client: &'a dyn ShardQuery,
+}
+
+impl<'a> QueryCoordinator<'a> {
+    pub fn new(shards: &'a [NodeId], client: &'a dyn ShardQuery) -> Self {
+        Self { shards, client }
+    }
+
+    /// Fan `query` out to every shard, gather each one's own top-`top_k`,
+    /// and re-merge by raw score into one global top-`top_k`. Fails fast
+    /// on the first shard error, the same way [`elax_core::search_across`]
+    /// propagates a per-namespace search failure rather than returning a
+    /// partial result silently.
+    pub fn scatter_gather(&self, query: &[f32], top_k: usize) -> Result<ScatterGatherResult, ApiError> {
+        let results: Vec<(NodeId, u64, Result<Vec<ShardHit>, ApiError>)> = thread::scope(|scope| {
+            self.shards
+                .iter()
+                .map(|node| {
+                    scope.spawn(move || {
+                        let start = Instant::now();
+                        let result = self.client.query_shard(node, query, top_k);
+                        (node.clone(), start.elapsed().as_millis() as u64, result)
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().expect("shard query thread panicked"))
+                .collect()
+        });
+
+        let mut hits = Vec::new();
+        let mut shard_timings = Vec::with_capacity(results.len());
+        for (node, elapsed_ms, result) in results {
+            let shard_hits = result?;
+            shard_timings.push(ShardTiming { node, elapsed_ms, hit_count: shard_hits.len() });
+            hits.extend(shard_hits);
+        }
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+        Ok(ScatterGatherResult { hits, shard_timings })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct StubShardQuery {
+        responses: HashMap<String, Vec<ShardHit>>,
+    }
+
+    impl ShardQuery for StubShardQuery {
+        fn query_shard(&self, node: &NodeId, _query: &[f32], top_k: usize) -> Result<Vec<ShardHit>, ApiError> {
+            let mut hits = self.responses.get(&node.0).cloned().unwrap_or_default();
+            hits.truncate(top_k);
+            Ok(hits)
+        }
+    }
+
+    fn node(id: &str) -> NodeId {
+        NodeId::new(id)
+    }
+
+    #[test]
+    fn merges_per_shard_top_k_into_one_global_ranking_by_score() {
+        let client = StubShardQuery {
+            responses: HashMap::from([
+                ("a".to_string(), vec![ShardHit { id: "x".to_string(), score: 0.9 }]),
+                ("b".to_string(), vec![ShardHit { id: "y".to_string(), score: 0.95 }]),
+            ]),
+        };
+        let shards = vec![node("a"), node("b")];
+        let coordinator = QueryCoordinator::new(&shards, &client);
+
+        let result = coordinator.scatter_gather(&[1.0, 0.0], 10).unwrap();
+        assert_eq!(result.hits, vec![
+            ShardHit { id: "y".to_string(), score: 0.95 },
+            ShardHit { id: "x".to_string(), score: 0.9 },
+        ]);
+        assert_eq!(result.shard_timings.len(), 2);
+    }
+
+    #[test]
+    fn truncates_the_merged_result_to_top_k() {
+        let client = StubShardQuery {
+            responses: HashMap::from([(
+                "a".to_string(),
+                vec![
+                    ShardHit { id: "x".to_string(), score: 0.9 },
+                    ShardHit { id: "y".to_string(), score: 0.5 },
+                ],
+            )]),
+        };
+        let shards = vec![node("a")];
+        let coordinator = QueryCoordinator::new(&shards, &client);
+
+        let result = coordinator.scatter_gather(&[1.0, 0.0], 1).unwrap();
+        assert_eq!(result.hits, vec![ShardHit { id: "x".to_string(), score: 0.9 }]);
+    }
+
+    #[test]
+    fn a_failing_shard_fails_the_whole_query() {
+        struct FailingShardQuery;
+        impl ShardQuery for FailingShardQuery {
+            fn query_shard(&self, _node: &NodeId, _query: &[f32], _top_k: usize) -> Result<Vec<ShardHit>, ApiError> {
+                Err(ApiError::Internal("shard unreachable".to_string()))
+            }
+        }
+
+        let shards = vec![node("a")];
+        let coordinator = QueryCoordinator::new(&shards, &FailingShardQuery);
+        assert!(coordinator.scatter_gather(&[1.0, 0.0], 10).is_err());
+    }
+
+    #[test]
+    fn records_a_timing_entry_per_shard_even_with_no_hits() {
+        let client = StubShardQuery { responses: HashMap::new() };
+        let shards = vec![node("a"), node("b"), node("c")];
+        let coordinator = QueryCoordinator::new(&shards, &client);
+
+        let result = coordinator.scatter_gather(&[1.0, 0.0], 10).unwrap();
+        assert!(result.hits.is_empty());
+        assert_eq!(result.shard_timings.len(), 3);
+        assert!(result.shard_timings.iter().all(|t| t.hit_count == 0));
+    }
+}
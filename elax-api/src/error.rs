@@ -0,0 +1,110 @@
+use serde_json::json;
+use thiserror::Error;
+
+use crate::http::Response;
+
+/// Structured API errors, each carrying its own HTTP status and a stable
+/// machine-readable code — replaces mapping every failure straight to 500.
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("namespace not found: {0}")]
+    NamespaceNotFound(String),
+
+    #[error("dimension mismatch: {0}")]
+    DimensionMismatch(String),
+
+    #[error("consistency violation: {0}")]
+    ConsistencyViolation(String),
+
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::NamespaceNotFound(_) => 404,
+            ApiError::DimensionMismatch(_) => 400,
+            ApiError::ConsistencyViolation(_) => 409,
+            ApiError::Unauthorized(_) => 401,
+            ApiError::Forbidden(_) => 403,
+            ApiError::Internal(_) => 500,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NamespaceNotFound(_) => "namespace_not_found",
+            ApiError::DimensionMismatch(_) => "dimension_mismatch",
+            ApiError::ConsistencyViolation(_) => "consistency_violation",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    pub fn into_response(self) -> Response {
+        Response::json(
+            self.status(),
+            json!({"code": self.code(), "message": self.to_string()}),
+        )
+    }
+}
+
+/// Anything not otherwise classified surfaces as a 500 with an internal
+/// error code, same as before, but now explicitly rather than by default.
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        ApiError::Internal(err.to_string())
+    }
+}
+
+/// Maps [`elax_core::CoreError`] onto the handful of `ApiError` variants a
+/// caller actually needs to distinguish; a read-your-writes
+/// [`elax_core::CoreError::StaleRead`] or
+/// [`elax_core::CoreError::InvalidConsistencyToken`] surfaces as a
+/// `ConsistencyViolation` the same way a stale epoch already did.
+impl From<elax_core::CoreError> for ApiError {
+    fn from(err: elax_core::CoreError) -> Self {
+        match err {
+            elax_core::CoreError::NamespaceNotFound(namespace) => ApiError::NamespaceNotFound(namespace),
+            elax_core::CoreError::DimensionMismatch { expected, found } => {
+                ApiError::DimensionMismatch(format!("expected {expected}, got {found}"))
+            }
+            elax_core::CoreError::StaleRead { .. } | elax_core::CoreError::InvalidConsistencyToken(_) => {
+                ApiError::ConsistencyViolation(err.to_string())
+            }
+            other => ApiError::Internal(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_codes_match_error_kind() {
+        assert_eq!(ApiError::NamespaceNotFound("docs".into()).status(), 404);
+        assert_eq!(ApiError::DimensionMismatch("768 != 1536".into()).status(), 400);
+        assert_eq!(ApiError::ConsistencyViolation("stale epoch".into()).status(), 409);
+    }
+
+    #[test]
+    fn a_stale_read_core_error_becomes_a_consistency_violation() {
+        let core_err = elax_core::CoreError::StaleRead {
+            namespace: "docs".to_string(),
+            needed: 3,
+            have: 1,
+        };
+        let api_err: ApiError = core_err.into();
+        assert_eq!(api_err.status(), 409);
+        assert_eq!(api_err.code(), "consistency_violation");
+    }
+}
@@ -0,0 +1,154 @@
+//! Response format negotiation for bulk endpoints (query, export): callers
+//! ask for Arrow IPC via `?format=arrow` or an
+//! `Accept: application/vnd.apache.arrow.stream` header, falling back to
+//! JSON otherwise.
+
+use std::collections::HashMap;
+
+/// The wire format a query/export response should be encoded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Arrow,
+    /// Ids and vectors packed into one base64-binary columnar section (see
+    /// [`encode_columnar_binary`]) instead of a JSON array per hit —
+    /// cheaper to parse and far smaller on the wire for high-dimensional
+    /// vectors.
+    BinaryColumnar,
+}
+
+const ARROW_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+const BINARY_CONTENT_TYPE: &str = "application/vnd.elax.binary+json";
+
+/// Negotiate the response format for a request. An explicit `format_param`
+/// (the `?format=` query value, if the caller parsed one out) takes
+/// precedence over the `Accept` header, which in turn takes precedence
+/// over the JSON default.
+pub fn negotiate(headers: &HashMap<String, String>, format_param: Option<&str>) -> ResponseFormat {
+    if format_param == Some("arrow") {
+        return ResponseFormat::Arrow;
+    }
+    if format_param == Some("binary") {
+        return ResponseFormat::BinaryColumnar;
+    }
+    match headers.get("accept") {
+        Some(accept) if accept.contains(ARROW_CONTENT_TYPE) => ResponseFormat::Arrow,
+        Some(accept) if accept.contains(BINARY_CONTENT_TYPE) => ResponseFormat::BinaryColumnar,
+        _ => ResponseFormat::Json,
+    }
+}
+
+/// Pack `ids` and their matching `vectors` into the columnar shape
+/// [`ResponseFormat::BinaryColumnar`] responses use: ids newline-joined and
+/// base64-encoded, vectors packed as concatenated little-endian f32 bytes
+/// and base64-encoded alongside the shared `dim` a client needs to split
+/// them back into rows. Scores stay a plain JSON array — one f32 per hit
+/// is cheap already, and leaving them readable makes responses easier to
+/// eyeball while debugging.
+pub fn encode_columnar_binary(ids: &[String], vectors: &[Vec<f32>]) -> serde_json::Value {
+    let dim = vectors.first().map(|vector| vector.len()).unwrap_or(0);
+    let mut vector_bytes = Vec::with_capacity(vectors.len() * dim * 4);
+    for vector in vectors {
+        for component in vector {
+            vector_bytes.extend_from_slice(&component.to_le_bytes());
+        }
+    }
+    serde_json::json!({
+        "dim": dim,
+        "count": ids.len(),
+        "ids_b64": base64_encode(ids.join("\n").as_bytes()),
+        "vectors_b64": base64_encode(&vector_bytes),
+    })
+}
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(BASE64_ALPHABET[(n >> 18 & 0x3f) as usize] as char);
+        out.push(BASE64_ALPHABET[(n >> 12 & 0x3f) as usize] as char);
+        out.push(if chunk.len() > 1 { BASE64_ALPHABET[(n >> 6 & 0x3f) as usize] as char } else { '=' });
+        out.push(if chunk.len() > 2 { BASE64_ALPHABET[(n & 0x3f) as usize] as char } else { '=' });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(accept: &str) -> HashMap<String, String> {
+        let mut h = HashMap::new();
+        h.insert("accept".to_string(), accept.to_string());
+        h
+    }
+
+    #[test]
+    fn defaults_to_json() {
+        assert_eq!(negotiate(&HashMap::new(), None), ResponseFormat::Json);
+    }
+
+    #[test]
+    fn format_query_param_wins_over_accept_header() {
+        assert_eq!(
+            negotiate(&headers("application/json"), Some("arrow")),
+            ResponseFormat::Arrow
+        );
+    }
+
+    #[test]
+    fn arrow_accept_header_is_recognized() {
+        assert_eq!(negotiate(&headers(ARROW_CONTENT_TYPE), None), ResponseFormat::Arrow);
+    }
+
+    #[test]
+    fn binary_format_param_and_accept_header_are_recognized() {
+        assert_eq!(negotiate(&HashMap::new(), Some("binary")), ResponseFormat::BinaryColumnar);
+        assert_eq!(negotiate(&headers(BINARY_CONTENT_TYPE), None), ResponseFormat::BinaryColumnar);
+    }
+
+    #[test]
+    fn columnar_binary_round_trips_ids_and_vectors() {
+        let ids = vec!["a".to_string(), "b".to_string()];
+        let vectors = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        let section = encode_columnar_binary(&ids, &vectors);
+
+        assert_eq!(section["dim"], 2);
+        assert_eq!(section["count"], 2);
+
+        let ids_bytes = base64_decode(section["ids_b64"].as_str().unwrap());
+        assert_eq!(String::from_utf8(ids_bytes).unwrap(), "a\nb");
+
+        let vector_bytes = base64_decode(section["vectors_b64"].as_str().unwrap());
+        let decoded: Vec<f32> = vector_bytes
+            .chunks(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(decoded, vec![1.0, 2.0, 3.0, 4.0]);
+    }
+
+    fn base64_decode(s: &str) -> Vec<u8> {
+        let decode_char = |c: u8| BASE64_ALPHABET.iter().position(|&a| a == c).map(|p| p as u32);
+        let mut out = Vec::new();
+        for chunk in s.as_bytes().chunks(4) {
+            let vals: Vec<u32> = chunk
+                .iter()
+                .filter(|&&c| c != b'=')
+                .map(|&c| decode_char(c).unwrap())
+                .collect();
+            let n = vals.iter().enumerate().fold(0u32, |acc, (i, v)| acc | (v << (18 - 6 * i)));
+            out.push((n >> 16) as u8);
+            if vals.len() > 2 {
+                out.push((n >> 8) as u8);
+            }
+            if vals.len() > 3 {
+                out.push(n as u8);
+            }
+        }
+        out
+    }
+}
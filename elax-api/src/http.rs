@@ -0,0 +1,69 @@
+//! Minimal in-process HTTP request/response model. Handlers are plain
+//! functions so the router can be exercised in tests without a real socket;
+//! `elacsym-server` (the eventual binary) adapts these to actual
+//! connections.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Method {
+    #[default]
+    Get,
+    Post,
+    Delete,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Request {
+    pub method: Method,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub body: serde_json::Value,
+}
+
+impl Response {
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self { status, body }
+    }
+}
+
+type Handler = Box<dyn Fn(&Request) -> Response + Send + Sync>;
+
+/// Maps exact `(method, path)` pairs to handlers. Path parameters are not
+/// supported yet — callers register one route per concrete path.
+#[derive(Default)]
+pub struct Router {
+    routes: HashMap<(Method, String), Handler>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(
+        mut self,
+        method: Method,
+        path: impl Into<String>,
+        handler: impl Fn(&Request) -> Response + Send + Sync + 'static,
+    ) -> Self {
+        self.routes.insert((method, path.into()), Box::new(handler));
+        self
+    }
+
+    pub fn dispatch(&self, request: &Request) -> Response {
+        match self.routes.get(&(request.method, request.path.clone())) {
+            Some(handler) => handler(request),
+            None => Response::json(
+                404,
+                serde_json::json!({"code": "not_found", "message": "no such route"}),
+            ),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! HTTP-facing API server for elacsym.
+
+pub mod access_log;
+pub mod auth;
+pub mod cluster_router;
+pub mod compression;
+pub mod coordinator;
+pub mod error;
+pub mod format;
+pub mod http;
+pub mod metrics_export;
+pub mod openapi;
+pub mod server;
+pub mod tenant_path;
+
+pub use access_log::{AccessLog, AccessLogEntry, ApiKeyUsage};
+pub use auth::{AuthConfig, AuthContext};
+pub use cluster_router::{ClusterRouter, RouteDecision};
+pub use compression::{negotiate_encoding, ContentEncoding};
+pub use coordinator::{QueryCoordinator, ScatterGatherResult, ShardHit, ShardQuery, ShardTiming};
+pub use error::ApiError;
+pub use format::{negotiate as negotiate_response_format, encode_columnar_binary, ResponseFormat};
+pub use metrics_export::{MetricsCardinalityPolicy, NamespaceGaugeSnapshot, RouteMetrics};
+pub use server::{ApiServer, AppConfig};
+pub use tenant_path::{parse as parse_tenant_namespace_path, TenantNamespacePath};
@@ -0,0 +1,327 @@
+//! Prometheus-style process metrics: build info, per-route RED (rate,
+//! errors, duration) counters, and per-namespace gauges. [`http::Response`]
+//! bodies are always JSON in this crate (see [`crate::compression`]'s note
+//! on why), so [`render`] returns the exposition text as a JSON string —
+//! the eventual socket-adapting binary is expected to special-case the
+//! `/metrics` route and write that string out verbatim with a
+//! `text/plain; version=0.0.4` content type instead of JSON-encoding it.
+//!
+//! [`http::Response`]: crate::http::Response
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use elax_core::metrics::Histogram;
+
+/// Latency buckets for [`RouteMetrics`], tuned for the range an in-process
+/// route handler actually spans: sub-millisecond cache hits up to
+/// multi-second scatter-gather queries.
+fn route_latency_buckets() -> Vec<f64> {
+    vec![
+        0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, f64::INFINITY,
+    ]
+}
+
+/// Escape `value` for use inside a Prometheus exposition label (the
+/// `label="value"` part of a metric line), per the text format spec:
+/// backslash and quote are backslash-escaped, and newlines become `\n`, in
+/// that order so an escaped backslash doesn't get re-escaped by the later
+/// passes. Without this, a caller-chosen route or namespace name containing
+/// `"` or `\n` could inject forged label/metric lines into the scrape.
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Bounds how many distinct namespace labels [`render_namespace_gauges`]
+/// will emit before folding the rest into a shared `"__overflow__"` label,
+/// so a deployment with thousands of namespaces can't blow up a scrape's
+/// cardinality. Configurable per deployment via [`crate::server::AppConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsCardinalityPolicy {
+    pub max_namespace_labels: usize,
+}
+
+impl Default for MetricsCardinalityPolicy {
+    fn default() -> Self {
+        Self { max_namespace_labels: 200 }
+    }
+}
+
+/// One namespace's point-in-time counts, supplied by whatever owns the
+/// actual store and indexer state — this crate doesn't depend on
+/// `elax-store` or `elax-core::registry` for its own data, the same reason
+/// [`crate::server::ReadinessCheck`] is a caller-supplied hook rather than
+/// a direct dependency.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NamespaceGaugeSnapshot {
+    pub row_count: u64,
+    pub part_count: u64,
+    /// Highest WAL offset durably written so far.
+    pub wal_highwater: u64,
+    /// Highest WAL offset the indexer has folded into a part.
+    pub wal_indexed: u64,
+}
+
+impl NamespaceGaugeSnapshot {
+    /// How far the indexer is behind the WAL tail, in records. Saturates
+    /// at zero rather than underflowing if a caller passes a stale
+    /// `wal_indexed` that's briefly ahead of `wal_highwater`.
+    pub fn wal_lag(&self) -> u64 {
+        self.wal_highwater.saturating_sub(self.wal_indexed)
+    }
+}
+
+/// Per-route RED (rate, errors, duration) accounting, scraped into a
+/// `/metrics` response alongside build info and namespace gauges.
+#[derive(Default)]
+pub struct RouteMetrics {
+    by_route: Mutex<HashMap<String, RouteStats>>,
+}
+
+struct RouteStats {
+    requests: u64,
+    errors: u64,
+    latency: Histogram,
+}
+
+impl Default for RouteStats {
+    fn default() -> Self {
+        Self { requests: 0, errors: 0, latency: Histogram::new(route_latency_buckets()) }
+    }
+}
+
+impl RouteMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one completed request. `status >= 400` counts as an error,
+    /// matching the HTTP convention the rest of `elax-api` already follows
+    /// (see [`crate::error::ApiError`]'s status codes).
+    pub fn record(&self, route: &str, status: u16, duration: Duration) {
+        let mut guard = self.by_route.lock().unwrap();
+        let stats = guard.entry(route.to_string()).or_default();
+        stats.requests += 1;
+        if status >= 400 {
+            stats.errors += 1;
+        }
+        stats.latency.observe(duration.as_secs_f64());
+    }
+
+    fn render(&self, out: &mut String) {
+        let guard = self.by_route.lock().unwrap();
+        let mut routes: Vec<&String> = guard.keys().collect();
+        routes.sort();
+
+        writeln!(out, "# HELP elax_http_requests_total Total HTTP requests handled, by route.").unwrap();
+        writeln!(out, "# TYPE elax_http_requests_total counter").unwrap();
+        for route in &routes {
+            let stats = &guard[*route];
+            let route = escape_label_value(route);
+            writeln!(out, "elax_http_requests_total{{route=\"{route}\"}} {}", stats.requests).unwrap();
+        }
+
+        writeln!(out, "# HELP elax_http_errors_total Total HTTP requests with a 4xx/5xx status, by route.").unwrap();
+        writeln!(out, "# TYPE elax_http_errors_total counter").unwrap();
+        for route in &routes {
+            let stats = &guard[*route];
+            let route = escape_label_value(route);
+            writeln!(out, "elax_http_errors_total{{route=\"{route}\"}} {}", stats.errors).unwrap();
+        }
+
+        writeln!(out, "# HELP elax_http_request_duration_seconds Request latency in seconds, by route.").unwrap();
+        writeln!(out, "# TYPE elax_http_request_duration_seconds histogram").unwrap();
+        for route in &routes {
+            let stats = &guard[*route];
+            let route = escape_label_value(route);
+            for (bound, count) in stats.latency.snapshot() {
+                let le = if bound.is_infinite() { "+Inf".to_string() } else { bound.to_string() };
+                writeln!(out, "elax_http_request_duration_seconds_bucket{{route=\"{route}\",le=\"{le}\"}} {count}")
+                    .unwrap();
+            }
+            writeln!(out, "elax_http_request_duration_seconds_count{{route=\"{route}\"}} {}", stats.requests)
+                .unwrap();
+        }
+    }
+}
+
+/// Namespace label under which namespaces past
+/// [`MetricsCardinalityPolicy::max_namespace_labels`] get aggregated.
+const OVERFLOW_LABEL: &str = "__overflow__";
+
+/// Cap `snapshots` at `policy.max_namespace_labels` distinct labels,
+/// summing everything past the cap into one [`OVERFLOW_LABEL`] entry so
+/// the totals a scrape reports still add up.
+fn apply_cardinality_policy(
+    snapshots: &[(String, NamespaceGaugeSnapshot)],
+    policy: MetricsCardinalityPolicy,
+) -> Vec<(String, NamespaceGaugeSnapshot)> {
+    if snapshots.len() <= policy.max_namespace_labels {
+        return snapshots.to_vec();
+    }
+    let mut kept: Vec<_> = snapshots.iter().take(policy.max_namespace_labels).cloned().collect();
+    let mut overflow = NamespaceGaugeSnapshot::default();
+    for (_, snapshot) in snapshots.iter().skip(policy.max_namespace_labels) {
+        overflow.row_count += snapshot.row_count;
+        overflow.part_count += snapshot.part_count;
+        overflow.wal_highwater += snapshot.wal_highwater;
+        overflow.wal_indexed += snapshot.wal_indexed;
+    }
+    kept.push((OVERFLOW_LABEL.to_string(), overflow));
+    kept
+}
+
+type GaugeField = (&'static str, &'static str, fn(&NamespaceGaugeSnapshot) -> u64);
+
+fn render_namespace_gauges(out: &mut String, snapshots: &[(String, NamespaceGaugeSnapshot)]) {
+    let gauges: &[GaugeField] = &[
+        ("elax_namespace_rows", "Row count, by namespace.", |s| s.row_count),
+        ("elax_namespace_parts", "On-disk part count, by namespace.", |s| s.part_count),
+        ("elax_namespace_wal_highwater", "Highest durably-written WAL offset, by namespace.", |s| {
+            s.wal_highwater
+        }),
+        ("elax_namespace_wal_indexed", "Highest WAL offset folded into a part, by namespace.", |s| {
+            s.wal_indexed
+        }),
+        ("elax_namespace_wal_lag", "WAL records not yet folded into a part (highwater - indexed), by namespace.", |s| {
+            s.wal_lag()
+        }),
+    ];
+    for (name, help, value_of) in gauges {
+        writeln!(out, "# HELP {name} {help}").unwrap();
+        writeln!(out, "# TYPE {name} gauge").unwrap();
+        for (namespace, snapshot) in snapshots {
+            let namespace = escape_label_value(namespace);
+            writeln!(out, "{name}{{namespace=\"{namespace}\"}} {}", value_of(snapshot)).unwrap();
+        }
+    }
+}
+
+fn render_build_info(out: &mut String) {
+    writeln!(out, "# HELP elax_build_info Build metadata; the sample's value is always 1.").unwrap();
+    writeln!(out, "# TYPE elax_build_info gauge").unwrap();
+    writeln!(out, "elax_build_info{{version=\"{}\"}} 1", env!("CARGO_PKG_VERSION")).unwrap();
+}
+
+/// Render the full `/metrics` exposition text: build info, per-route RED
+/// metrics, and per-namespace gauges (cardinality-capped per `policy`).
+/// `namespaces` is `None` when the binary wiring this server up hasn't
+/// supplied a [`crate::server::ApiServer::with_namespace_gauges`] hook, in
+/// which case the namespace gauge families are omitted rather than emitted
+/// empty.
+pub fn render(
+    route_metrics: &RouteMetrics,
+    namespaces: Option<&[(String, NamespaceGaugeSnapshot)]>,
+    policy: MetricsCardinalityPolicy,
+) -> String {
+    let mut out = String::new();
+    render_build_info(&mut out);
+    route_metrics.render(&mut out);
+    if let Some(snapshots) = namespaces {
+        render_namespace_gauges(&mut out, &apply_cardinality_policy(snapshots, policy));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_info_reports_the_crate_version() {
+        let mut out = String::new();
+        render_build_info(&mut out);
+        assert!(out.contains(&format!("version=\"{}\"", env!("CARGO_PKG_VERSION"))));
+    }
+
+    #[test]
+    fn route_metrics_tracks_requests_errors_and_latency_per_route() {
+        let metrics = RouteMetrics::new();
+        metrics.record("/v2/query", 200, Duration::from_millis(2));
+        metrics.record("/v2/query", 500, Duration::from_millis(4));
+        metrics.record("/healthz", 200, Duration::from_micros(100));
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+        assert!(out.contains("elax_http_requests_total{route=\"/v2/query\"} 2"));
+        assert!(out.contains("elax_http_errors_total{route=\"/v2/query\"} 1"));
+        assert!(out.contains("elax_http_requests_total{route=\"/healthz\"} 1"));
+        assert!(out.contains("elax_http_errors_total{route=\"/healthz\"} 0"));
+    }
+
+    #[test]
+    fn wal_lag_saturates_instead_of_underflowing() {
+        let snapshot = NamespaceGaugeSnapshot { wal_highwater: 5, wal_indexed: 10, ..Default::default() };
+        assert_eq!(snapshot.wal_lag(), 0);
+    }
+
+    #[test]
+    fn namespaces_within_the_cap_all_get_their_own_label() {
+        let snapshots = vec![
+            ("a".to_string(), NamespaceGaugeSnapshot { row_count: 1, ..Default::default() }),
+            ("b".to_string(), NamespaceGaugeSnapshot { row_count: 2, ..Default::default() }),
+        ];
+        let policy = MetricsCardinalityPolicy { max_namespace_labels: 10 };
+        let capped = apply_cardinality_policy(&snapshots, policy);
+        assert_eq!(capped.len(), 2);
+    }
+
+    #[test]
+    fn namespaces_past_the_cap_fold_into_one_overflow_label() {
+        let snapshots = vec![
+            ("a".to_string(), NamespaceGaugeSnapshot { row_count: 1, ..Default::default() }),
+            ("b".to_string(), NamespaceGaugeSnapshot { row_count: 2, ..Default::default() }),
+            ("c".to_string(), NamespaceGaugeSnapshot { row_count: 3, ..Default::default() }),
+        ];
+        let policy = MetricsCardinalityPolicy { max_namespace_labels: 1 };
+        let capped = apply_cardinality_policy(&snapshots, policy);
+        assert_eq!(capped.len(), 2);
+        let overflow = capped.iter().find(|(name, _)| name == OVERFLOW_LABEL).unwrap();
+        assert_eq!(overflow.1.row_count, 5);
+    }
+
+    #[test]
+    fn render_omits_namespace_gauges_when_no_hook_is_wired_up() {
+        let metrics = RouteMetrics::new();
+        let out = render(&metrics, None, MetricsCardinalityPolicy::default());
+        assert!(!out.contains("elax_namespace_rows"));
+    }
+
+    #[test]
+    fn render_includes_namespace_gauges_when_a_snapshot_is_supplied() {
+        let metrics = RouteMetrics::new();
+        let snapshots = vec![("docs".to_string(), NamespaceGaugeSnapshot { row_count: 42, ..Default::default() })];
+        let out = render(&metrics, Some(&snapshots), MetricsCardinalityPolicy::default());
+        assert!(out.contains("elax_namespace_rows{namespace=\"docs\"} 42"));
+    }
+
+    #[test]
+    fn label_values_escape_backslash_quote_and_newline() {
+        assert_eq!(escape_label_value(r#"x"} 1\nnew_metric{y="z"#), r#"x\"} 1\\nnew_metric{y=\"z"#);
+        assert_eq!(escape_label_value("line1\nline2"), "line1\\nline2");
+        assert_eq!(escape_label_value(r"back\slash"), r"back\\slash");
+    }
+
+    #[test]
+    fn a_namespace_with_an_embedded_quote_cannot_forge_an_extra_metric_line() {
+        let metrics = RouteMetrics::new();
+        let snapshots =
+            vec![(r#"x"} 999
+elax_namespace_rows{namespace="y"#
+                .to_string(), NamespaceGaugeSnapshot { row_count: 1, ..Default::default() })];
+        let out = render(&metrics, Some(&snapshots), MetricsCardinalityPolicy::default());
+        assert!(!out.contains("elax_namespace_rows{namespace=\"y\"} 1"));
+        assert!(out.contains(r#"elax_namespace_rows{namespace="x\"} 999\nelax_namespace_rows{namespace=\"y"} 1"#));
+    }
+
+    #[test]
+    fn a_route_with_an_embedded_quote_is_escaped_in_every_family() {
+        let metrics = RouteMetrics::new();
+        metrics.record("/v2/\"weird\"", 200, Duration::from_millis(1));
+        let out = render(&metrics, None, MetricsCardinalityPolicy::default());
+        assert!(out.contains(r#"route="/v2/\"weird\""#));
+        assert!(!out.contains("route=\"/v2/\"weird\"\""));
+    }
+}
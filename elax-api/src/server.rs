@@ -0,0 +1,448 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::access_log::{AccessLog, AccessLogEntry};
+use crate::auth::{authenticate, AuthConfig, AuthContext, AuthMetrics, Scope};
+use crate::error::ApiError;
+use crate::http::{Method, Request, Response, Router};
+use crate::metrics_export::{self, MetricsCardinalityPolicy, NamespaceGaugeSnapshot, RouteMetrics};
+use crate::openapi::openapi_json;
+use crate::tenant_path;
+
+/// Static server configuration, loaded once at startup.
+#[derive(Debug, Clone, Default)]
+pub struct AppConfig {
+    pub bind_addr: String,
+    pub auth: AuthConfig,
+    pub metrics_cardinality: MetricsCardinalityPolicy,
+}
+
+/// Called by `/readyz` to decide whether the server should take traffic.
+/// Defaults to always-ready; the binary wiring this server up to an actual
+/// store and namespace set should override it to check store accessibility
+/// and namespace warm-up, since `elax-api` itself doesn't depend on
+/// `elax-store`.
+pub type ReadinessCheck = Arc<dyn Fn() -> bool + Send + Sync>;
+
+/// Called by `/metrics` to gather per-namespace gauges. `None` (the
+/// default) omits the namespace gauge families from the scrape entirely,
+/// for the same reason [`ReadinessCheck`] defaults to always-ready:
+/// `elax-api` doesn't depend on `elax-store`, so it has no namespace set
+/// of its own to report on until the binary wiring it up supplies one.
+pub type NamespaceGaugesFn = Arc<dyn Fn() -> Vec<(String, NamespaceGaugeSnapshot)> + Send + Sync>;
+
+/// Owns the route table and dispatches requests into it.
+pub struct ApiServer {
+    pub config: AppConfig,
+    pub auth_metrics: AuthMetrics,
+    pub access_log: AccessLog,
+    pub route_metrics: Arc<RouteMetrics>,
+    ready: ReadinessCheck,
+    namespace_gauges: Option<NamespaceGaugesFn>,
+    shutting_down: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+/// Routes that don't require authentication (the self-describing API
+/// surface itself, plus the health/readiness probes load balancers poll
+/// before a session is even authenticated).
+const PUBLIC_ROUTES: &[&str] = &["/v2/openapi.json", "/healthz", "/readyz", "/metrics"];
+
+/// The [`Scope`] a request needs, derived from its HTTP method: `GET` reads,
+/// `POST` writes, `DELETE` administers (drops namespaces, rotates keys,
+/// etc). One variant of [`Method`] per variant of [`Scope`], so this is
+/// total rather than falling back to some default.
+fn required_scope(method: Method) -> Scope {
+    match method {
+        Method::Get => Scope::Read,
+        Method::Post => Scope::Write,
+        Method::Delete => Scope::Admin,
+    }
+}
+
+/// Enforce `ctx`'s scopes and namespace allowlist against `request`, the
+/// check [`authenticate`]'s doc comment promises but that nothing used to
+/// actually perform. Only tenant-scoped routes
+/// (`/v2/tenants/:tenant/namespaces/:namespace/...`, per
+/// [`tenant_path::parse`]) have a scope or namespace to check against;
+/// anything else that reaches here (an authenticated request to an unknown
+/// route, say) is left to the router to 404.
+fn authorize(ctx: &AuthContext, request: &Request) -> Result<(), ApiError> {
+    let Some(parsed) = tenant_path::parse(&request.path) else {
+        return Ok(());
+    };
+    let scope = required_scope(request.method);
+    if !ctx.has_scope(scope) {
+        return Err(ApiError::Forbidden(format!("missing required scope: {scope:?}")));
+    }
+    if !ctx.can_access(&parsed.namespace) {
+        return Err(ApiError::Forbidden(format!(
+            "namespace {:?} is not in this credential's allowlist",
+            parsed.namespace
+        )));
+    }
+    Ok(())
+}
+
+impl ApiServer {
+    pub fn new(config: AppConfig) -> Self {
+        Self {
+            config,
+            auth_metrics: AuthMetrics::default(),
+            access_log: AccessLog::new(),
+            route_metrics: Arc::new(RouteMetrics::new()),
+            ready: Arc::new(|| true),
+            namespace_gauges: None,
+            shutting_down: AtomicBool::new(false),
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// Override the `/readyz` check (e.g. to verify store accessibility and
+    /// namespace warm-up).
+    pub fn with_readiness_check(mut self, check: ReadinessCheck) -> Self {
+        self.ready = check;
+        self
+    }
+
+    /// Supply the hook `/metrics` uses to report per-namespace gauges (row
+    /// counts, part counts, WAL lag). Unset by default — see
+    /// [`NamespaceGaugesFn`].
+    pub fn with_namespace_gauges(mut self, gauges: NamespaceGaugesFn) -> Self {
+        self.namespace_gauges = Some(gauges);
+        self
+    }
+
+    /// Build the route table. Kept as a method (rather than a free
+    /// function) so later layers — quotas, tenancy — can wrap it with
+    /// `server.router().route(...)`-style composition.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(Method::Get, "/v2/openapi.json", |_req: &Request| {
+                Response::json(200, openapi_json())
+            })
+            .route(Method::Get, "/healthz", |_req: &Request| {
+                Response::json(200, serde_json::json!({"status": "ok"}))
+            })
+            .route(Method::Get, "/readyz", {
+                let ready = self.ready.clone();
+                move |_req: &Request| {
+                    if ready() {
+                        Response::json(200, serde_json::json!({"status": "ready"}))
+                    } else {
+                        Response::json(503, serde_json::json!({"status": "not_ready"}))
+                    }
+                }
+            })
+            .route(Method::Get, "/metrics", {
+                let route_metrics = self.route_metrics.clone();
+                let namespace_gauges = self.namespace_gauges.clone();
+                let cardinality = self.config.metrics_cardinality;
+                move |_req: &Request| {
+                    let namespaces = namespace_gauges.as_ref().map(|gauges| gauges());
+                    let body = metrics_export::render(&route_metrics, namespaces.as_deref(), cardinality);
+                    Response::json(200, serde_json::Value::String(body))
+                }
+            })
+    }
+
+    pub fn handle(&self, request: &Request) -> Response {
+        if self.shutting_down.load(Ordering::SeqCst) && request.path != "/healthz" {
+            return Response::json(
+                503,
+                serde_json::json!({"code": "shutting_down", "message": "server is draining"}),
+            );
+        }
+
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let response = self.handle_inner(request);
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        response
+    }
+
+    fn handle_inner(&self, request: &Request) -> Response {
+        let start = Instant::now();
+        let mut api_key = None;
+        if !PUBLIC_ROUTES.contains(&request.path.as_str()) {
+            let result = authenticate(&self.config.auth, &request.headers);
+            self.auth_metrics.record(&result);
+            match result {
+                Ok(ctx) => {
+                    if let Err(err) = authorize(&ctx, request) {
+                        return err.into_response();
+                    }
+                    api_key = ctx.api_key;
+                }
+                Err(err) => return err.into_response(),
+            }
+        }
+        let response = self.router().dispatch(request);
+        let elapsed = start.elapsed();
+        self.route_metrics.record(&request.path, response.status, elapsed);
+        self.access_log.record(AccessLogEntry {
+            namespace: tenant_path::parse(&request.path).map(|p| p.namespace),
+            route: request.path.clone(),
+            api_key,
+            latency_micros: elapsed.as_micros() as u64,
+            bytes_returned: response.body.to_string().len(),
+            ..Default::default()
+        });
+        response
+    }
+
+    /// Stop accepting new non-health requests and block until any requests
+    /// already in flight finish (polling, since there's no async runtime
+    /// here to await on), up to `timeout`. Callers should flush any pending
+    /// WAL/indexer work themselves once this returns — that work lives
+    /// outside `elax-api` and has no handle here to drive it.
+    pub fn shutdown(&self, timeout: std::time::Duration) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        let deadline = std::time::Instant::now() + timeout;
+        while self.in_flight.load(Ordering::SeqCst) > 0 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn openapi_route_is_served() {
+        let server = ApiServer::new(AppConfig::default());
+        let response = server.handle(&Request {
+            method: Method::Get,
+            path: "/v2/openapi.json".into(),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 200);
+    }
+
+    #[test]
+    fn unknown_route_requires_auth_first() {
+        let server = ApiServer::new(AppConfig::default());
+        let response = server.handle(&Request {
+            method: Method::Get,
+            path: "/v2/nope".into(),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 401);
+    }
+
+    #[test]
+    fn healthz_and_default_readyz_are_public() {
+        let server = ApiServer::new(AppConfig::default());
+        let health = server.handle(&Request {
+            method: Method::Get,
+            path: "/healthz".into(),
+            ..Default::default()
+        });
+        assert_eq!(health.status, 200);
+
+        let ready = server.handle(&Request {
+            method: Method::Get,
+            path: "/readyz".into(),
+            ..Default::default()
+        });
+        assert_eq!(ready.status, 200);
+    }
+
+    #[test]
+    fn readyz_reflects_the_custom_readiness_check() {
+        let server = ApiServer::new(AppConfig::default()).with_readiness_check(Arc::new(|| false));
+        let response = server.handle(&Request {
+            method: Method::Get,
+            path: "/readyz".into(),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 503);
+    }
+
+    #[test]
+    fn shutdown_drains_requests_then_handle_returns_503() {
+        let server = ApiServer::new(AppConfig::default());
+        server.shutdown(std::time::Duration::from_millis(50));
+
+        let response = server.handle(&Request {
+            method: Method::Get,
+            path: "/v2/openapi.json".into(),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 503);
+
+        // Health checks still answer during drain so orchestrators can
+        // confirm the process is still alive while it shuts down.
+        let health = server.handle(&Request {
+            method: Method::Get,
+            path: "/healthz".into(),
+            ..Default::default()
+        });
+        assert_eq!(health.status, 200);
+    }
+
+    #[test]
+    fn every_request_is_recorded_to_the_access_log() {
+        let server = ApiServer::new(AppConfig::default());
+        server.handle(&Request {
+            method: Method::Get,
+            path: "/healthz".into(),
+            ..Default::default()
+        });
+        let entries = server.access_log.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].route, "/healthz");
+        assert_eq!(entries[0].api_key, None);
+    }
+
+    #[test]
+    fn an_authenticated_request_is_logged_under_its_namespace_and_api_key() {
+        let config = AppConfig {
+            auth: AuthConfig {
+                api_keys: vec![crate::auth::ApiKeyEntry {
+                    key: "secret".into(),
+                    scopes: vec![crate::auth::Scope::Read],
+                    namespace_allowlist: None,
+                }],
+                jwt_secret: None,
+            },
+            ..Default::default()
+        };
+        let server = ApiServer::new(config);
+        server.handle(&Request {
+            method: Method::Get,
+            path: "/v2/tenants/acme/namespaces/docs/query".into(),
+            headers: HashMap::from([("authorization".to_string(), "ApiKey secret".to_string())]),
+            ..Default::default()
+        });
+
+        let entries = server.access_log.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].namespace, Some("docs".to_string()));
+        assert_eq!(entries[0].api_key, Some("secret".to_string()));
+    }
+
+    #[test]
+    fn a_request_missing_the_scope_its_method_requires_is_forbidden() {
+        let config = AppConfig {
+            auth: AuthConfig {
+                api_keys: vec![crate::auth::ApiKeyEntry {
+                    key: "secret".into(),
+                    scopes: vec![crate::auth::Scope::Read],
+                    namespace_allowlist: None,
+                }],
+                jwt_secret: None,
+            },
+            ..Default::default()
+        };
+        let server = ApiServer::new(config);
+        // POST requires Scope::Write, but this key only has Scope::Read.
+        let response = server.handle(&Request {
+            method: Method::Post,
+            path: "/v2/tenants/acme/namespaces/docs/write".into(),
+            headers: HashMap::from([("authorization".to_string(), "ApiKey secret".to_string())]),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 403);
+    }
+
+    #[test]
+    fn a_request_outside_its_namespace_allowlist_is_forbidden() {
+        let config = AppConfig {
+            auth: AuthConfig {
+                api_keys: vec![crate::auth::ApiKeyEntry {
+                    key: "secret".into(),
+                    scopes: vec![crate::auth::Scope::Read],
+                    namespace_allowlist: Some(vec!["other".into()]),
+                }],
+                jwt_secret: None,
+            },
+            ..Default::default()
+        };
+        let server = ApiServer::new(config);
+        let response = server.handle(&Request {
+            method: Method::Get,
+            path: "/v2/tenants/acme/namespaces/docs/query".into(),
+            headers: HashMap::from([("authorization".to_string(), "ApiKey secret".to_string())]),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 403);
+    }
+
+    #[test]
+    fn a_request_with_sufficient_scope_and_allowlist_is_not_forbidden() {
+        let config = AppConfig {
+            auth: AuthConfig {
+                api_keys: vec![crate::auth::ApiKeyEntry {
+                    key: "secret".into(),
+                    scopes: vec![crate::auth::Scope::Read],
+                    namespace_allowlist: Some(vec!["docs".into()]),
+                }],
+                jwt_secret: None,
+            },
+            ..Default::default()
+        };
+        let server = ApiServer::new(config);
+        let response = server.handle(&Request {
+            method: Method::Get,
+            path: "/v2/tenants/acme/namespaces/docs/query".into(),
+            headers: HashMap::from([("authorization".to_string(), "ApiKey secret".to_string())]),
+            ..Default::default()
+        });
+        // Authorized, so falls through to the router, which 404s since no
+        // handler is registered for this tenant-scoped path — the point is
+        // it's not 403.
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn an_admin_scope_satisfies_any_required_scope() {
+        let config = AppConfig {
+            auth: AuthConfig {
+                api_keys: vec![crate::auth::ApiKeyEntry {
+                    key: "secret".into(),
+                    scopes: vec![crate::auth::Scope::Admin],
+                    namespace_allowlist: None,
+                }],
+                jwt_secret: None,
+            },
+            ..Default::default()
+        };
+        let server = ApiServer::new(config);
+        let response = server.handle(&Request {
+            method: Method::Delete,
+            path: "/v2/tenants/acme/namespaces/docs".into(),
+            headers: HashMap::from([("authorization".to_string(), "ApiKey secret".to_string())]),
+            ..Default::default()
+        });
+        assert_ne!(response.status, 403);
+    }
+
+    #[test]
+    fn non_tenant_scoped_authenticated_routes_skip_scope_checks() {
+        let config = AppConfig {
+            auth: AuthConfig {
+                api_keys: vec![crate::auth::ApiKeyEntry {
+                    key: "secret".into(),
+                    scopes: vec![],
+                    namespace_allowlist: None,
+                }],
+                jwt_secret: None,
+            },
+            ..Default::default()
+        };
+        let server = ApiServer::new(config);
+        // No scopes at all, but "/v2/nope" isn't a tenant-scoped path, so
+        // there's nothing for `authorize` to check; it 404s, not 403s.
+        let response = server.handle(&Request {
+            method: Method::Get,
+            path: "/v2/nope".into(),
+            headers: HashMap::from([("authorization".to_string(), "ApiKey secret".to_string())]),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 404);
+    }
+}
@@ -0,0 +1,86 @@
+//! Parses the `/v2/tenants/:tenant/namespaces/:namespace/...` route shape
+//! used to address a tenant/namespace pair (see `elax_store::LocalStore::tenant`)
+//! from an HTTP request path. [`crate::http::Router`] only matches exact
+//! paths today, so handlers that need tenant scoping call this first to
+//! pull the path params out of `request.path` themselves.
+
+/// The tenant, namespace and remaining path segments parsed out of a
+/// `/v2/tenants/:tenant/namespaces/:namespace/...` request path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantNamespacePath {
+    pub tenant: String,
+    pub namespace: String,
+    pub rest: String,
+}
+
+/// Whether `segment` is safe to use as a single tenant or namespace path
+/// component: non-empty, not `.`/`..`, and free of path separators. Rejects
+/// the same shapes `elax-store`'s own `LocalStore::tenant` validation does
+/// (this crate doesn't depend on `elax-store` to share the check directly),
+/// so a path like `/v2/tenants/../namespaces/x` is turned away here instead
+/// of reaching the store with `tenant == ".."`.
+fn is_valid_segment(segment: &str) -> bool {
+    !segment.is_empty() && segment != "." && segment != ".." && !segment.contains('/') && !segment.contains('\\')
+}
+
+/// Parse `path`, returning `None` if it doesn't match the
+/// `/v2/tenants/:tenant/namespaces/:namespace[/...]` shape, or if the
+/// tenant/namespace segments aren't plain path components (see
+/// [`is_valid_segment`]).
+pub fn parse(path: &str) -> Option<TenantNamespacePath> {
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    if segments.next()? != "v2" {
+        return None;
+    }
+    if segments.next()? != "tenants" {
+        return None;
+    }
+    let tenant = segments.next()?.to_string();
+    if !is_valid_segment(&tenant) {
+        return None;
+    }
+    if segments.next()? != "namespaces" {
+        return None;
+    }
+    let namespace = segments.next()?.to_string();
+    if !is_valid_segment(&namespace) {
+        return None;
+    }
+    let rest = segments.collect::<Vec<_>>().join("/");
+    Some(TenantNamespacePath { tenant, namespace, rest })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tenant_and_namespace_with_trailing_path() {
+        let parsed = parse("/v2/tenants/acme/namespaces/docs/query").unwrap();
+        assert_eq!(parsed.tenant, "acme");
+        assert_eq!(parsed.namespace, "docs");
+        assert_eq!(parsed.rest, "query");
+    }
+
+    #[test]
+    fn parses_bare_namespace_path() {
+        let parsed = parse("/v2/tenants/acme/namespaces/docs").unwrap();
+        assert_eq!(parsed.rest, "");
+    }
+
+    #[test]
+    fn rejects_unrelated_paths() {
+        assert!(parse("/v2/openapi.json").is_none());
+        assert!(parse("/v2/tenants/acme").is_none());
+    }
+
+    #[test]
+    fn rejects_a_traversing_tenant_segment() {
+        assert!(parse("/v2/tenants/../namespaces/x").is_none());
+    }
+
+    #[test]
+    fn rejects_a_traversing_namespace_segment() {
+        assert!(parse("/v2/tenants/acme/namespaces/..").is_none());
+    }
+}
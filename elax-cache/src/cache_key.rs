@@ -0,0 +1,55 @@
+use std::fmt;
+
+/// What kind of asset a [`CacheKey`] names, so hit-ratio metrics and future
+/// eviction policies can be tracked per kind instead of lumped together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Part,
+    Manifest,
+}
+
+impl fmt::Display for AssetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetKind::Part => write!(f, "part"),
+            AssetKind::Manifest => write!(f, "manifest"),
+        }
+    }
+}
+
+/// Identifies one cacheable asset: a namespace's manifest, or one of its
+/// parts. `asset` is the object-store key (e.g. a part file name).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub namespace: String,
+    pub asset: String,
+    pub kind: AssetKind,
+}
+
+impl CacheKey {
+    pub fn new(namespace: impl Into<String>, asset: impl Into<String>, kind: AssetKind) -> Self {
+        Self {
+            namespace: namespace.into(),
+            asset: asset.into(),
+            kind,
+        }
+    }
+
+    /// Flat on-disk cache entry name. Namespaced so the same part name in
+    /// two namespaces never collides (object-store part names are globally
+    /// unique today, but this keeps the cache correct if that changes).
+    pub fn cache_name(&self) -> String {
+        format!("{}__{}", self.namespace, self.asset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_name_namespaces_the_asset() {
+        let key = CacheKey::new("docs", "part-0.bin", AssetKind::Part);
+        assert_eq!(key.cache_name(), "docs__part-0.bin");
+    }
+}
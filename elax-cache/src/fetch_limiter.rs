@@ -0,0 +1,70 @@
+use std::sync::{Condvar, Mutex};
+
+/// Bounds how many remote fetches `PartCache::get_or_fetch` will run at
+/// once, so a cold cache under concurrent query load doesn't open one
+/// connection to the object store per request.
+pub struct FetchLimiter {
+    available: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl FetchLimiter {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            available: Mutex::new(max_concurrent.max(1)),
+            freed: Condvar::new(),
+        }
+    }
+
+    /// Block until a fetch slot is free, run `f`, then release the slot.
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        let mut available = self.available.lock().unwrap();
+        while *available == 0 {
+            available = self.freed.wait(available).unwrap();
+        }
+        *available -= 1;
+        drop(available);
+
+        let result = f();
+
+        *self.available.lock().unwrap() += 1;
+        self.freed.notify_one();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn never_exceeds_the_configured_concurrency() {
+        let limiter = Arc::new(FetchLimiter::new(2));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_observed = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let in_flight = in_flight.clone();
+                let max_observed = max_observed.clone();
+                thread::spawn(move || {
+                    limiter.run(|| {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_observed.fetch_max(now, Ordering::SeqCst);
+                        thread::sleep(std::time::Duration::from_millis(5));
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                    });
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(max_observed.load(Ordering::SeqCst) <= 2);
+    }
+}
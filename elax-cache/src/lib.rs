@@ -0,0 +1,11 @@
+//! Local NVMe-backed cache for downloaded part assets.
+
+mod cache_key;
+mod error;
+mod fetch_limiter;
+mod part_cache;
+mod sharded_map;
+
+pub use cache_key::{AssetKind, CacheKey};
+pub use error::CacheError;
+pub use part_cache::{CacheCapacity, PartCache};
@@ -0,0 +1,453 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::cache_key::{AssetKind, CacheKey};
+use crate::error::Result;
+use crate::fetch_limiter::FetchLimiter;
+use crate::sharded_map::ShardedMap;
+
+/// Default cap on how many remote fetches `get_or_fetch` runs at once.
+const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 8;
+
+#[derive(Default)]
+struct KindCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct EntryMeta {
+    size: u64,
+    last_access: SystemTime,
+}
+
+/// Bounds on how much the NVMe tier is allowed to hold before `PartCache`
+/// starts evicting. `None` in either field means no limit on that axis,
+/// matching how [`crate`]-adjacent config like `elax_core::registry::Quota`
+/// treats an absent bound as unlimited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheCapacity {
+    pub max_bytes: Option<u64>,
+    pub max_age_secs: Option<u64>,
+}
+
+/// Split a namespaced cache entry name (see `CacheKey::cache_name`) back
+/// into its namespace, if it was written through that scheme.
+fn namespace_of(cache_name: &str) -> Option<&str> {
+    cache_name.split_once("__").map(|(namespace, _)| namespace)
+}
+
+/// A directory of part assets mirrored from remote storage onto local NVMe,
+/// so query nodes can serve reads without round-tripping to the object
+/// store on every request.
+///
+/// Entry bookkeeping is a [`ShardedMap`] rather than one global mutex, so
+/// concurrent `get_or_fetch` calls for different parts don't serialize on
+/// each other; `total_bytes` is tracked separately with an atomic so the
+/// capacity check doesn't need to walk every shard on the common path.
+pub struct PartCache {
+    root: PathBuf,
+    entries: ShardedMap<EntryMeta>,
+    total_bytes: AtomicU64,
+    pinned_namespaces: Mutex<HashSet<String>>,
+    fetch_limiter: FetchLimiter,
+    counters: Mutex<HashMap<AssetKind, KindCounters>>,
+    capacity: CacheCapacity,
+}
+
+impl PartCache {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        Self::with_capacity(root, CacheCapacity::default())
+    }
+
+    /// Like `new`, but evicts entries once `capacity` is exceeded.
+    pub fn with_capacity(root: impl Into<PathBuf>, capacity: CacheCapacity) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        let entries = ShardedMap::new();
+        let mut total_bytes = 0u64;
+        for entry in fs::read_dir(&root)? {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if name.ends_with(".tmp") {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            total_bytes += metadata.len();
+            entries.insert(
+                name,
+                EntryMeta {
+                    size: metadata.len(),
+                    last_access: metadata.modified()?,
+                },
+            );
+        }
+        let cache = Self {
+            root,
+            entries,
+            total_bytes: AtomicU64::new(total_bytes),
+            pinned_namespaces: Mutex::new(HashSet::new()),
+            fetch_limiter: FetchLimiter::new(DEFAULT_MAX_CONCURRENT_FETCHES),
+            counters: Mutex::new(HashMap::new()),
+            capacity,
+        };
+        cache.evict_over_capacity();
+        Ok(cache)
+    }
+
+    /// Return the cached bytes for `key`, fetching and storing them via
+    /// `fetch` on a miss. Concurrent misses across the cache are bounded by
+    /// an internal [`FetchLimiter`] so a cold cache under load doesn't open
+    /// one remote connection per request. Hit/miss counts are tracked per
+    /// [`AssetKind`] for `hit_ratio`. `PartCache` never inspects the bytes
+    /// `fetch` returns, so whatever encryption a caller's `fetch` preserves
+    /// (e.g. reading a part's raw ciphertext rather than routing the read
+    /// through an `elax_store::EncryptingStore`) lands on NVMe unchanged —
+    /// cache files at rest are encrypted for free whenever the upstream
+    /// bytes already are, with no separate at-rest handling needed here.
+    pub fn get_or_fetch(
+        &self,
+        key: &CacheKey,
+        fetch: impl FnOnce() -> Result<Vec<u8>>,
+    ) -> Result<PathBuf> {
+        let name = key.cache_name();
+        if self.contains(&name) {
+            self.touch(&name);
+            self.record(key.kind, true);
+            return Ok(self.path_for(&name));
+        }
+
+        self.record(key.kind, false);
+        let bytes = self.fetch_limiter.run(fetch)?;
+        self.put(&name, &bytes)
+    }
+
+    fn record(&self, kind: AssetKind, hit: bool) {
+        let mut counters = self.counters.lock().unwrap();
+        let entry = counters.entry(kind).or_default();
+        if hit {
+            entry.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            entry.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Fraction of `get_or_fetch` calls for `kind` that were served from
+    /// cache, `0.0` if `kind` has never been looked up.
+    pub fn hit_ratio(&self, kind: AssetKind) -> f64 {
+        let counters = self.counters.lock().unwrap();
+        let Some(entry) = counters.get(&kind) else {
+            return 0.0;
+        };
+        let hits = entry.hits.load(Ordering::Relaxed);
+        let misses = entry.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+
+    pub fn path_for(&self, part_name: &str) -> PathBuf {
+        self.root.join(part_name)
+    }
+
+    pub fn contains(&self, part_name: &str) -> bool {
+        self.entries.contains_key(part_name)
+    }
+
+    fn touch(&self, part_name: &str) {
+        self.entries.with_mut(part_name, |entry| {
+            entry.last_access = SystemTime::now();
+        });
+    }
+
+    /// Materialize `part_name` into the cache directory, then evict the
+    /// least-recently-used unpinned entries if that pushes the cache over
+    /// its size budget.
+    pub fn put(&self, part_name: &str, bytes: &[u8]) -> Result<PathBuf> {
+        let path = self.path_for(part_name);
+        let tmp_path = self.root.join(format!("{part_name}.tmp"));
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &path)?;
+        self.entries.insert(
+            part_name.to_string(),
+            EntryMeta {
+                size: bytes.len() as u64,
+                last_access: SystemTime::now(),
+            },
+        );
+        self.total_bytes.fetch_add(bytes.len() as u64, Ordering::Relaxed);
+        self.evict_over_capacity();
+        Ok(path)
+    }
+
+    pub fn remove(&self, part_name: &str) -> Result<()> {
+        let path = self.path_for(part_name);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        if let Some(entry) = self.entries.remove(part_name) {
+            self.total_bytes.fetch_sub(entry.size, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Mark `namespace` as pinned: its entries are exempt from both the
+    /// size-based and age-based eviction below.
+    pub fn pin_namespace(&self, namespace: &str) {
+        self.pinned_namespaces.lock().unwrap().insert(namespace.to_string());
+    }
+
+    pub fn unpin_namespace(&self, namespace: &str) {
+        self.pinned_namespaces.lock().unwrap().remove(namespace);
+    }
+
+    pub fn is_pinned(&self, namespace: &str) -> bool {
+        self.pinned_namespaces.lock().unwrap().contains(namespace)
+    }
+
+    /// Bytes currently cached on local NVMe for `namespace`, for capacity
+    /// planning and the per-namespace stats surfaced by
+    /// `elax_core::registry::NamespaceRegistry`. Derived from
+    /// `entries`/`namespace_of` rather than a separate per-namespace
+    /// counter, the same way `total_bytes` is derived on insert/remove —
+    /// there's just one more than one namespace to add up here.
+    pub fn bytes_for_namespace(&self, namespace: &str) -> u64 {
+        let mut bytes = 0;
+        self.entries.for_each(|name, entry| {
+            if namespace_of(name) == Some(namespace) {
+                bytes += entry.size;
+            }
+        });
+        bytes
+    }
+
+    fn is_entry_pinned(&self, name: &str, pinned: &HashSet<String>) -> bool {
+        namespace_of(name).is_some_and(|namespace| pinned.contains(namespace))
+    }
+
+    /// Evict least-recently-used unpinned entries until total size is back
+    /// under `capacity.max_bytes`. A no-op if no budget was configured.
+    fn evict_over_capacity(&self) -> Vec<String> {
+        let Some(max_bytes) = self.capacity.max_bytes else {
+            return Vec::new();
+        };
+
+        let pinned = self.pinned_namespaces.lock().unwrap().clone();
+        let mut evicted = Vec::new();
+        while self.total_bytes.load(Ordering::Relaxed) > max_bytes {
+            let mut oldest: Option<(String, SystemTime)> = None;
+            self.entries.for_each(|name, meta| {
+                if self.is_entry_pinned(name, &pinned) {
+                    return;
+                }
+                if oldest.as_ref().is_none_or(|(_, t)| meta.last_access < *t) {
+                    oldest = Some((name.to_string(), meta.last_access));
+                }
+            });
+            match oldest {
+                Some((name, _)) => {
+                    let _ = self.remove(&name);
+                    evicted.push(name);
+                }
+                None => break, // everything left over budget is pinned
+            }
+        }
+        evicted
+    }
+
+    /// Remove unpinned entries last accessed more than `max_age_secs` ago.
+    /// Meant to be driven periodically by a janitor loop, the same way
+    /// `elax_core::replication::catch_up` is driven by a polling loop
+    /// rather than spawning its own background thread.
+    pub fn sweep_aged_out(&self, now: SystemTime) -> Vec<String> {
+        let Some(max_age_secs) = self.capacity.max_age_secs else {
+            return Vec::new();
+        };
+
+        let pinned = self.pinned_namespaces.lock().unwrap().clone();
+        let mut stale = Vec::new();
+        self.entries.for_each(|name, meta| {
+            if self.is_entry_pinned(name, &pinned) {
+                return;
+            }
+            let is_stale = now
+                .duration_since(meta.last_access)
+                .map(|age| age.as_secs() > max_age_secs)
+                .unwrap_or(false);
+            if is_stale {
+                stale.push(name.to_string());
+            }
+        });
+        for name in &stale {
+            let _ = self.remove(name);
+        }
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-cache-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn put_then_contains() {
+        let cache = PartCache::new(tmp_dir()).unwrap();
+        assert!(!cache.contains("part-0.bin"));
+        cache.put("part-0.bin", b"payload").unwrap();
+        assert!(cache.contains("part-0.bin"));
+    }
+
+    #[test]
+    fn pinning_a_namespace_is_independent_of_others() {
+        let cache = PartCache::new(tmp_dir()).unwrap();
+        assert!(!cache.is_pinned("docs"));
+        cache.pin_namespace("docs");
+        assert!(cache.is_pinned("docs"));
+        assert!(!cache.is_pinned("images"));
+        cache.unpin_namespace("docs");
+        assert!(!cache.is_pinned("docs"));
+    }
+
+    #[test]
+    fn get_or_fetch_only_calls_fetch_on_a_miss() {
+        let cache = PartCache::new(tmp_dir()).unwrap();
+        let key = CacheKey::new("docs", "part-0.bin", AssetKind::Part);
+
+        let mut fetch_calls = 0;
+        cache
+            .get_or_fetch(&key, || {
+                fetch_calls += 1;
+                Ok(b"payload".to_vec())
+            })
+            .unwrap();
+        assert_eq!(fetch_calls, 1);
+        assert_eq!(cache.hit_ratio(AssetKind::Part), 0.0);
+
+        cache
+            .get_or_fetch(&key, || {
+                fetch_calls += 1;
+                Ok(b"payload".to_vec())
+            })
+            .unwrap();
+        assert_eq!(fetch_calls, 1, "second lookup should be served from cache");
+        assert_eq!(cache.hit_ratio(AssetKind::Part), 0.5);
+    }
+
+    #[test]
+    fn bytes_for_namespace_only_counts_that_namespaces_entries() {
+        let cache = PartCache::new(tmp_dir()).unwrap();
+        let docs_key = CacheKey::new("docs", "part-0.bin", AssetKind::Part);
+        let images_key = CacheKey::new("images", "part-0.bin", AssetKind::Part);
+
+        cache.get_or_fetch(&docs_key, || Ok(b"0123456789".to_vec())).unwrap();
+        cache.get_or_fetch(&images_key, || Ok(b"hello".to_vec())).unwrap();
+
+        assert_eq!(cache.bytes_for_namespace("docs"), 10);
+        assert_eq!(cache.bytes_for_namespace("images"), 5);
+        assert_eq!(cache.bytes_for_namespace("missing"), 0);
+    }
+
+    #[test]
+    fn size_based_eviction_drops_the_least_recently_used_entry() {
+        let cache = PartCache::with_capacity(
+            tmp_dir(),
+            CacheCapacity {
+                max_bytes: Some(10),
+                max_age_secs: None,
+            },
+        )
+        .unwrap();
+
+        cache.put("docs__part-0.bin", b"aaaaa").unwrap(); // 5 bytes, oldest
+        cache.put("docs__part-1.bin", b"bbbbb").unwrap(); // 5 bytes, 10 total: fits
+        cache.put("docs__part-2.bin", b"ccccc").unwrap(); // pushes to 15: evict part-0
+
+        assert!(!cache.contains("docs__part-0.bin"));
+        assert!(cache.contains("docs__part-1.bin"));
+        assert!(cache.contains("docs__part-2.bin"));
+    }
+
+    #[test]
+    fn pinned_namespace_survives_size_eviction() {
+        let cache = PartCache::with_capacity(
+            tmp_dir(),
+            CacheCapacity {
+                max_bytes: Some(5),
+                max_age_secs: None,
+            },
+        )
+        .unwrap();
+        cache.pin_namespace("docs");
+
+        cache.put("docs__part-0.bin", b"aaaaa").unwrap();
+        cache.put("other__part-0.bin", b"bbbbb").unwrap();
+
+        assert!(cache.contains("docs__part-0.bin"));
+        assert!(!cache.contains("other__part-0.bin"));
+    }
+
+    #[test]
+    fn sweep_aged_out_removes_only_stale_unpinned_entries() {
+        let cache = PartCache::with_capacity(
+            tmp_dir(),
+            CacheCapacity {
+                max_bytes: None,
+                max_age_secs: Some(60),
+            },
+        )
+        .unwrap();
+        cache.put("docs__part-0.bin", b"payload").unwrap();
+
+        let far_future = SystemTime::now() + std::time::Duration::from_secs(3600);
+        let evicted = cache.sweep_aged_out(far_future);
+        assert_eq!(evicted, vec!["docs__part-0.bin".to_string()]);
+        assert!(!cache.contains("docs__part-0.bin"));
+    }
+
+    #[test]
+    fn concurrent_get_or_fetch_from_many_threads_stays_consistent() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let cache = Arc::new(PartCache::new(tmp_dir()).unwrap());
+        let handles: Vec<_> = (0..32)
+            .map(|i| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    let key = CacheKey::new("docs", format!("part-{i}.bin"), AssetKind::Part);
+                    cache.get_or_fetch(&key, || Ok(vec![0u8; 16])).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for i in 0..32 {
+            assert!(cache.contains(&format!("docs__part-{i}.bin")));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+const DEFAULT_SHARDS: usize = 16;
+
+/// A `String`-keyed map split across independent mutex-guarded shards, so
+/// two callers touching different keys never block each other the way a
+/// single `Mutex<HashMap<_, _>>` would under concurrent query load.
+pub struct ShardedMap<V> {
+    shards: Vec<Mutex<HashMap<String, V>>>,
+}
+
+impl<V> ShardedMap<V> {
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    pub fn with_shards(shard_count: usize) -> Self {
+        let shard_count = shard_count.max(1);
+        Self {
+            shards: (0..shard_count).map(|_| Mutex::new(HashMap::new())).collect(),
+        }
+    }
+
+    fn shard_for(&self, key: &str) -> &Mutex<HashMap<String, V>> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() as usize) % self.shards.len();
+        &self.shards[index]
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.shard_for(key).lock().unwrap().contains_key(key)
+    }
+
+    pub fn insert(&self, key: String, value: V) {
+        let shard = self.shard_for(&key);
+        shard.lock().unwrap().insert(key, value);
+    }
+
+    pub fn remove(&self, key: &str) -> Option<V> {
+        self.shard_for(key).lock().unwrap().remove(key)
+    }
+
+    /// Run `f` against the entry for `key`, if present.
+    pub fn with_mut<R>(&self, key: &str, f: impl FnOnce(&mut V) -> R) -> Option<R> {
+        self.shard_for(key).lock().unwrap().get_mut(key).map(f)
+    }
+
+    /// Visit every `(key, value)` pair. Shards are locked one at a time, so
+    /// this never holds more than one shard's lock at once, but it is not a
+    /// point-in-time snapshot of the whole map.
+    pub fn for_each(&self, mut f: impl FnMut(&str, &V)) {
+        for shard in &self.shards {
+            let guard = shard.lock().unwrap();
+            for (key, value) in guard.iter() {
+                f(key, value);
+            }
+        }
+    }
+}
+
+impl<V> Default for ShardedMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn insert_get_remove_round_trip() {
+        let map: ShardedMap<u64> = ShardedMap::new();
+        map.insert("a".to_string(), 1);
+        assert!(map.contains_key("a"));
+        assert_eq!(map.remove("a"), Some(1));
+        assert!(!map.contains_key("a"));
+    }
+
+    #[test]
+    fn concurrent_inserts_from_many_threads_are_all_visible() {
+        let map = Arc::new(ShardedMap::<u64>::new());
+        let handles: Vec<_> = (0..64)
+            .map(|i| {
+                let map = map.clone();
+                thread::spawn(move || map.insert(format!("key-{i}"), i))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut seen = 0;
+        map.for_each(|_, _| seen += 1);
+        assert_eq!(seen, 64);
+    }
+}
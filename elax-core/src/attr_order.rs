@@ -0,0 +1,160 @@
+//! Typed multi-key ordering over document attributes: how
+//! [`crate::registry::NamespaceRegistry::query_by_filter`] ranks a
+//! filter-only query (no vector to score by), and how
+//! [`crate::scoring::order_by_attrs`] breaks a tie in vector score for a
+//! regular one. Every key compares by its JSON type (numbers numerically,
+//! strings lexicographically) rather than by stringifying everything
+//! first, so `"rank": 10` sorts after `"rank": 9` instead of before it.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+/// Which way one [`AttrOrder`] key sorts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Where a row missing this key's attribute (or holding an explicit
+/// `null`) lands, independent of `direction` — the same
+/// `NULLS FIRST`/`NULLS LAST` split a SQL `ORDER BY` offers, so reversing
+/// `direction` doesn't also flip where the gaps in the data end up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// One key in a multi-key sort: an attribute name (or `"id"` for the
+/// document id itself), a direction, and where a missing value lands.
+/// [`crate::registry::NamespaceRegistry::query_by_filter`] and
+/// [`crate::scoring::order_by_attrs`] both take a `&[AttrOrder]` — later
+/// keys only matter once every earlier one ties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttrOrder {
+    pub key: String,
+    pub direction: SortDirection,
+    pub nulls: NullsOrder,
+}
+
+fn resolve_key(attributes: &Value, id: &str, key: &str) -> Value {
+    if key == "id" {
+        Value::String(id.to_string())
+    } else {
+        attributes.get(key).cloned().unwrap_or(Value::Null)
+    }
+}
+
+/// Resolve every key `order_by` sorts by, in order, against one row's
+/// attributes — the values a [`crate::registry::QueryCursor`] captures so
+/// a later page can resume by comparing against them without re-reading
+/// the row itself.
+pub(crate) fn sort_keys(attributes: &Value, id: &str, order_by: &[AttrOrder]) -> Vec<Value> {
+    order_by.iter().map(|order| resolve_key(attributes, id, &order.key)).collect()
+}
+
+fn compare_typed(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().unwrap_or(0.0).total_cmp(&b.as_f64().unwrap_or(0.0)),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        // Mismatched types (e.g. a key typed numeric on one row and
+        // stringly on another) have no sensible typed order; treat them as
+        // tied rather than panicking or guessing.
+        _ => Ordering::Equal,
+    }
+}
+
+fn compare_one(a: &Value, b: &Value, order: &AttrOrder) -> Ordering {
+    match (a.is_null(), b.is_null()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => match order.nulls {
+            NullsOrder::First => Ordering::Less,
+            NullsOrder::Last => Ordering::Greater,
+        },
+        (false, true) => match order.nulls {
+            NullsOrder::First => Ordering::Greater,
+            NullsOrder::Last => Ordering::Less,
+        },
+        (false, false) => {
+            let ord = compare_typed(a, b);
+            match order.direction {
+                SortDirection::Asc => ord,
+                SortDirection::Desc => ord.reverse(),
+            }
+        }
+    }
+}
+
+/// Total order over two rows' already-resolved `order_by` key values, plus
+/// their ids — `a_keys`/`b_keys` must have come from [`sort_keys`] with the
+/// same `order_by`. Ties on every key break by ascending id, so `(keys,
+/// id)` is a total order regardless of how many keys tie.
+pub(crate) fn compare_keys(a_keys: &[Value], a_id: &str, b_keys: &[Value], b_id: &str, order_by: &[AttrOrder]) -> Ordering {
+    for (i, order) in order_by.iter().enumerate() {
+        let ord = compare_one(&a_keys[i], &b_keys[i], order);
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    a_id.cmp(b_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_keys_compare_by_value_not_by_string() {
+        let order_by = vec![AttrOrder { key: "rank".to_string(), direction: SortDirection::Asc, nulls: NullsOrder::Last }];
+        let a = serde_json::json!({"rank": 9});
+        let b = serde_json::json!({"rank": 10});
+        let a_keys = sort_keys(&a, "a", &order_by);
+        let b_keys = sort_keys(&b, "b", &order_by);
+        assert_eq!(compare_keys(&a_keys, "a", &b_keys, "b", &order_by), Ordering::Less);
+    }
+
+    #[test]
+    fn nulls_last_puts_a_missing_key_after_a_present_one_even_when_descending() {
+        let order_by = vec![AttrOrder { key: "rank".to_string(), direction: SortDirection::Desc, nulls: NullsOrder::Last }];
+        let present = serde_json::json!({"rank": 1});
+        let missing = serde_json::json!({});
+        let a_keys = sort_keys(&present, "a", &order_by);
+        let b_keys = sort_keys(&missing, "b", &order_by);
+        assert_eq!(compare_keys(&a_keys, "a", &b_keys, "b", &order_by), Ordering::Less);
+    }
+
+    #[test]
+    fn nulls_first_puts_a_missing_key_before_a_present_one() {
+        let order_by = vec![AttrOrder { key: "rank".to_string(), direction: SortDirection::Asc, nulls: NullsOrder::First }];
+        let present = serde_json::json!({"rank": 1});
+        let missing = serde_json::json!({});
+        let a_keys = sort_keys(&missing, "a", &order_by);
+        let b_keys = sort_keys(&present, "b", &order_by);
+        assert_eq!(compare_keys(&a_keys, "a", &b_keys, "b", &order_by), Ordering::Less);
+    }
+
+    #[test]
+    fn a_second_key_breaks_a_tie_on_the_first() {
+        let order_by = vec![
+            AttrOrder { key: "tier".to_string(), direction: SortDirection::Asc, nulls: NullsOrder::Last },
+            AttrOrder { key: "rank".to_string(), direction: SortDirection::Desc, nulls: NullsOrder::Last },
+        ];
+        let a = serde_json::json!({"tier": "gold", "rank": 1});
+        let b = serde_json::json!({"tier": "gold", "rank": 5});
+        let a_keys = sort_keys(&a, "a", &order_by);
+        let b_keys = sort_keys(&b, "b", &order_by);
+        // Same tier, so the descending rank key decides: b's 5 beats a's 1.
+        assert_eq!(compare_keys(&a_keys, "a", &b_keys, "b", &order_by), Ordering::Greater);
+    }
+
+    #[test]
+    fn ties_on_every_key_break_by_ascending_id() {
+        let order_by: Vec<AttrOrder> = Vec::new();
+        let a_keys = sort_keys(&Value::Null, "a", &order_by);
+        let b_keys = sort_keys(&Value::Null, "b", &order_by);
+        assert_eq!(compare_keys(&a_keys, "a", &b_keys, "b", &order_by), Ordering::Less);
+    }
+}
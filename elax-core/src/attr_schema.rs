@@ -0,0 +1,131 @@
+//! Per-namespace attribute schema: the type each attribute key is expected
+//! to hold, inferred from the first write that defines it (or declared
+//! explicitly up front via [`NamespaceConfig::attr_schema`]). Later writes
+//! are checked against it so type drift is rejected at write time instead
+//! of being discovered by whatever later rebuilds FTS/field indexes from
+//! raw rows.
+//!
+//! [`NamespaceConfig::attr_schema`]: crate::registry::NamespaceConfig::attr_schema
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::error::{CoreError, Result};
+
+/// The type an attribute column is expected to hold. Mirrors the column
+/// kinds [`crate::columnar::AttributeColumns`] materializes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttrType {
+    Numeric,
+    Keyword,
+    Bool,
+}
+
+impl fmt::Display for AttrType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AttrType::Numeric => "numeric",
+            AttrType::Keyword => "keyword",
+            AttrType::Bool => "bool",
+        };
+        f.write_str(name)
+    }
+}
+
+fn type_of(value: &serde_json::Value) -> Option<AttrType> {
+    if value.is_number() {
+        Some(AttrType::Numeric)
+    } else if value.is_boolean() {
+        Some(AttrType::Bool)
+    } else if value.is_string() {
+        Some(AttrType::Keyword)
+    } else {
+        None
+    }
+}
+
+/// The effective attribute schema for one namespace.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AttrSchema {
+    fields: HashMap<String, AttrType>,
+}
+
+impl AttrSchema {
+    /// Seed a schema with explicit declarations (from
+    /// [`NamespaceConfig::attr_schema`]), which subsequent writes are
+    /// checked against the same as an inferred field.
+    ///
+    /// [`NamespaceConfig::attr_schema`]: crate::registry::NamespaceConfig::attr_schema
+    pub fn new(declared: HashMap<String, AttrType>) -> Self {
+        Self { fields: declared }
+    }
+
+    pub fn fields(&self) -> &HashMap<String, AttrType> {
+        &self.fields
+    }
+
+    /// Check `attributes` against the schema, inferring and recording the
+    /// type of any key seen for the first time. Keys whose value is neither
+    /// a number, bool, nor string (e.g. an array or nested object) are left
+    /// untyped and skipped — arrays/nested objects aren't in scope for this
+    /// flat key-to-scalar schema yet.
+    pub fn observe(&mut self, attributes: &serde_json::Value) -> Result<()> {
+        let Some(obj) = attributes.as_object() else {
+            return Ok(());
+        };
+        for (key, value) in obj {
+            let Some(found) = type_of(value) else {
+                continue;
+            };
+            match self.fields.get(key) {
+                Some(expected) if *expected != found => {
+                    return Err(CoreError::AttrTypeMismatch {
+                        key: key.clone(),
+                        expected: *expected,
+                        found,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    self.fields.insert(key.clone(), found);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn infers_field_types_on_first_write() {
+        let mut schema = AttrSchema::default();
+        schema.observe(&json!({"status": "active", "score": 0.5, "pinned": true})).unwrap();
+
+        assert_eq!(schema.fields().get("status"), Some(&AttrType::Keyword));
+        assert_eq!(schema.fields().get("score"), Some(&AttrType::Numeric));
+        assert_eq!(schema.fields().get("pinned"), Some(&AttrType::Bool));
+    }
+
+    #[test]
+    fn rejects_a_conflicting_type_on_a_later_write() {
+        let mut schema = AttrSchema::default();
+        schema.observe(&json!({"score": 0.5})).unwrap();
+
+        let err = schema.observe(&json!({"score": "high"})).unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::AttrTypeMismatch { expected: AttrType::Numeric, found: AttrType::Keyword, .. }
+        ));
+    }
+
+    #[test]
+    fn an_explicit_declaration_is_enforced_like_an_inferred_field() {
+        let mut schema = AttrSchema::new(HashMap::from([("score".to_string(), AttrType::Numeric)]));
+        let err = schema.observe(&json!({"score": "high"})).unwrap_err();
+        assert!(matches!(err, CoreError::AttrTypeMismatch { .. }));
+    }
+}
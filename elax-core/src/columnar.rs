@@ -0,0 +1,268 @@
+//! Typed columnar attribute storage, materialized once from a batch of
+//! rows' JSON `attributes`, so a filter or aggregation over many rows
+//! doesn't re-parse and re-walk each row's JSON tree on every pass. Rows
+//! themselves still carry `attributes` as `serde_json::Value` (see
+//! [`crate::document::Document`]) — there's no Parquet or other on-disk
+//! columnar part format in this tree yet for this to back directly, so
+//! [`AttributeColumns`] is an in-memory materialization a planner can build
+//! from whatever rows it already has in hand, analogous to what per-part
+//! columns would give for free once one exists.
+
+use std::collections::HashMap;
+
+use crate::document::Document;
+use crate::filter::FilterExpr;
+
+/// One attribute's values across a batch of rows, by the type the first
+/// row that defines the key used. A row missing the key, or whose value
+/// doesn't match the column's type, contributes `None`.
+#[derive(Debug, Clone, PartialEq)]
+enum Column {
+    Numeric(Vec<Option<f64>>),
+    Keyword(Vec<Option<String>>),
+    Bool(Vec<Option<bool>>),
+}
+
+impl Column {
+    /// `(min, max)` over this column's non-null values, or `None` for an
+    /// all-null or non-numeric column. The cheap summary [`Self::could_match`]
+    /// checks a predicate against before bothering to evaluate it row by
+    /// row — the in-memory stand-in for the row-group/page statistics a
+    /// real on-disk columnar part format would carry (see this module's
+    /// doc comment; there's no such format here yet to carry them).
+    fn numeric_range(&self) -> Option<(f64, f64)> {
+        match self {
+            Column::Numeric(values) => {
+                let mut present = values.iter().flatten().copied();
+                let first = present.next()?;
+                Some(present.fold((first, first), |(lo, hi), v| (lo.min(v), hi.max(v))))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A batch of rows' attributes, materialized into one typed column per key.
+#[derive(Debug, Clone, Default)]
+pub struct AttributeColumns {
+    ids: Vec<String>,
+    columns: HashMap<String, Column>,
+}
+
+fn infer_column(key: &str, rows: &[&Document]) -> Column {
+    let first_kind = rows.iter().find_map(|doc| doc.attributes.get(key));
+    match first_kind {
+        Some(v) if v.is_number() => {
+            Column::Numeric(rows.iter().map(|doc| doc.attributes.get(key).and_then(|v| v.as_f64())).collect())
+        }
+        Some(v) if v.is_boolean() => {
+            Column::Bool(rows.iter().map(|doc| doc.attributes.get(key).and_then(|v| v.as_bool())).collect())
+        }
+        _ => Column::Keyword(
+            rows.iter()
+                .map(|doc| doc.attributes.get(key).and_then(|v| v.as_str()).map(str::to_string))
+                .collect(),
+        ),
+    }
+}
+
+impl AttributeColumns {
+    /// Materialize columns for every attribute key present on any of
+    /// `rows`, in one pass over each row's JSON object.
+    pub fn build<'a>(rows: impl IntoIterator<Item = &'a Document>) -> Self {
+        let rows: Vec<&Document> = rows.into_iter().collect();
+        let ids = rows.iter().map(|doc| doc.id.clone()).collect();
+
+        let mut keys = Vec::new();
+        for doc in &rows {
+            if let Some(obj) = doc.attributes.as_object() {
+                for key in obj.keys() {
+                    if !keys.contains(key) {
+                        keys.push(key.clone());
+                    }
+                }
+            }
+        }
+
+        let columns = keys
+            .into_iter()
+            .map(|key| {
+                let column = infer_column(&key, &rows);
+                (key, column)
+            })
+            .collect();
+
+        Self { ids, columns }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Evaluate `filter` against the materialized columns, returning one
+    /// bool per row in the same order `rows` was given to [`Self::build`].
+    pub fn eval_filter(&self, filter: &FilterExpr) -> Vec<bool> {
+        match filter {
+            FilterExpr::AttrEq { key, value } => self.eval_eq(key, value),
+            FilterExpr::AttrGt { key, value } => self.eval_cmp(key, |v| v > *value),
+            FilterExpr::AttrLt { key, value } => self.eval_cmp(key, |v| v < *value),
+            FilterExpr::And(clauses) => self.fold(clauses, true, |a, b| a && b),
+            FilterExpr::Or(clauses) => self.fold(clauses, false, |a, b| a || b),
+            FilterExpr::Not(inner) => self.eval_filter(inner).into_iter().map(|b| !b).collect(),
+        }
+    }
+
+    /// Whether `filter` can possibly match any row in this batch, decided
+    /// from each referenced column's numeric range rather than a per-row
+    /// scan. May return a false positive (`true` for a batch with no
+    /// matching row, e.g. for a column it has no cheap range for such as
+    /// `Keyword`), never a false negative, so a
+    /// caller scanning many batches can skip [`Self::eval_filter`]
+    /// (and decoding the rows behind it) entirely for a batch this returns
+    /// `false` for without changing the result.
+    pub fn could_match(&self, filter: &FilterExpr) -> bool {
+        match filter {
+            FilterExpr::AttrEq { key, value } => match (self.columns.get(key), value.as_f64()) {
+                (Some(column), Some(target)) => column
+                    .numeric_range()
+                    .is_none_or(|(lo, hi)| (lo..=hi).contains(&target)),
+                _ => true,
+            },
+            FilterExpr::AttrGt { key, value } => self
+                .columns
+                .get(key)
+                .and_then(Column::numeric_range)
+                .is_none_or(|(_, hi)| hi > *value),
+            FilterExpr::AttrLt { key, value } => self
+                .columns
+                .get(key)
+                .and_then(Column::numeric_range)
+                .is_none_or(|(lo, _)| lo < *value),
+            FilterExpr::And(clauses) => clauses.iter().all(|clause| self.could_match(clause)),
+            FilterExpr::Or(clauses) => clauses.iter().any(|clause| self.could_match(clause)),
+            // A range that rules a predicate in doesn't rule its negation
+            // out, so `Not` can't be pruned from the inner range alone.
+            FilterExpr::Not(_) => true,
+        }
+    }
+
+    /// Ids of every row matching `filter`.
+    pub fn matching_ids(&self, filter: &FilterExpr) -> Vec<String> {
+        self.eval_filter(filter)
+            .into_iter()
+            .zip(&self.ids)
+            .filter(|(matched, _)| *matched)
+            .map(|(_, id)| id.clone())
+            .collect()
+    }
+
+    fn fold(&self, clauses: &[FilterExpr], identity: bool, combine: impl Fn(bool, bool) -> bool) -> Vec<bool> {
+        let mut acc = vec![identity; self.row_count()];
+        for clause in clauses {
+            let evaluated = self.eval_filter(clause);
+            for (a, b) in acc.iter_mut().zip(evaluated) {
+                *a = combine(*a, b);
+            }
+        }
+        acc
+    }
+
+    fn eval_eq(&self, key: &str, value: &serde_json::Value) -> Vec<bool> {
+        match self.columns.get(key) {
+            Some(Column::Numeric(values)) => {
+                let target = value.as_f64();
+                values.iter().map(|v| target.is_some() && *v == target).collect()
+            }
+            Some(Column::Bool(values)) => {
+                let target = value.as_bool();
+                values.iter().map(|v| target.is_some() && *v == target).collect()
+            }
+            Some(Column::Keyword(values)) => {
+                let target = value.as_str();
+                values.iter().map(|v| target.is_some() && v.as_deref() == target).collect()
+            }
+            None => vec![false; self.row_count()],
+        }
+    }
+
+    fn eval_cmp(&self, key: &str, matches: impl Fn(f64) -> bool) -> Vec<bool> {
+        match self.columns.get(key) {
+            Some(Column::Numeric(values)) => values.iter().map(|v| v.is_some_and(&matches)).collect(),
+            _ => vec![false; self.row_count()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc(id: &str, attrs: serde_json::Value) -> Document {
+        Document {
+            id: id.to_string(),
+            vector: vec![],
+            attributes: attrs,
+            embedding: None,
+            embedding_model: None,
+        }
+    }
+
+    #[test]
+    fn eval_filter_matches_the_same_rows_as_per_row_evaluation() {
+        let rows = vec![
+            doc("a", json!({"status": "archived", "score": 0.9})),
+            doc("b", json!({"status": "active", "score": 0.2})),
+            doc("c", json!({"status": "archived", "score": 0.1})),
+        ];
+        let columns = AttributeColumns::build(&rows);
+
+        let filter = FilterExpr::And(vec![
+            FilterExpr::AttrEq { key: "status".to_string(), value: json!("archived") },
+            FilterExpr::AttrGt { key: "score".to_string(), value: 0.5 },
+        ]);
+
+        assert_eq!(columns.matching_ids(&filter), vec!["a".to_string()]);
+        for row in &rows {
+            assert_eq!(filter.matches(row), columns.matching_ids(&filter).contains(&row.id));
+        }
+    }
+
+    #[test]
+    fn could_match_prunes_a_batch_whose_numeric_range_rules_out_the_predicate() {
+        let rows = vec![
+            doc("a", json!({"score": 0.1})),
+            doc("b", json!({"score": 0.3})),
+        ];
+        let columns = AttributeColumns::build(&rows);
+
+        assert!(!columns.could_match(&FilterExpr::AttrGt { key: "score".to_string(), value: 0.5 }));
+        assert!(columns.could_match(&FilterExpr::AttrGt { key: "score".to_string(), value: 0.2 }));
+    }
+
+    #[test]
+    fn could_match_never_produces_a_false_negative() {
+        let rows = vec![
+            doc("a", json!({"status": "archived", "score": 0.9})),
+            doc("b", json!({"status": "active", "score": 0.2})),
+        ];
+        let columns = AttributeColumns::build(&rows);
+
+        let filter = FilterExpr::And(vec![
+            FilterExpr::AttrEq { key: "status".to_string(), value: json!("archived") },
+            FilterExpr::AttrGt { key: "score".to_string(), value: 0.5 },
+        ]);
+
+        assert!(columns.could_match(&filter));
+        assert_eq!(columns.matching_ids(&filter), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn a_row_missing_the_key_never_matches_eq_or_comparisons() {
+        let rows = vec![doc("a", json!({"status": "archived"})), doc("b", json!({}))];
+        let columns = AttributeColumns::build(&rows);
+
+        let filter = FilterExpr::AttrEq { key: "status".to_string(), value: json!("archived") };
+        assert_eq!(columns.matching_ids(&filter), vec!["a".to_string()]);
+    }
+}
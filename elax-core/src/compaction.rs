@@ -0,0 +1,184 @@
+//! Folds tombstoned docs and superseded parts into a single merged part,
+//! fenced by the same [`Lease`] the indexer uses to own a namespace.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use elax_index::VectorPrecision;
+use elax_store::ObjectStore;
+
+use crate::error::Result;
+use crate::indexer::Lease;
+use crate::manifest::{Manifest, ManifestView};
+
+/// Merge every part in `lease.namespace`'s current manifest into one new
+/// part named `output_part_name`, dropping `tombstoned` doc ids along the
+/// way, then publish a manifest epoch that references only the merged
+/// part. Any parts the prior manifest was carrying in `delete_parts` are
+/// absorbed too — this compaction pass is exactly what clears them.
+pub fn compact_namespace(
+    store: &dyn ObjectStore,
+    dir: &Path,
+    lease: &Lease,
+    tombstoned: &HashSet<String>,
+    output_part_name: &str,
+    precision: VectorPrecision,
+) -> Result<Manifest> {
+    let view = ManifestView::load(store, &lease.namespace)?;
+    elax_index::compact_parts(dir, &view.manifest.parts, output_part_name, tombstoned, precision)?;
+
+    let new_manifest = Manifest {
+        epoch: view.manifest.epoch + 1,
+        parts: vec![output_part_name.to_string()],
+        delete_parts: Vec::new(),
+        fts_parts: view.manifest.fts_parts.clone(),
+        delete_fts_parts: view.manifest.delete_fts_parts.clone(),
+        key_id: view.manifest.key_id.clone(),
+    };
+    view.publish(store, lease, new_manifest.clone())?;
+    Ok(new_manifest)
+}
+
+/// Rebuild more than one index kind for `lease.namespace` and publish the
+/// result as a single manifest epoch, so a query can never observe one
+/// index rebuilt and the other still on its old parts. `build_vector_parts`
+/// and `build_fts_parts` each prepare their own new part assets (under
+/// whatever part names they choose) without touching the manifest
+/// themselves; only once both have succeeded does this publish one CAS
+/// write referencing both. If either build fails, nothing is published and
+/// the prior manifest — consistent with itself, if stale relative to the
+/// namespace's current WAL tail — is left in place.
+///
+/// There's no FTS index builder in this crate yet (see
+/// [`crate::text_expansion`]), so `build_fts_parts` is a caller-supplied
+/// step rather than a concrete function like [`elax_index::compact_parts`]
+/// is for `build_vector_parts`.
+pub fn rebuild_combined<BuildVectorParts, BuildFtsParts>(
+    store: &dyn ObjectStore,
+    lease: &Lease,
+    build_vector_parts: BuildVectorParts,
+    build_fts_parts: BuildFtsParts,
+) -> Result<Manifest>
+where
+    BuildVectorParts: FnOnce() -> Result<Vec<String>>,
+    BuildFtsParts: FnOnce() -> Result<Vec<String>>,
+{
+    let view = ManifestView::load(store, &lease.namespace)?;
+    let vector_parts = build_vector_parts()?;
+    let fts_parts = build_fts_parts()?;
+
+    let new_manifest = Manifest {
+        epoch: view.manifest.epoch + 1,
+        parts: vector_parts,
+        delete_parts: view.manifest.parts.clone(),
+        fts_parts,
+        delete_fts_parts: view.manifest.fts_parts.clone(),
+        key_id: view.manifest.key_id.clone(),
+    };
+    view.publish(store, lease, new_manifest.clone())?;
+    Ok(new_manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::acquire_lease;
+    use crate::router::NodeId;
+    use elax_index::{build_part, Row};
+    use elax_store::LocalStore;
+
+    fn tmp_path(label: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-core-compaction-{label}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn compaction_drops_tombstones_and_clears_delete_parts() {
+        let store = LocalStore::new(tmp_path("store")).unwrap();
+        let dir = tmp_path("parts");
+
+        build_part(&dir, "part-0", &[Row::new("a", vec![1.0]), Row::new("b", vec![2.0])], VectorPrecision::F32).unwrap();
+
+        let lease = acquire_lease(&store, "docs", NodeId::new("indexer-a")).unwrap();
+        let manifest = Manifest {
+            epoch: lease.epoch,
+            parts: vec!["part-0".to_string()],
+            delete_parts: vec!["part-ancient".to_string()],
+            ..Default::default()
+        };
+        manifest.save_if_match(&store, "docs", None).unwrap();
+
+        let tombstoned: HashSet<String> = ["b".to_string()].into_iter().collect();
+        let new_manifest =
+            compact_namespace(&store, &dir, &lease, &tombstoned, "part-1", VectorPrecision::F32).unwrap();
+
+        assert_eq!(new_manifest.parts, vec!["part-1".to_string()]);
+        assert!(new_manifest.delete_parts.is_empty());
+
+        let merged = elax_index::read_part_assets(&dir, "part-1").unwrap();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].id, "a");
+    }
+
+    #[test]
+    fn compaction_fails_once_the_lease_is_stale() {
+        let store = LocalStore::new(tmp_path("stale-store")).unwrap();
+        let dir = tmp_path("stale-parts");
+
+        let lease = acquire_lease(&store, "docs", NodeId::new("indexer-a")).unwrap();
+        let (mut state, generation) = crate::router::RouterState::load(&store).unwrap();
+        state.reassign("docs", NodeId::new("indexer-b"));
+        state.save_if_match(&store, generation).unwrap();
+
+        let err = compact_namespace(&store, &dir, &lease, &HashSet::new(), "part-1", VectorPrecision::F32).unwrap_err();
+        assert!(matches!(err, crate::error::CoreError::LeaseLost(_)));
+    }
+
+    #[test]
+    fn rebuild_combined_publishes_both_indexes_in_one_manifest_epoch() {
+        let store = LocalStore::new(tmp_path("combined-store")).unwrap();
+        let lease = acquire_lease(&store, "docs", NodeId::new("indexer-a")).unwrap();
+
+        let new_manifest = rebuild_combined(
+            &store,
+            &lease,
+            || Ok(vec!["vector-part-1".to_string()]),
+            || Ok(vec!["fts-part-1".to_string()]),
+        )
+        .unwrap();
+
+        assert_eq!(new_manifest.parts, vec!["vector-part-1".to_string()]);
+        assert_eq!(new_manifest.fts_parts, vec!["fts-part-1".to_string()]);
+
+        let view = ManifestView::load(&store, "docs").unwrap();
+        assert_eq!(view.manifest.parts, vec!["vector-part-1".to_string()]);
+        assert_eq!(view.manifest.fts_parts, vec!["fts-part-1".to_string()]);
+    }
+
+    #[test]
+    fn rebuild_combined_publishes_nothing_if_the_fts_build_fails() {
+        let store = LocalStore::new(tmp_path("combined-fail-store")).unwrap();
+        let lease = acquire_lease(&store, "docs", NodeId::new("indexer-a")).unwrap();
+
+        let manifest = Manifest { epoch: lease.epoch, parts: vec!["part-0".to_string()], ..Default::default() };
+        manifest.save_if_match(&store, "docs", None).unwrap();
+
+        let err = rebuild_combined(
+            &store,
+            &lease,
+            || Ok(vec!["vector-part-1".to_string()]),
+            || Err(crate::error::CoreError::LeaseLost(None)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, crate::error::CoreError::LeaseLost(_)));
+
+        let view = ManifestView::load(&store, "docs").unwrap();
+        assert_eq!(view.manifest.parts, vec!["part-0".to_string()]);
+    }
+}
@@ -0,0 +1,76 @@
+//! `distinct_on`: at most one hit per attribute value, so e.g. chunked
+//! documents don't crowd out every other result with five near-duplicate
+//! passages from the same source.
+
+use std::collections::{HashMap, HashSet};
+
+use elax_index::ScoredRow;
+
+/// Walk `results` (assumed already sorted best-first, as every search path
+/// returns them) and keep accumulating until `top_k` hits with *distinct*
+/// `attributes[field]` values have been collected, dropping any row whose
+/// value has already been kept. Doing this during accumulation rather than
+/// after truncating to `top_k` first means a later, lower-scored distinct
+/// hit never gets discarded just because an earlier duplicate took its
+/// slot. Rows missing `field` are never deduplicated against each other.
+pub fn distinct_on(
+    results: Vec<ScoredRow>,
+    field: &str,
+    attributes: &HashMap<String, serde_json::Value>,
+    top_k: usize,
+) -> Vec<ScoredRow> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(top_k.min(results.len()));
+    for row in results {
+        if out.len() >= top_k {
+            break;
+        }
+        let key = match attributes.get(&row.id).and_then(|v| v.get(field)) {
+            Some(value) => value.to_string(),
+            None => format!("__no_value:{}", row.id),
+        };
+        if seen.insert(key) {
+            out.push(row);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_best_hit_per_distinct_value() {
+        let results = vec![
+            ScoredRow { id: "a1".to_string(), score: 0.9 },
+            ScoredRow { id: "a2".to_string(), score: 0.8 },
+            ScoredRow { id: "b1".to_string(), score: 0.7 },
+        ];
+        let mut attributes = HashMap::new();
+        attributes.insert("a1".to_string(), serde_json::json!({"source": "doc-a"}));
+        attributes.insert("a2".to_string(), serde_json::json!({"source": "doc-a"}));
+        attributes.insert("b1".to_string(), serde_json::json!({"source": "doc-b"}));
+
+        let deduped = distinct_on(results, "source", &attributes, 10);
+        let ids: Vec<&str> = deduped.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "b1"]);
+    }
+
+    #[test]
+    fn does_not_drop_valid_later_results_for_an_early_cap() {
+        let results = vec![
+            ScoredRow { id: "a1".to_string(), score: 0.9 },
+            ScoredRow { id: "a2".to_string(), score: 0.8 },
+            ScoredRow { id: "b1".to_string(), score: 0.1 },
+        ];
+        let mut attributes = HashMap::new();
+        attributes.insert("a1".to_string(), serde_json::json!({"source": "doc-a"}));
+        attributes.insert("a2".to_string(), serde_json::json!({"source": "doc-a"}));
+        attributes.insert("b1".to_string(), serde_json::json!({"source": "doc-b"}));
+
+        let deduped = distinct_on(results, "source", &attributes, 2);
+        let ids: Vec<&str> = deduped.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "b1"]);
+    }
+}
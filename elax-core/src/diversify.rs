@@ -0,0 +1,91 @@
+//! Maximal Marginal Relevance: trade a little relevance for variety so RAG
+//! consumers don't get five near-duplicate passages from the same cluster.
+
+use std::collections::HashMap;
+
+use elax_index::ScoredRow;
+
+fn cosine_similarity(vectors: &HashMap<String, Vec<f32>>, a: &str, b: &str) -> f32 {
+    let (Some(va), Some(vb)) = (vectors.get(a), vectors.get(b)) else {
+        return 0.0;
+    };
+    let norm_a = va.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = vb.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    elax_index::score(va, vb) / (norm_a * norm_b)
+}
+
+/// Greedily re-select from the best `candidate_pool` of `results`, at each
+/// step picking whichever remaining candidate maximizes
+/// `lambda * relevance - (1 - lambda) * max_similarity_to_already_selected`,
+/// until `top_k` have been chosen. `lambda = 1.0` reduces to plain
+/// relevance ranking; lower `lambda` favors variety over score. Candidates
+/// missing a vector in `vectors` are treated as maximally dissimilar to
+/// everything (similarity 0).
+pub fn mmr_select(
+    results: Vec<ScoredRow>,
+    vectors: &HashMap<String, Vec<f32>>,
+    lambda: f32,
+    candidate_pool: usize,
+    top_k: usize,
+) -> Vec<ScoredRow> {
+    let mut remaining: Vec<ScoredRow> = results.into_iter().take(candidate_pool).collect();
+    let mut selected: Vec<ScoredRow> = Vec::with_capacity(top_k.min(remaining.len()));
+
+    while selected.len() < top_k && !remaining.is_empty() {
+        let mut best_idx = 0;
+        let mut best_mmr_score = f32::NEG_INFINITY;
+        for (i, candidate) in remaining.iter().enumerate() {
+            let max_similarity = selected
+                .iter()
+                .map(|s| cosine_similarity(vectors, &candidate.id, &s.id))
+                .fold(0.0f32, f32::max);
+            let mmr_score = lambda * candidate.score - (1.0 - lambda) * max_similarity;
+            if mmr_score > best_mmr_score {
+                best_mmr_score = mmr_score;
+                best_idx = i;
+            }
+        }
+        selected.push(remaining.remove(best_idx));
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lambda_one_reduces_to_plain_relevance_order() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: 0.9 },
+            ScoredRow { id: "b".to_string(), score: 0.8 },
+        ];
+        let mut vectors = HashMap::new();
+        vectors.insert("a".to_string(), vec![1.0, 0.0]);
+        vectors.insert("b".to_string(), vec![1.0, 0.0]);
+
+        let selected = mmr_select(results, &vectors, 1.0, 10, 2);
+        assert_eq!(selected[0].id, "a");
+        assert_eq!(selected[1].id, "b");
+    }
+
+    #[test]
+    fn prefers_a_dissimilar_candidate_over_a_near_duplicate() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: 0.9 },
+            ScoredRow { id: "a_dup".to_string(), score: 0.85 },
+            ScoredRow { id: "c".to_string(), score: 0.5 },
+        ];
+        let mut vectors = HashMap::new();
+        vectors.insert("a".to_string(), vec![1.0, 0.0]);
+        vectors.insert("a_dup".to_string(), vec![1.0, 0.0001]);
+        vectors.insert("c".to_string(), vec![0.0, 1.0]);
+
+        let selected = mmr_select(results, &vectors, 0.3, 10, 2);
+        let ids: Vec<&str> = selected.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::embedder::EmbeddingModel;
+
+/// An upsert-time request to compute `vector` from raw text via the
+/// namespace's configured [`crate::embedder::Embedder`], instead of the
+/// caller supplying floats directly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingHook {
+    pub text: String,
+    pub model: String,
+}
+
+/// A single row stored in a namespace: a vector plus arbitrary attributes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    pub id: String,
+    #[serde(default)]
+    pub vector: Vec<f32>,
+    #[serde(default)]
+    pub attributes: serde_json::Value,
+    #[serde(default)]
+    pub embedding: Option<EmbeddingHook>,
+    /// Which model produced `vector`, if the writer declared one. Checked
+    /// against the namespace's configured model by
+    /// [`crate::registry::NamespaceRegistry::apply_write`].
+    #[serde(default)]
+    pub embedding_model: Option<EmbeddingModel>,
+}
+
+impl Document {
+    pub fn new(id: impl Into<String>, vector: Vec<f32>) -> Self {
+        Self {
+            id: id.into(),
+            vector,
+            attributes: serde_json::Value::Null,
+            embedding: None,
+            embedding_model: None,
+        }
+    }
+
+    /// A document written with raw text instead of a precomputed vector;
+    /// the vector is filled in from `hook` by
+    /// [`crate::registry::NamespaceRegistry::apply_write`] before the WAL
+    /// append.
+    pub fn with_embedding(id: impl Into<String>, hook: EmbeddingHook) -> Self {
+        Self {
+            id: id.into(),
+            vector: Vec::new(),
+            attributes: serde_json::Value::Null,
+            embedding: Some(hook),
+            embedding_model: None,
+        }
+    }
+
+    /// The `expires_at` attribute, if present, as unix epoch seconds.
+    pub fn expires_at(&self) -> Option<u64> {
+        self.attributes.get("expires_at")?.as_u64()
+    }
+
+    /// Whether this document's TTL, if it has one, has passed `now`. Rows
+    /// without an `expires_at` attribute never expire.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        match self.expires_at() {
+            Some(expires_at) => now
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_secs() >= expires_at)
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn doc_expiring_at(epoch_secs: u64) -> Document {
+        Document {
+            id: "a".to_string(),
+            vector: vec![],
+            attributes: serde_json::json!({"expires_at": epoch_secs}),
+            embedding: None,
+            embedding_model: None,
+        }
+    }
+
+    #[test]
+    fn document_without_expires_at_never_expires() {
+        let doc = Document::new("a", vec![1.0]);
+        assert!(!doc.is_expired(SystemTime::now()));
+    }
+
+    #[test]
+    fn document_expires_once_now_passes_expires_at() {
+        let doc = doc_expiring_at(1_000);
+        assert!(!doc.is_expired(UNIX_EPOCH + Duration::from_secs(999)));
+        assert!(doc.is_expired(UNIX_EPOCH + Duration::from_secs(1_000)));
+        assert!(doc.is_expired(UNIX_EPOCH + Duration::from_secs(1_001)));
+    }
+}
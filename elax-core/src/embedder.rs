@@ -0,0 +1,113 @@
+//! Pluggable text-to-vector embedding, used both at upsert time (see
+//! [`crate::registry::NamespaceRegistry::apply_write`]) and, later, for
+//! query-time `ANN_TEXT` clauses.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// Computes an embedding vector for a piece of text. Implementations may
+/// call out to an external model-serving endpoint (see [`HttpEmbedder`]) or
+/// be swapped for a deterministic stub in tests.
+pub trait Embedder: Send + Sync {
+    fn embed(&self, text: &str, model: &str) -> Result<Vec<f32>>;
+}
+
+/// Identifies which embedding model produced a vector: its name, the
+/// dimension it emits, the metric it was tuned for, and whether it hands
+/// back unit-normalized vectors. Declared once in
+/// [`crate::registry::NamespaceConfig::embedding_model`] and optionally
+/// stamped on a [`crate::document::Document`] by the writer, so
+/// [`crate::registry::NamespaceRegistry::check_embedding_model`] can reject
+/// a write or query carrying a different model than the namespace expects,
+/// rather than silently returning garbage scores after a model upgrade.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingModel {
+    pub name: String,
+    pub dimension: usize,
+    pub metric: crate::tiered::Metric,
+    pub normalized: bool,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequestBody<'a> {
+    model: &'a str,
+    input: &'a str,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponseBody {
+    data: Vec<EmbeddingDatum>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingDatum {
+    embedding: Vec<f32>,
+}
+
+/// Calls an OpenAI-compatible `/v1/embeddings` endpoint over plain
+/// HTTP/1.1, using only `std::net` rather than pulling in an async HTTP
+/// client for what is, per call, a single blocking POST.
+#[derive(Debug, Clone)]
+pub struct HttpEmbedder {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+    pub api_key: Option<String>,
+}
+
+impl HttpEmbedder {
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            path: "/v1/embeddings".to_string(),
+            api_key: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+impl Embedder for HttpEmbedder {
+    fn embed(&self, text: &str, model: &str) -> Result<Vec<f32>> {
+        let body = serde_json::to_vec(&EmbeddingRequestBody { model, input: text })?;
+
+        let mut head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.path,
+            self.host,
+            body.len()
+        );
+        if let Some(api_key) = &self.api_key {
+            head.push_str(&format!("Authorization: Bearer {api_key}\r\n"));
+        }
+        head.push_str("\r\n");
+
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&body)?;
+
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+
+        let response = String::from_utf8_lossy(&raw);
+        let payload = response
+            .split("\r\n\r\n")
+            .nth(1)
+            .ok_or_else(|| CoreError::EmbeddingFailed("malformed HTTP response".to_string()))?;
+        let parsed: EmbeddingResponseBody = serde_json::from_str(payload)?;
+        parsed
+            .data
+            .into_iter()
+            .next()
+            .map(|d| d.embedding)
+            .ok_or_else(|| CoreError::EmbeddingFailed("empty embedding response".to_string()))
+    }
+}
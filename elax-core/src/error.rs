@@ -0,0 +1,72 @@
+use thiserror::Error;
+
+/// Errors surfaced by the namespace runtime and storage layer.
+#[derive(Debug, Error)]
+pub enum CoreError {
+    #[error("namespace not found: {0}")]
+    NamespaceNotFound(String),
+
+    #[error("row {id:?} not found in namespace {namespace:?}")]
+    RowNotFound { namespace: String, id: String },
+
+    #[error("stale epoch: got {got}, current is {current}")]
+    StaleEpoch { got: u64, current: u64 },
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("store error: {0}")]
+    Store(#[from] elax_store::StoreError),
+
+    #[error("cache error: {0}")]
+    Cache(#[from] elax_cache::CacheError),
+
+    #[error("index error: {0}")]
+    Index(#[from] elax_index::IndexError),
+
+    #[error("lease held by another node: {0:?}")]
+    LeaseLost(Option<crate::router::NodeId>),
+
+    #[error("quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    #[error("vector dimension mismatch: namespace expects {expected}, got {found}")]
+    DimensionMismatch { expected: usize, found: usize },
+
+    #[error("embedding failed: {0}")]
+    EmbeddingFailed(String),
+
+    #[error("attribute {key:?} is {found}, but the namespace schema already has it as {expected}")]
+    AttrTypeMismatch {
+        key: String,
+        expected: crate::attr_schema::AttrType,
+        found: crate::attr_schema::AttrType,
+    },
+
+    #[error("malformed consistency token: {0:?}")]
+    InvalidConsistencyToken(String),
+
+    #[error("stale read: namespace {namespace:?} needs epoch {needed}, only caught up to {have}")]
+    StaleRead { namespace: String, needed: u64, have: u64 },
+
+    #[error("embedding model mismatch: namespace {namespace:?} expects {expected:?}, got {found:?}")]
+    EmbeddingModelMismatch {
+        namespace: String,
+        expected: crate::embedder::EmbeddingModel,
+        found: crate::embedder::EmbeddingModel,
+    },
+
+    #[error("document id is required: namespace's id_strategy is ClientSupplied")]
+    MissingDocumentId,
+
+    #[error("vector rejected by ingest pipeline: {0}")]
+    InvalidVector(String),
+
+    #[error("view {view:?} not found on namespace {namespace:?}")]
+    ViewNotFound { namespace: String, view: String },
+}
+
+pub type Result<T> = std::result::Result<T, CoreError>;
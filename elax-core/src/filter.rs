@@ -0,0 +1,137 @@
+//! A small boolean filter language over [`crate::document::Document`]
+//! attributes, used by delete-by-query and update-by-query so callers can
+//! describe "which documents" without scanning the namespace themselves.
+
+use crate::document::Document;
+
+/// A predicate over one document's attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    /// `attributes[key] == value`. `key` is a dot-path (`"a.b"`) that may
+    /// step into an array with a trailing `[]` segment (`"tags[].name"`),
+    /// in which case the clause matches if any element satisfies it.
+    AttrEq { key: String, value: serde_json::Value },
+    /// `attributes[key] > value`, numeric comparison only. Same path
+    /// syntax as [`Self::AttrEq`].
+    AttrGt { key: String, value: f64 },
+    /// `attributes[key] < value`, numeric comparison only. Same path
+    /// syntax as [`Self::AttrEq`].
+    AttrLt { key: String, value: f64 },
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+/// Resolve a dot-path against `value`, returning every value it reaches.
+/// A plain segment (`"a"`) steps into an object field; a segment ending in
+/// `[]` (`"tags[]"`) steps into an array field and fans out to every
+/// element, so the rest of the path is resolved against each of them —
+/// that's how `"tags[].name"` ends up checking every tag's `name` rather
+/// than just the first.
+fn resolve_path<'a>(value: &'a serde_json::Value, path: &str) -> Vec<&'a serde_json::Value> {
+    let mut current = vec![value];
+    for segment in path.split('.') {
+        let (key, is_array) = match segment.strip_suffix("[]") {
+            Some(key) => (key, true),
+            None => (segment, false),
+        };
+        let mut next = Vec::new();
+        for v in current {
+            let Some(field) = v.get(key) else { continue };
+            if is_array {
+                if let Some(elements) = field.as_array() {
+                    next.extend(elements.iter());
+                }
+            } else {
+                next.push(field);
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+impl FilterExpr {
+    pub fn matches(&self, doc: &Document) -> bool {
+        match self {
+            FilterExpr::AttrEq { key, value } => {
+                resolve_path(&doc.attributes, key).contains(&value)
+            }
+            FilterExpr::AttrGt { key, value } => resolve_path(&doc.attributes, key)
+                .iter()
+                .any(|v| v.as_f64().is_some_and(|v| v > *value)),
+            FilterExpr::AttrLt { key, value } => resolve_path(&doc.attributes, key)
+                .iter()
+                .any(|v| v.as_f64().is_some_and(|v| v < *value)),
+            FilterExpr::And(clauses) => clauses.iter().all(|c| c.matches(doc)),
+            FilterExpr::Or(clauses) => clauses.iter().any(|c| c.matches(doc)),
+            FilterExpr::Not(inner) => !inner.matches(doc),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc_with(attrs: serde_json::Value) -> Document {
+        Document {
+            id: "a".to_string(),
+            vector: vec![],
+            attributes: attrs,
+            embedding: None,
+            embedding_model: None,
+        }
+    }
+
+    #[test]
+    fn attr_eq_matches_only_equal_values() {
+        let expr = FilterExpr::AttrEq {
+            key: "status".to_string(),
+            value: json!("archived"),
+        };
+        assert!(expr.matches(&doc_with(json!({"status": "archived"}))));
+        assert!(!expr.matches(&doc_with(json!({"status": "active"}))));
+    }
+
+    #[test]
+    fn and_or_not_compose() {
+        let expr = FilterExpr::And(vec![
+            FilterExpr::AttrGt {
+                key: "score".to_string(),
+                value: 0.5,
+            },
+            FilterExpr::Not(Box::new(FilterExpr::AttrEq {
+                key: "pinned".to_string(),
+                value: json!(true),
+            })),
+        ]);
+        assert!(expr.matches(&doc_with(json!({"score": 0.9, "pinned": false}))));
+        assert!(!expr.matches(&doc_with(json!({"score": 0.9, "pinned": true}))));
+        assert!(!expr.matches(&doc_with(json!({"score": 0.1, "pinned": false}))));
+    }
+
+    #[test]
+    fn nested_dot_path_steps_into_objects() {
+        let expr = FilterExpr::AttrEq {
+            key: "author.name".to_string(),
+            value: json!("ada"),
+        };
+        assert!(expr.matches(&doc_with(json!({"author": {"name": "ada"}}))));
+        assert!(!expr.matches(&doc_with(json!({"author": {"name": "grace"}}))));
+    }
+
+    #[test]
+    fn array_wildcard_matches_if_any_element_matches() {
+        let expr = FilterExpr::AttrEq {
+            key: "tags[].name".to_string(),
+            value: json!("rust"),
+        };
+        let doc = doc_with(json!({"tags": [{"name": "go"}, {"name": "rust"}]}));
+        assert!(expr.matches(&doc));
+
+        let doc = doc_with(json!({"tags": [{"name": "go"}, {"name": "python"}]}));
+        assert!(!expr.matches(&doc));
+    }
+}
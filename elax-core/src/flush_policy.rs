@@ -0,0 +1,116 @@
+//! Decides when an indexer pass should cut the next part off the WAL tail
+//! it's accumulated so far, instead of always waiting for a fixed row
+//! count. Row count alone (the pre-existing behavior) treats every
+//! document as the same size, so a namespace with large vectors or big
+//! attribute payloads can produce parts far bigger than a namespace with
+//! small ones. [`crate::registry::NamespaceRegistry::effective_flush_policy`]
+//! resolves the per-namespace override the same way
+//! [`crate::registry::NamespaceRegistry::effective_ann_params`] does.
+
+use std::time::SystemTime;
+
+/// Bounds on how much WAL backlog an indexer pass accumulates before it's
+/// worth cutting a part. `None` on a field leaves that dimension
+/// unbounded, the same convention as [`crate::registry::Quota`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlushPolicy {
+    pub max_rows_per_part: Option<usize>,
+    pub max_bytes_per_part: Option<u64>,
+    /// Oldest a WAL record accumulated into the pending part may get before
+    /// it's cut regardless of size, so a slow-trickling namespace doesn't
+    /// leave writes unindexed indefinitely.
+    pub max_wal_age_secs: Option<u64>,
+}
+
+impl Default for FlushPolicy {
+    fn default() -> Self {
+        Self {
+            max_rows_per_part: Some(100_000),
+            max_bytes_per_part: None,
+            max_wal_age_secs: None,
+        }
+    }
+}
+
+/// Whether the WAL tail accumulated so far (`rows` records, an estimated
+/// `bytes` of vector+attribute payload, oldest one written at
+/// `oldest_record_at`) has outgrown `policy` and should be cut into a part.
+pub fn should_flush(
+    rows: usize,
+    bytes: u64,
+    oldest_record_at: SystemTime,
+    now: SystemTime,
+    policy: &FlushPolicy,
+) -> bool {
+    if let Some(max_rows) = policy.max_rows_per_part {
+        if rows >= max_rows {
+            return true;
+        }
+    }
+    if let Some(max_bytes) = policy.max_bytes_per_part {
+        if bytes >= max_bytes {
+            return true;
+        }
+    }
+    if let Some(max_age_secs) = policy.max_wal_age_secs {
+        let age_secs = now.duration_since(oldest_record_at).map(|d| d.as_secs()).unwrap_or(0);
+        if age_secs >= max_age_secs {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn does_not_flush_below_every_threshold() {
+        let policy = FlushPolicy {
+            max_rows_per_part: Some(1_000),
+            max_bytes_per_part: Some(1_000_000),
+            max_wal_age_secs: Some(60),
+        };
+        let now = SystemTime::now();
+        assert!(!should_flush(10, 1_000, now, now, &policy));
+    }
+
+    #[test]
+    fn flushes_once_row_count_crosses_the_threshold() {
+        let policy = FlushPolicy { max_rows_per_part: Some(1_000), ..Default::default() };
+        let now = SystemTime::now();
+        assert!(should_flush(1_000, 0, now, now, &policy));
+    }
+
+    #[test]
+    fn flushes_once_byte_usage_crosses_the_threshold() {
+        let policy = FlushPolicy { max_bytes_per_part: Some(1_000), ..Default::default() };
+        let now = SystemTime::now();
+        assert!(should_flush(0, 1_000, now, now, &policy));
+    }
+
+    #[test]
+    fn flushes_once_the_oldest_record_outlives_max_wal_age() {
+        let policy = FlushPolicy { max_rows_per_part: None, max_wal_age_secs: Some(60), ..Default::default() };
+        let oldest = SystemTime::now();
+        let now = oldest + Duration::from_secs(61);
+        assert!(should_flush(1, 0, oldest, now, &policy));
+    }
+
+    #[test]
+    fn a_fresh_wal_tail_is_not_flushed_on_age_alone() {
+        let policy = FlushPolicy { max_rows_per_part: None, max_wal_age_secs: Some(60), ..Default::default() };
+        let oldest = SystemTime::now();
+        let now = oldest + Duration::from_secs(1);
+        assert!(!should_flush(1, 0, oldest, now, &policy));
+    }
+
+    #[test]
+    fn a_fully_unconfigured_policy_never_flushes() {
+        let policy = FlushPolicy { max_rows_per_part: None, max_bytes_per_part: None, max_wal_age_secs: None };
+        let now = SystemTime::now();
+        assert!(!should_flush(usize::MAX, u64::MAX, now, now, &policy));
+    }
+}
@@ -0,0 +1,116 @@
+//! Server-assigned document ids, for writes that omit one. Only consulted
+//! by [`crate::registry::NamespaceRegistry::apply_write`] when
+//! [`crate::document::Document::id`] is empty — a client-supplied id is
+//! always used as-is, regardless of the namespace's configured strategy.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use uuid::Uuid;
+
+use crate::error::{CoreError, Result};
+
+/// How a namespace fills in `id` when a write omits it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdStrategy {
+    /// Reject writes with an empty id (the pre-existing behavior).
+    #[default]
+    ClientSupplied,
+    /// Random, time-ordered [UUID v7](https://www.rfc-editor.org/rfc/rfc9562#section-5.7).
+    UuidV7,
+    /// Twitter Snowflake-style: a 41-bit millisecond timestamp, a 10-bit
+    /// node id, and a 12-bit per-millisecond sequence, packed into one
+    /// `u64` and rendered as a decimal string — sortable like `UuidV7`, but
+    /// shorter.
+    Snowflake,
+}
+
+const SNOWFLAKE_EPOCH_MS: u64 = 1_700_000_000_000;
+const SEQUENCE_BITS: u32 = 12;
+const NODE_BITS: u32 = 10;
+const NODE_MASK: u64 = (1 << NODE_BITS) - 1;
+const SEQUENCE_MASK: u64 = (1 << SEQUENCE_BITS) - 1;
+
+/// Per-registry state backing [`IdStrategy::Snowflake`]: a stable node id
+/// plus the counter generated ids spend their low 12 bits on, so two ids
+/// minted in the same millisecond on the same node still sort distinctly.
+pub struct SnowflakeGenerator {
+    node_id: u64,
+    sequence: AtomicU64,
+}
+
+impl SnowflakeGenerator {
+    /// `node_id` is masked to the low 10 bits; callers running more than
+    /// one indexer should give each a distinct id (e.g. derived from
+    /// [`crate::router::NodeId`]) so their generated ids never collide.
+    pub fn new(node_id: u64) -> Self {
+        Self {
+            node_id: node_id & NODE_MASK,
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    fn next_id(&self) -> String {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before the unix epoch")
+            .as_millis() as u64;
+        let timestamp = now_ms.saturating_sub(SNOWFLAKE_EPOCH_MS);
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed) & SEQUENCE_MASK;
+        let id = (timestamp << (NODE_BITS + SEQUENCE_BITS)) | (self.node_id << SEQUENCE_BITS) | sequence;
+        id.to_string()
+    }
+}
+
+impl Default for SnowflakeGenerator {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Mint a fresh id under `strategy`, or fail with
+/// [`CoreError::MissingDocumentId`] for `ClientSupplied` — the caller is
+/// expected to only reach this once it already knows `doc.id` is empty.
+pub fn generate_id(strategy: IdStrategy, snowflake: &SnowflakeGenerator) -> Result<String> {
+    match strategy {
+        IdStrategy::ClientSupplied => Err(CoreError::MissingDocumentId),
+        IdStrategy::UuidV7 => Ok(Uuid::now_v7().to_string()),
+        IdStrategy::Snowflake => Ok(snowflake.next_id()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_supplied_strategy_refuses_to_generate() {
+        let snowflake = SnowflakeGenerator::default();
+        let err = generate_id(IdStrategy::ClientSupplied, &snowflake).unwrap_err();
+        assert!(matches!(err, CoreError::MissingDocumentId));
+    }
+
+    #[test]
+    fn uuid_v7_ids_are_unique_and_well_formed() {
+        let snowflake = SnowflakeGenerator::default();
+        let a = generate_id(IdStrategy::UuidV7, &snowflake).unwrap();
+        let b = generate_id(IdStrategy::UuidV7, &snowflake).unwrap();
+        assert_ne!(a, b);
+        assert!(Uuid::parse_str(&a).is_ok());
+    }
+
+    #[test]
+    fn snowflake_ids_are_monotonically_increasing_within_a_generator() {
+        let snowflake = SnowflakeGenerator::new(3);
+        let a: u64 = generate_id(IdStrategy::Snowflake, &snowflake).unwrap().parse().unwrap();
+        let b: u64 = generate_id(IdStrategy::Snowflake, &snowflake).unwrap().parse().unwrap();
+        assert!(b > a);
+    }
+
+    #[test]
+    fn snowflake_generators_with_different_node_ids_never_collide_in_the_same_millisecond() {
+        let one = SnowflakeGenerator::new(1);
+        let two = SnowflakeGenerator::new(2);
+        assert_ne!(one.next_id(), two.next_id());
+    }
+}
@@ -0,0 +1,110 @@
+//! Single-writer indexing loop, fenced by a lease over the router manifest
+//! so two indexer processes can never materialize parts for the same
+//! namespace concurrently.
+
+use elax_store::ObjectStore;
+
+use crate::error::{CoreError, Result};
+use crate::manifest::ManifestView;
+use crate::router::{NodeId, RouterState};
+
+/// Proof that `node` currently owns `namespace`, good until a newer epoch is
+/// published.
+#[derive(Debug, Clone)]
+pub struct Lease {
+    pub namespace: String,
+    pub node: NodeId,
+    pub epoch: u64,
+}
+
+/// Acquire (or renew) the lease on `namespace` for `node`. Fails if another
+/// node already owns the namespace.
+pub fn acquire_lease(store: &dyn ObjectStore, namespace: &str, node: NodeId) -> Result<Lease> {
+    let (mut state, generation) = RouterState::load(store)?;
+
+    if let Some(owner) = state.owner_of(namespace) {
+        if owner != &node {
+            return Err(CoreError::LeaseLost(Some(owner.clone())));
+        }
+    }
+
+    state.reassign(namespace, node.clone());
+    state.save_if_match(store, generation)?;
+
+    Ok(Lease {
+        namespace: namespace.to_string(),
+        node,
+        epoch: state.epoch,
+    })
+}
+
+/// Run one indexing pass for `lease.namespace`, materializing parts from the
+/// WAL tail. Fails fast if the lease has been superseded by a newer epoch
+/// before the pass can publish its results.
+pub fn run_once(store: &dyn ObjectStore, lease: &Lease) -> Result<()> {
+    let view = ManifestView::load(store, &lease.namespace)?;
+    if view.router_epoch != lease.epoch {
+        return Err(CoreError::LeaseLost(view.owner));
+    }
+
+    // ... build parts from the WAL tail (elided: covered by elax-index),
+    // cutting each part once `crate::flush_policy::should_flush` trips
+    // against the namespace's `NamespaceRegistry::effective_flush_policy`
+    // rather than always waiting for a fixed row count.
+    // A caller tracking this namespace's drift should check
+    // `NamespaceMetrics::drift`'s `should_retrain` around here and, if it
+    // returns true, also rebuild (not just refresh) the IVF/ERQ index
+    // before calling `DriftTracker::record_baseline` ...
+    let next_manifest = crate::manifest::Manifest {
+        epoch: view.manifest.epoch + 1,
+        parts: view.manifest.parts.clone(),
+        delete_parts: view.manifest.delete_parts.clone(),
+        fts_parts: view.manifest.fts_parts.clone(),
+        delete_fts_parts: view.manifest.delete_fts_parts.clone(),
+        key_id: view.manifest.key_id.clone(),
+    };
+    view.publish(store, lease, next_manifest)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elax_store::LocalStore;
+
+    fn tmp_store() -> LocalStore {
+        let dir = std::env::temp_dir().join(format!(
+            "elax-core-indexer-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        LocalStore::new(dir).unwrap()
+    }
+
+    #[test]
+    fn second_leader_cannot_steal_without_handoff() {
+        let store = tmp_store();
+        let lease_a = acquire_lease(&store, "docs", NodeId::new("a")).unwrap();
+        let err = acquire_lease(&store, "docs", NodeId::new("b")).unwrap_err();
+        assert!(matches!(err, CoreError::LeaseLost(Some(_))));
+        assert!(run_once(&store, &lease_a).is_ok());
+    }
+
+    #[test]
+    fn stale_leader_fails_fast_after_handoff() {
+        let store = tmp_store();
+        let lease_a = acquire_lease(&store, "docs", NodeId::new("a")).unwrap();
+
+        // Owner "a" explicitly hands the namespace to "b".
+        let (mut state, generation) = RouterState::load(&store).unwrap();
+        state.reassign("docs", NodeId::new("b"));
+        state.save_if_match(&store, generation).unwrap();
+
+        let err = run_once(&store, &lease_a).unwrap_err();
+        assert!(matches!(err, CoreError::LeaseLost(_)));
+    }
+}
@@ -0,0 +1,70 @@
+//! Core data model and namespace runtime for elacsym.
+
+pub mod attr_order;
+pub mod attr_schema;
+pub mod columnar;
+pub mod compaction;
+pub mod distinct;
+pub mod diversify;
+pub mod document;
+pub mod embedder;
+pub mod error;
+pub mod filter;
+pub mod flush_policy;
+pub mod id_gen;
+pub mod indexer;
+pub mod manifest;
+pub mod metrics;
+pub mod namespace;
+pub mod notifier;
+pub mod pipeline;
+pub mod query_cache;
+pub mod query_log;
+pub mod registry;
+pub mod replication;
+pub mod reranker;
+pub mod router;
+pub mod scoring;
+pub mod semantic_cache;
+pub mod settings;
+pub mod sharding;
+pub mod text_expansion;
+pub mod tiered;
+pub mod view;
+
+pub use attr_order::{AttrOrder, NullsOrder, SortDirection};
+pub use attr_schema::{AttrSchema, AttrType};
+pub use columnar::AttributeColumns;
+pub use compaction::{compact_namespace, rebuild_combined};
+pub use distinct::distinct_on;
+pub use diversify::mmr_select;
+pub use document::{Document, EmbeddingHook};
+pub use embedder::{Embedder, EmbeddingModel, HttpEmbedder};
+pub use error::CoreError;
+pub use filter::FilterExpr;
+pub use flush_policy::{should_flush, FlushPolicy};
+pub use id_gen::{IdStrategy, SnowflakeGenerator};
+pub use indexer::{acquire_lease, run_once, Lease};
+pub use manifest::{Manifest, ManifestView};
+pub use metrics::{DriftTracker, MetricsRegistry, NamespaceMetrics, SelectivityTracker};
+pub use namespace::Namespace;
+pub use notifier::{DeliveryMetrics, HttpWebhook, Notifier, WebhookDispatcher, WebhookEvent};
+pub use pipeline::{run_pipeline, PipelineStep};
+pub use query_cache::QueryEmbeddingCache;
+pub use query_log::{hash_vector, replay, should_sample, QueryLog, QueryLogConfig, QueryLogEntry, ReplayHit};
+pub use registry::{
+    NamespaceConfig, NamespaceRegistry, NamespaceStats, QueryCursor, QueryFilter, QueryPage, Quota, WalLoadOptions,
+};
+pub use replication::{catch_up, prefetch, warm_namespace, ConsistencyToken, FollowerState, ManifestWatcher};
+pub use reranker::{rerank_top_n, HttpReranker, RerankCandidate, RerankConfig, Reranker};
+pub use router::{NodeId, RouterState};
+pub use scoring::{apply_boosts, rescore, Boost, BoostOp, ScoreExpr};
+pub use semantic_cache::{SemanticCacheMetrics, SemanticQueryCache};
+pub use settings::{AnnParams, DriftThresholds, IndexerThresholds, RateLimit, RuntimeSettings, SettingsHandle};
+pub use sharding::{shard_for_id, should_shard, ShardingPolicy};
+pub use text_expansion::{combine_field_scores, Bm25Params, BoostedTerm, FieldKind, LanguagePackConfig, TextSearchConfig};
+pub use tiered::{
+    search_across, search_sharded, ClauseExplain, Metric, NamespaceHit, PlanHint, QueryClause, RankBy, TieredNamespace,
+    VectorBatch,
+};
+pub use view::ViewConfig;
@@ -0,0 +1,166 @@
+use elax_store::{Generation, ObjectStore};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+use crate::indexer::Lease;
+use crate::router::{NodeId, RouterState};
+
+/// The set of immutable part assets that make up a namespace's on-disk
+/// state as of `epoch`. Followers replicate by diffing this against what
+/// they already hold in cache.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub epoch: u64,
+    pub parts: Vec<String>,
+    /// Parts superseded by a later compaction, kept listed here until
+    /// physically removed — followers that already downloaded them should
+    /// drop them from cache rather than treat them as still live.
+    #[serde(default)]
+    pub delete_parts: Vec<String>,
+    /// The FTS index's part assets, published in the same manifest epoch as
+    /// `parts` whenever [`crate::compaction::rebuild_combined`] rebuilds
+    /// both indexes together — so a query never observes one rebuilt and
+    /// the other stale. Empty when a namespace has no FTS index built yet
+    /// (the pre-existing behavior, since nothing writes this field outside
+    /// `rebuild_combined`).
+    #[serde(default)]
+    pub fts_parts: Vec<String>,
+    /// `fts_parts`' analog of `delete_parts`.
+    #[serde(default)]
+    pub delete_fts_parts: Vec<String>,
+    /// The id of the key `parts` were encrypted under, if this namespace's
+    /// store is wrapped in an [`elax_store::EncryptingStore`]. Surfaced so
+    /// operators and key-rotation tooling can tell which parts still need
+    /// re-encrypting under a newer key without decrypting each one to
+    /// check; decryption itself resolves the key from the ciphertext
+    /// envelope, not from this field.
+    #[serde(default)]
+    pub key_id: Option<String>,
+}
+
+fn manifest_key(namespace: &str) -> String {
+    format!("manifests/{namespace}.json")
+}
+
+impl Manifest {
+    pub fn load(store: &dyn ObjectStore, namespace: &str) -> Result<(Manifest, Option<Generation>)> {
+        match store.get(&manifest_key(namespace))? {
+            Some((bytes, generation)) => {
+                let manifest: Manifest = serde_json::from_slice(&bytes)?;
+                Ok((manifest, Some(generation)))
+            }
+            None => Ok((Manifest::default(), None)),
+        }
+    }
+
+    pub fn save_if_match(
+        &self,
+        store: &dyn ObjectStore,
+        namespace: &str,
+        expected_generation: Option<Generation>,
+    ) -> Result<Generation> {
+        let bytes = serde_json::to_vec(self)?;
+        Ok(store.put_if_match(&manifest_key(namespace), expected_generation, bytes)?)
+    }
+}
+
+/// A single consistent snapshot of a namespace's state, combining who owns
+/// it ([`RouterState`]) with what it physically consists of ([`Manifest`]) —
+/// the one thing query nodes and indexers both load instead of each
+/// re-deriving namespace state their own way (the router by scanning
+/// assignments, the indexer by re-walking the WAL).
+#[derive(Debug, Clone)]
+pub struct ManifestView {
+    pub namespace: String,
+    pub owner: Option<NodeId>,
+    pub router_epoch: u64,
+    pub manifest: Manifest,
+    manifest_generation: Option<Generation>,
+}
+
+impl ManifestView {
+    /// Load the current router assignment and manifest for `namespace` in
+    /// one call, so callers never act on a router epoch and a manifest that
+    /// were read at different points in time.
+    pub fn load(store: &dyn ObjectStore, namespace: &str) -> Result<ManifestView> {
+        let (state, _router_generation) = RouterState::load(store)?;
+        let (manifest, manifest_generation) = Manifest::load(store, namespace)?;
+        Ok(ManifestView {
+            namespace: namespace.to_string(),
+            owner: state.owner_of(namespace).cloned(),
+            router_epoch: state.epoch,
+            manifest,
+            manifest_generation,
+        })
+    }
+
+    /// Publish `manifest` as the namespace's new state, conditioned on two
+    /// things holding since this view was loaded: `lease` still matches the
+    /// router epoch (no handoff happened underneath the indexer), and the
+    /// manifest generation this view read is still current (no concurrent
+    /// publisher raced it). Returns the new manifest generation.
+    pub fn publish(&self, store: &dyn ObjectStore, lease: &Lease, manifest: Manifest) -> Result<Generation> {
+        if lease.namespace != self.namespace {
+            return Err(CoreError::LeaseLost(self.owner.clone()));
+        }
+        let (state, _generation) = RouterState::load(store)?;
+        if state.epoch != lease.epoch {
+            return Err(CoreError::LeaseLost(state.owner_of(&self.namespace).cloned()));
+        }
+        manifest.save_if_match(store, &self.namespace, self.manifest_generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::acquire_lease;
+    use elax_store::LocalStore;
+
+    fn tmp_store() -> LocalStore {
+        LocalStore::new(std::env::temp_dir().join(format!(
+            "elax-core-manifest-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        )))
+        .unwrap()
+    }
+
+    #[test]
+    fn publish_succeeds_while_the_lease_still_matches_the_router_epoch() {
+        let store = tmp_store();
+        let lease = acquire_lease(&store, "docs", NodeId::new("a")).unwrap();
+
+        let view = ManifestView::load(&store, "docs").unwrap();
+        assert_eq!(view.owner, Some(NodeId::new("a")));
+
+        let manifest = Manifest {
+            epoch: 1,
+            parts: vec!["part-0.bin".to_string()],
+            delete_parts: vec![],
+            key_id: None,
+            ..Default::default()
+        };
+        view.publish(&store, &lease, manifest).unwrap();
+
+        let reloaded = ManifestView::load(&store, "docs").unwrap();
+        assert_eq!(reloaded.manifest.parts, vec!["part-0.bin".to_string()]);
+    }
+
+    #[test]
+    fn publish_fails_once_the_router_has_handed_off_to_another_node() {
+        let store = tmp_store();
+        let lease = acquire_lease(&store, "docs", NodeId::new("a")).unwrap();
+        let view = ManifestView::load(&store, "docs").unwrap();
+
+        let (mut state, generation) = RouterState::load(&store).unwrap();
+        state.reassign("docs", NodeId::new("b"));
+        state.save_if_match(&store, generation).unwrap();
+
+        let err = view.publish(&store, &lease, Manifest::default()).unwrap_err();
+        assert!(matches!(err, CoreError::LeaseLost(_)));
+    }
+}
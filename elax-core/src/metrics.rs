@@ -0,0 +1,324 @@
+//! Per-namespace histograms for ANN internals, so operators can diagnose
+//! recall/latency issues from metrics alone instead of ad hoc tracing.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::settings::DriftThresholds;
+
+/// A fixed-bucket histogram (upper-bound buckets, Prometheus-style).
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    counts: Vec<AtomicU64>,
+}
+
+impl Histogram {
+    pub fn new(bounds: Vec<f64>) -> Self {
+        let counts = bounds.iter().map(|_| AtomicU64::new(0)).collect();
+        Self { bounds, counts }
+    }
+
+    pub fn observe(&self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            if value <= *bound {
+                count.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        }
+        // Above the last bound: still counted in the +Inf bucket via the
+        // last entry so totals stay consistent.
+        if let Some(last) = self.counts.last() {
+            last.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// (upper_bound, cumulative_count) pairs, in bucket order.
+    pub fn snapshot(&self) -> Vec<(f64, u64)> {
+        let mut running = 0;
+        self.bounds
+            .iter()
+            .zip(&self.counts)
+            .map(|(b, c)| {
+                running += c.load(Ordering::Relaxed);
+                (*b, running)
+            })
+            .collect()
+    }
+}
+
+/// Running estimate of what fraction of rows a namespace's filters tend to
+/// match, learned from observed query results instead of a fixed guess.
+/// Until enough samples have accumulated it falls back to `0.2`, a
+/// deliberately conservative bootstrap value.
+#[derive(Debug)]
+pub struct SelectivityTracker {
+    total_rows: AtomicU64,
+    matched_rows: AtomicU64,
+}
+
+impl SelectivityTracker {
+    pub fn new() -> Self {
+        Self {
+            total_rows: AtomicU64::new(0),
+            matched_rows: AtomicU64::new(0),
+        }
+    }
+
+    /// Record that a filter matched `matched` of `total` candidate rows.
+    pub fn observe(&self, total: usize, matched: usize) {
+        self.total_rows.fetch_add(total as u64, Ordering::Relaxed);
+        self.matched_rows.fetch_add(matched as u64, Ordering::Relaxed);
+    }
+
+    /// The cumulative matched/total ratio observed so far, or `0.2` if
+    /// nothing has been observed yet.
+    pub fn estimate(&self) -> f64 {
+        let total = self.total_rows.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.2;
+        }
+        self.matched_rows.load(Ordering::Relaxed) as f64 / total as f64
+    }
+}
+
+impl Default for SelectivityTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Running totals behind [`DriftTracker`], snapshotted by value so a
+/// drift check can compare a `baseline` copy against the still-live one
+/// without holding two locks at once.
+#[derive(Debug, Clone, Copy, Default)]
+struct RunningMeans {
+    count: u64,
+    norm_sum: f64,
+    residual_count: u64,
+    residual_sum: f64,
+}
+
+/// Running per-namespace statistics used to decide whether an IVF/ERQ
+/// index has drifted far enough from the data it was trained on to be
+/// worth retraining: the mean inserted-vector norm, and — once a caller
+/// has a trained index to measure against — the mean residual distance
+/// from each vector to its nearest centroid. `observe` is meant to run at
+/// write time; `should_retrain` compares the live running means against a
+/// baseline snapshot taken at the last retraining, so drift is judged
+/// relative to what the index actually saw, not a fixed absolute value.
+/// This crate has no scheduler that calls `observe`/`should_retrain` on
+/// its own — see the comment inside [`crate::indexer::run_once`] — so wiring
+/// this into a write path or an indexing loop is left to the caller.
+#[derive(Debug, Default)]
+pub struct DriftTracker {
+    live: Mutex<RunningMeans>,
+    baseline: Mutex<Option<RunningMeans>>,
+    retrain_requested: AtomicBool,
+}
+
+impl DriftTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold one inserted vector's norm (and, if a trained index is
+    /// available to measure against, its distance to the nearest
+    /// centroid) into the running statistics.
+    pub fn observe(&self, norm: f32, nearest_centroid_distance: Option<f32>) {
+        let mut live = self.live.lock().unwrap();
+        live.count += 1;
+        live.norm_sum += norm as f64;
+        if let Some(distance) = nearest_centroid_distance {
+            live.residual_count += 1;
+            live.residual_sum += distance as f64;
+        }
+    }
+
+    /// Snapshot the current running statistics as the new baseline —
+    /// call this right after a retraining pass completes, so subsequent
+    /// drift is measured against what the index was just trained on. Also
+    /// clears any pending [`Self::request_retrain`] override.
+    pub fn record_baseline(&self) {
+        let live = *self.live.lock().unwrap();
+        *self.baseline.lock().unwrap() = Some(live);
+        self.retrain_requested.store(false, Ordering::Relaxed);
+    }
+
+    /// Force the next [`Self::should_retrain`] check to return `true`
+    /// regardless of measured drift — an admin override for cases that
+    /// warrant a retrain without showing up in these statistics, e.g. a
+    /// deliberate embedding model migration.
+    pub fn request_retrain(&self) {
+        self.retrain_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether accumulated drift (or [`Self::request_retrain`]) warrants
+    /// scheduling a retraining pass. Before any baseline has been
+    /// recorded, only the admin override can trigger this.
+    pub fn should_retrain(&self, thresholds: DriftThresholds) -> bool {
+        if self.retrain_requested.load(Ordering::Relaxed) {
+            return true;
+        }
+        let Some(baseline) = *self.baseline.lock().unwrap() else {
+            return false;
+        };
+        let live = *self.live.lock().unwrap();
+
+        if baseline.count > 0 && live.count > 0 {
+            let baseline_norm = baseline.norm_sum / baseline.count as f64;
+            let live_norm = live.norm_sum / live.count as f64;
+            if baseline_norm > 0.0 && relative_change(baseline_norm, live_norm) > thresholds.max_norm_drift as f64 {
+                return true;
+            }
+        }
+
+        if baseline.residual_count > 0 && live.residual_count > 0 {
+            let baseline_residual = baseline.residual_sum / baseline.residual_count as f64;
+            let live_residual = live.residual_sum / live.residual_count as f64;
+            if baseline_residual > 0.0
+                && relative_change(baseline_residual, live_residual) > thresholds.max_residual_drift as f64
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+fn relative_change(baseline: f64, live: f64) -> f64 {
+    ((live - baseline) / baseline).abs()
+}
+
+fn default_count_buckets() -> Vec<f64> {
+    vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, f64::INFINITY]
+}
+
+fn default_duration_buckets() -> Vec<f64> {
+    vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, f64::INFINITY]
+}
+
+/// Histograms covering one namespace's ANN query and rebuild paths.
+pub struct NamespaceMetrics {
+    pub lists_probed: Histogram,
+    pub coarse_candidates: Histogram,
+    pub candidates_reranked: Histogram,
+    pub ivf_rebuild_seconds: Histogram,
+    pub fts_rebuild_seconds: Histogram,
+    pub filter_selectivity: SelectivityTracker,
+    pub drift: DriftTracker,
+}
+
+impl Default for NamespaceMetrics {
+    fn default() -> Self {
+        Self {
+            lists_probed: Histogram::new(default_count_buckets()),
+            coarse_candidates: Histogram::new(default_count_buckets()),
+            candidates_reranked: Histogram::new(default_count_buckets()),
+            ivf_rebuild_seconds: Histogram::new(default_duration_buckets()),
+            fts_rebuild_seconds: Histogram::new(default_duration_buckets()),
+            filter_selectivity: SelectivityTracker::new(),
+            drift: DriftTracker::new(),
+        }
+    }
+}
+
+/// Process-wide registry handing out (and caching) per-namespace metrics.
+#[derive(Default)]
+pub struct MetricsRegistry {
+    per_namespace: Mutex<HashMap<String, Arc<NamespaceMetrics>>>,
+}
+
+impl MetricsRegistry {
+    pub fn namespace(&self, name: &str) -> Arc<NamespaceMetrics> {
+        let mut guard = self.per_namespace.lock().unwrap();
+        guard
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(NamespaceMetrics::default()))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_cumulatively() {
+        let hist = Histogram::new(vec![1.0, 10.0, f64::INFINITY]);
+        hist.observe(0.5);
+        hist.observe(5.0);
+        hist.observe(1000.0);
+        assert_eq!(hist.snapshot(), vec![(1.0, 1), (10.0, 2), (f64::INFINITY, 3)]);
+    }
+
+    #[test]
+    fn selectivity_tracker_defaults_to_a_conservative_guess_until_observed() {
+        let tracker = SelectivityTracker::new();
+        assert_eq!(tracker.estimate(), 0.2);
+        tracker.observe(100, 10);
+        assert_eq!(tracker.estimate(), 0.1);
+    }
+
+    #[test]
+    fn registry_returns_same_instance_per_namespace() {
+        let registry = MetricsRegistry::default();
+        let a = registry.namespace("docs");
+        a.lists_probed.observe(4.0);
+        let b = registry.namespace("docs");
+        assert_eq!(b.lists_probed.snapshot()[2].1, 1);
+    }
+
+    #[test]
+    fn drift_tracker_does_not_retrain_before_a_baseline_exists() {
+        let tracker = DriftTracker::new();
+        tracker.observe(10.0, None);
+        assert!(!tracker.should_retrain(DriftThresholds::default()));
+    }
+
+    #[test]
+    fn drift_tracker_flags_retraining_once_norm_drifts_past_the_threshold() {
+        let tracker = DriftTracker::new();
+        for _ in 0..10 {
+            tracker.observe(1.0, None);
+        }
+        tracker.record_baseline();
+        assert!(!tracker.should_retrain(DriftThresholds::default()));
+
+        for _ in 0..10 {
+            tracker.observe(2.0, None);
+        }
+        assert!(tracker.should_retrain(DriftThresholds::default()));
+    }
+
+    #[test]
+    fn drift_tracker_admin_override_forces_a_retrain() {
+        let tracker = DriftTracker::new();
+        tracker.observe(1.0, None);
+        tracker.record_baseline();
+        assert!(!tracker.should_retrain(DriftThresholds::default()));
+
+        tracker.request_retrain();
+        assert!(tracker.should_retrain(DriftThresholds::default()));
+
+        tracker.record_baseline();
+        assert!(!tracker.should_retrain(DriftThresholds::default()));
+    }
+
+    #[test]
+    fn drift_tracker_tracks_residual_drift_independently_of_norm() {
+        let tracker = DriftTracker::new();
+        for _ in 0..10 {
+            tracker.observe(1.0, Some(0.1));
+        }
+        tracker.record_baseline();
+        assert!(!tracker.should_retrain(DriftThresholds::default()));
+
+        for _ in 0..10 {
+            tracker.observe(1.0, Some(0.5));
+        }
+        assert!(tracker.should_retrain(DriftThresholds::default()));
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::document::Document;
+
+/// An in-memory vector namespace.
+///
+/// At load time every row is materialized into `rows`; this type has no
+/// on-disk execution path ([`crate::tiered::TieredNamespace`] is the one
+/// that searches on-disk parts).
+pub struct Namespace {
+    pub name: String,
+    pub rows: HashMap<String, Document>,
+}
+
+impl Namespace {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            rows: HashMap::new(),
+        }
+    }
+
+    pub fn upsert(&mut self, doc: Document) {
+        self.rows.insert(doc.id.clone(), doc);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Document> {
+        self.rows.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upsert_overwrites_by_id() {
+        let mut ns = Namespace::new("docs");
+        ns.upsert(Document::new("a", vec![1.0, 2.0]));
+        ns.upsert(Document::new("a", vec![3.0, 4.0]));
+        assert_eq!(ns.len(), 1);
+        assert_eq!(ns.get("a").unwrap().vector, vec![3.0, 4.0]);
+    }
+}
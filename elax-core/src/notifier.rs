@@ -0,0 +1,221 @@
+//! Webhook notifications for write and maintenance events (namespace
+//! lifecycle, part publication, compaction), so downstream systems can
+//! react without polling. Mirrors [`crate::embedder::HttpEmbedder`]'s
+//! std-only blocking transport rather than pulling in an async HTTP client.
+
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::Result;
+
+/// A notable event a registered webhook may care about.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    NamespaceCreated { namespace: String },
+    NamespaceDeleted { namespace: String },
+    PartPublished { namespace: String, part_id: String },
+    CompactionCompleted { namespace: String },
+}
+
+/// Delivers a [`WebhookEvent`] somewhere. Implementations may call out to
+/// an HTTP endpoint (see [`HttpWebhook`]) or be swapped for a recording
+/// stub in tests.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, event: &WebhookEvent) -> Result<()>;
+}
+
+/// Delivery attempt counts for one registered webhook, so an operator can
+/// tell a silently-failing endpoint from a healthy one.
+#[derive(Debug, Default)]
+pub struct DeliveryMetrics {
+    pub attempts: AtomicU64,
+    pub successes: AtomicU64,
+    pub failures: AtomicU64,
+}
+
+impl DeliveryMetrics {
+    pub fn snapshot(&self) -> (u64, u64, u64) {
+        (
+            self.attempts.load(Ordering::Relaxed),
+            self.successes.load(Ordering::Relaxed),
+            self.failures.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// How long to wait before each retry of a failed delivery. `delays[i]` is
+/// the wait before attempt `i + 2` (the first attempt never waits).
+fn backoff_delays(max_retries: usize) -> Vec<Duration> {
+    (0..max_retries)
+        .map(|attempt| Duration::from_millis(50 * 2u64.pow(attempt as u32)))
+        .collect()
+}
+
+/// POSTs a JSON-encoded [`WebhookEvent`] to a plain HTTP/1.1 endpoint,
+/// retrying with exponential backoff on failure and recording every attempt
+/// in [`DeliveryMetrics`].
+pub struct HttpWebhook {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+    pub max_retries: usize,
+    pub metrics: DeliveryMetrics,
+}
+
+impl HttpWebhook {
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            path: "/".to_string(),
+            max_retries: 3,
+            metrics: DeliveryMetrics::default(),
+        }
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    fn deliver_once(&self, body: &[u8]) -> Result<()> {
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.host,
+            body.len()
+        );
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(body)?;
+        Ok(())
+    }
+}
+
+impl Notifier for HttpWebhook {
+    fn notify(&self, event: &WebhookEvent) -> Result<()> {
+        let body = serde_json::to_vec(event)?;
+        let delays = backoff_delays(self.max_retries);
+
+        let mut last_err = None;
+        for delay in std::iter::once(None).chain(delays.into_iter().map(Some)) {
+            if let Some(delay) = delay {
+                std::thread::sleep(delay);
+            }
+            self.metrics.attempts.fetch_add(1, Ordering::Relaxed);
+            match self.deliver_once(&body) {
+                Ok(()) => {
+                    self.metrics.successes.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(err) => {
+                    self.metrics.failures.fetch_add(1, Ordering::Relaxed);
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(last_err.unwrap())
+    }
+}
+
+/// Fans one event out to every registered [`Notifier`]. A failing webhook
+/// (after its own retries) is logged in its `DeliveryMetrics` but never
+/// blocks the others, since webhook delivery is best-effort and must not
+/// hold up the write path that triggered it.
+#[derive(Default, Clone)]
+pub struct WebhookDispatcher {
+    notifiers: Arc<Mutex<Vec<Arc<dyn Notifier>>>>,
+}
+
+impl WebhookDispatcher {
+    pub fn register(&self, notifier: Arc<dyn Notifier>) {
+        self.notifiers.lock().unwrap().push(notifier);
+    }
+
+    /// Deliver `event` to every registered notifier, swallowing individual
+    /// failures (already captured in each notifier's own delivery metrics).
+    pub fn dispatch(&self, event: &WebhookEvent) {
+        for notifier in self.notifiers.lock().unwrap().iter() {
+            let _ = notifier.notify(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        seen: StdMutex<Vec<WebhookEvent>>,
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, event: &WebhookEvent) -> Result<()> {
+            self.seen.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dispatch_fans_out_to_every_registered_notifier() {
+        let dispatcher = WebhookDispatcher::default();
+        let a = Arc::new(RecordingNotifier::default());
+        let b = Arc::new(RecordingNotifier::default());
+        dispatcher.register(a.clone());
+        dispatcher.register(b.clone());
+
+        dispatcher.dispatch(&WebhookEvent::NamespaceCreated {
+            namespace: "docs".to_string(),
+        });
+
+        assert_eq!(a.seen.lock().unwrap().len(), 1);
+        assert_eq!(b.seen.lock().unwrap().len(), 1);
+    }
+
+    struct FailingNotifier;
+
+    impl Notifier for FailingNotifier {
+        fn notify(&self, _event: &WebhookEvent) -> Result<()> {
+            Err(crate::error::CoreError::EmbeddingFailed("delivery failed".to_string()))
+        }
+    }
+
+    #[test]
+    fn a_failing_notifier_does_not_block_the_others() {
+        let dispatcher = WebhookDispatcher::default();
+        let recorder = Arc::new(RecordingNotifier::default());
+        dispatcher.register(Arc::new(FailingNotifier));
+        dispatcher.register(recorder.clone());
+
+        dispatcher.dispatch(&WebhookEvent::CompactionCompleted {
+            namespace: "docs".to_string(),
+        });
+
+        assert_eq!(recorder.seen.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn http_webhook_retries_and_records_every_attempt_on_failure() {
+        // Port 0 never accepts a connection, so every attempt fails fast.
+        let webhook = HttpWebhook::new("127.0.0.1", 0).with_max_retries(2);
+        assert!(webhook.notify(&WebhookEvent::CompactionCompleted { namespace: "docs".to_string() }).is_err());
+
+        let (attempts, successes, failures) = webhook.metrics.snapshot();
+        assert_eq!(attempts, 3);
+        assert_eq!(successes, 0);
+        assert_eq!(failures, 3);
+    }
+}
@@ -0,0 +1,176 @@
+//! Composable per-namespace preprocessing steps run over a document's
+//! vector before it's accepted into the namespace, in addition to the
+//! fixed `dimension`/`normalize` checks [`crate::registry::NamespaceConfig`]
+//! already applies. Unlike those two, a pipeline is an ordered list a
+//! namespace opts into and can reorder or extend freely.
+//! [`crate::registry::NamespaceRegistry::apply_write_batch`] is the
+//! per-document-outcome counterpart to [`crate::registry::NamespaceRegistry::apply_write`]
+//! that runs this pipeline without letting one bad document fail the rest
+//! of the batch.
+
+use elax_index::VectorPrecision;
+
+use crate::document::Document;
+use crate::error::{CoreError, Result};
+
+/// One preprocessing step applied to a document's vector, in order, before
+/// it's accepted into the namespace.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineStep {
+    /// Scale the vector to unit L2 norm, same as
+    /// [`crate::registry::NamespaceConfig::normalize`] — a separate step so
+    /// it can be composed with the others in whatever order the namespace
+    /// wants, e.g. clip first, then normalize.
+    Normalize,
+    /// Clamp every component into `[min, max]`.
+    Clip { min: f32, max: f32 },
+    /// Round-trip the vector through a lower-precision encoding, so a
+    /// namespace storing parts as [`VectorPrecision::F16`] or
+    /// [`VectorPrecision::Bf16`] rejects precision loss it can't tolerate
+    /// at write time rather than discovering it once the part is built.
+    CastPrecision(VectorPrecision),
+    /// Reject the document if any vector component is `NaN` or infinite.
+    RejectNonFinite,
+    /// Reject the document if its vector length isn't exactly `dim`.
+    DimensionCheck(usize),
+}
+
+/// Reject `vector` if any component is `NaN` or infinite. Unlike the other
+/// steps, this isn't opt-in: [`crate::registry::NamespaceRegistry::apply_write_with_metrics`]
+/// and every [`crate::tiered::TieredNamespace`] search entry point run it
+/// unconditionally, since a non-finite score or stored vector would
+/// otherwise propagate into ranking as silent nondeterminism rather than a
+/// clear rejection at the boundary where it was introduced.
+pub(crate) fn reject_non_finite(vector: &[f32]) -> Result<()> {
+    if vector.iter().any(|v| !v.is_finite()) {
+        return Err(CoreError::InvalidVector("vector contains NaN or infinite components".to_string()));
+    }
+    Ok(())
+}
+
+impl PipelineStep {
+    fn apply(&self, doc: &mut Document) -> Result<()> {
+        match self {
+            PipelineStep::Normalize => {
+                crate::registry::normalize_l2(&mut doc.vector);
+                Ok(())
+            }
+            PipelineStep::Clip { min, max } => {
+                for v in doc.vector.iter_mut() {
+                    *v = v.clamp(*min, *max);
+                }
+                Ok(())
+            }
+            PipelineStep::CastPrecision(precision) => {
+                for v in doc.vector.iter_mut() {
+                    *v = precision.decode(&precision.encode(*v));
+                }
+                Ok(())
+            }
+            PipelineStep::RejectNonFinite => reject_non_finite(&doc.vector),
+            PipelineStep::DimensionCheck(dim) => {
+                if doc.vector.len() != *dim {
+                    return Err(CoreError::DimensionMismatch {
+                        expected: *dim,
+                        found: doc.vector.len(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Run every step of `pipeline` against `doc`, in order, stopping at the
+/// first one that rejects it.
+pub fn run_pipeline(doc: &mut Document, pipeline: &[PipelineStep]) -> Result<()> {
+    for step in pipeline {
+        step.apply(doc)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(vector: Vec<f32>) -> Document {
+        Document::new("doc-1", vector)
+    }
+
+    #[test]
+    fn normalize_scales_to_unit_norm() {
+        let mut d = doc(vec![3.0, 4.0]);
+        run_pipeline(&mut d, &[PipelineStep::Normalize]).unwrap();
+        let norm = d.vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn clip_clamps_out_of_range_components() {
+        let mut d = doc(vec![-5.0, 0.5, 5.0]);
+        run_pipeline(&mut d, &[PipelineStep::Clip { min: -1.0, max: 1.0 }]).unwrap();
+        assert_eq!(d.vector, vec![-1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn cast_precision_rounds_through_the_lower_precision_encoding() {
+        let mut d = doc(vec![1.0 / 3.0]);
+        run_pipeline(&mut d, &[PipelineStep::CastPrecision(VectorPrecision::F16)]).unwrap();
+        assert!((d.vector[0] - 1.0 / 3.0).abs() < 1e-3);
+        assert_ne!(d.vector[0], 1.0 / 3.0);
+    }
+
+    #[test]
+    fn reject_non_finite_catches_nan() {
+        let mut d = doc(vec![1.0, f32::NAN]);
+        let err = run_pipeline(&mut d, &[PipelineStep::RejectNonFinite]).unwrap_err();
+        assert!(matches!(err, CoreError::InvalidVector(_)));
+    }
+
+    #[test]
+    fn reject_non_finite_catches_infinity() {
+        let mut d = doc(vec![f32::INFINITY]);
+        assert!(run_pipeline(&mut d, &[PipelineStep::RejectNonFinite]).is_err());
+    }
+
+    #[test]
+    fn dimension_check_rejects_the_wrong_length() {
+        let mut d = doc(vec![1.0, 2.0]);
+        let err = run_pipeline(&mut d, &[PipelineStep::DimensionCheck(3)]).unwrap_err();
+        assert!(matches!(err, CoreError::DimensionMismatch { expected: 3, found: 2 }));
+    }
+
+    #[test]
+    fn steps_run_in_order_so_clip_then_normalize_differs_from_the_reverse() {
+        let mut clip_then_normalize = doc(vec![10.0, 0.5]);
+        run_pipeline(
+            &mut clip_then_normalize,
+            &[PipelineStep::Clip { min: -1.0, max: 1.0 }, PipelineStep::Normalize],
+        )
+        .unwrap();
+        assert!((clip_then_normalize.vector[0] - 0.894_427_2).abs() < 1e-6);
+
+        let mut normalize_then_clip = doc(vec![10.0, 0.5]);
+        run_pipeline(
+            &mut normalize_then_clip,
+            &[PipelineStep::Normalize, PipelineStep::Clip { min: -1.0, max: 1.0 }],
+        )
+        .unwrap();
+        assert!((normalize_then_clip.vector[0] - 0.998_752_3).abs() < 1e-6);
+        assert_ne!(clip_then_normalize.vector, normalize_then_clip.vector);
+    }
+
+    #[test]
+    fn a_failing_step_stops_the_pipeline_before_later_steps_run() {
+        let mut d = doc(vec![1.0, f32::NAN]);
+        let err = run_pipeline(
+            &mut d,
+            &[PipelineStep::RejectNonFinite, PipelineStep::Clip { min: 0.0, max: 1.0 }],
+        )
+        .unwrap_err();
+        assert!(matches!(err, CoreError::InvalidVector(_)));
+        assert_eq!(d.vector[0], 1.0);
+        assert!(d.vector[1].is_nan());
+    }
+}
@@ -0,0 +1,97 @@
+//! Caches embeddings of recent `ANN_TEXT` query strings so a thin client
+//! repeating the same search phrase doesn't pay for a fresh embedding call
+//! every time.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::embedder::Embedder;
+use crate::error::Result;
+
+struct State {
+    vectors: HashMap<String, Vec<f32>>,
+    order: VecDeque<String>,
+}
+
+/// A bounded cache keyed by `(model, text)`, evicting the oldest entry once
+/// `capacity` is exceeded.
+pub struct QueryEmbeddingCache {
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+impl QueryEmbeddingCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(State {
+                vectors: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+        }
+    }
+
+    fn cache_key(model: &str, text: &str) -> String {
+        format!("{model}\u{0}{text}")
+    }
+
+    /// Return the cached embedding for `(model, text)`, computing and
+    /// caching it via `embedder` on a miss.
+    pub fn get_or_embed(&self, text: &str, model: &str, embedder: &dyn Embedder) -> Result<Vec<f32>> {
+        let key = Self::cache_key(model, text);
+        {
+            let state = self.state.lock().unwrap();
+            if let Some(vector) = state.vectors.get(&key) {
+                return Ok(vector.clone());
+            }
+        }
+
+        let vector = embedder.embed(text, model)?;
+
+        let mut state = self.state.lock().unwrap();
+        state.vectors.insert(key.clone(), vector.clone());
+        state.order.push_back(key);
+        while state.order.len() > self.capacity {
+            if let Some(oldest) = state.order.pop_front() {
+                state.vectors.remove(&oldest);
+            }
+        }
+        Ok(vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingEmbedder(AtomicUsize);
+
+    impl Embedder for CountingEmbedder {
+        fn embed(&self, text: &str, _model: &str) -> Result<Vec<f32>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![text.len() as f32])
+        }
+    }
+
+    #[test]
+    fn repeated_query_text_hits_the_cache() {
+        let embedder = CountingEmbedder(AtomicUsize::new(0));
+        let cache = QueryEmbeddingCache::new(8);
+
+        cache.get_or_embed("hello", "m1", &embedder).unwrap();
+        cache.get_or_embed("hello", "m1", &embedder).unwrap();
+        assert_eq!(embedder.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn evicts_oldest_entry_past_capacity() {
+        let embedder = CountingEmbedder(AtomicUsize::new(0));
+        let cache = QueryEmbeddingCache::new(1);
+
+        cache.get_or_embed("a", "m1", &embedder).unwrap();
+        cache.get_or_embed("b", "m1", &embedder).unwrap();
+        cache.get_or_embed("a", "m1", &embedder).unwrap();
+        assert_eq!(embedder.0.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,230 @@
+//! Opt-in sampling of production queries, persisted so a later [`replay`]
+//! run can re-execute them against a namespace (or a clone of one) and
+//! compare recall/latency before and after a config or index-version
+//! change. A sampled entry always keeps [`hash_vector`]'s hash of the
+//! query vector (cheap to compare, never reversible); the raw vector is
+//! kept alongside it only when [`QueryLogConfig::store_vectors`] opts in,
+//! since without it there's nothing to feed back into [`IvfIndex::probe`]
+//! on replay. Whether that raw vector sits at rest as plaintext or
+//! ciphertext is a property of the [`elax_store::ObjectStore`] the log is
+//! saved through — [`elax_store::crypto::EncryptingStore`] wraps any
+//! store transparently, so this module never needs to know.
+//!
+//! Sampling is decided from [`hash_vector`] rather than a random draw, so
+//! a given query vector always makes the same sample/skip decision for a
+//! fixed [`QueryLogConfig::sample_rate`] — deterministic and reproducible
+//! in tests, the same reasoning that keeps timestamps explicit-input
+//! rather than clock-read elsewhere (see
+//! [`crate::flush_policy::should_flush`]).
+
+use elax_index::IvfIndex;
+use elax_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// FNV-1a over a vector's raw bytes — the same algorithm
+/// [`elax_store::wal`] uses to checksum frames, repurposed here as a
+/// cheap, deterministic fingerprint rather than an integrity check.
+pub fn hash_vector(vector: &[f32]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for value in vector {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// Per-namespace query-recording knobs. Off by default — `sample_rate`
+/// of `0.0` samples nothing, the same opt-in-by-default shape as
+/// [`crate::flush_policy::FlushPolicy`]'s unbounded `None` fields.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QueryLogConfig {
+    /// Fraction of queries to record, from `0.0` (none) to `1.0` (all).
+    pub sample_rate: f64,
+    /// Keep the raw query vector alongside its hash, so [`replay`] has
+    /// something to feed back into the index. Off by default: a deployment
+    /// that only wants to track query volume/hash drift shouldn't also
+    /// pay to persist raw vectors.
+    pub store_vectors: bool,
+}
+
+impl Default for QueryLogConfig {
+    fn default() -> Self {
+        Self { sample_rate: 0.0, store_vectors: false }
+    }
+}
+
+/// Whether a query with this hash should be sampled under `config`.
+pub fn should_sample(config: &QueryLogConfig, query_hash: u64) -> bool {
+    if config.sample_rate <= 0.0 {
+        return false;
+    }
+    if config.sample_rate >= 1.0 {
+        return true;
+    }
+    const BUCKETS: u64 = 1_000_000;
+    (query_hash % BUCKETS) < (config.sample_rate * BUCKETS as f64) as u64
+}
+
+/// One sampled query, timestamped by the caller rather than read from the
+/// clock here — see [`crate::flush_policy::should_flush`] for why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryLogEntry {
+    pub recorded_at_unix_secs: u64,
+    pub query_hash: u64,
+    /// Present only when the config that sampled this query had
+    /// [`QueryLogConfig::store_vectors`] set.
+    pub vector: Option<Vec<f32>>,
+    pub top_k: usize,
+    pub nprobe: usize,
+    pub latency_micros: Option<u64>,
+}
+
+fn query_log_key(namespace: &str) -> String {
+    format!("query_log/{namespace}.json")
+}
+
+/// A namespace's sampled queries, persisted the same way
+/// [`elax_index::GroundTruthSet`] and [`elax_index::RecallCurve`] are.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryLog {
+    pub entries: Vec<QueryLogEntry>,
+}
+
+impl QueryLog {
+    pub fn load(store: &dyn ObjectStore, namespace: &str) -> Result<QueryLog> {
+        match store.get(&query_log_key(namespace))? {
+            Some((bytes, _generation)) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(QueryLog::default()),
+        }
+    }
+
+    pub fn save(&self, store: &dyn ObjectStore, namespace: &str) -> Result<()> {
+        let bytes = serde_json::to_vec(self)?;
+        let current = store.get(&query_log_key(namespace))?.map(|(_, generation)| generation);
+        store.put_if_match(&query_log_key(namespace), current, bytes)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, entry: QueryLogEntry) {
+        self.entries.push(entry);
+    }
+}
+
+/// `index.probe`'s result for one replayed entry, paired with the hash
+/// that identifies which sampled query it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayHit {
+    pub query_hash: u64,
+    pub hits: Vec<(String, f32)>,
+}
+
+/// Re-run every entry in `log` that kept its raw vector against `index`,
+/// so a caller can diff the results (or the latency recorded alongside
+/// the original run) against a fresh probe on a changed config or index
+/// version. Entries sampled without `store_vectors` are skipped — there's
+/// nothing to feed back into `index.probe`.
+pub fn replay(log: &QueryLog, index: &IvfIndex, nprobe: usize) -> Vec<ReplayHit> {
+    log.entries
+        .iter()
+        .filter_map(|entry| {
+            let vector = entry.vector.as_ref()?;
+            Some(ReplayHit { query_hash: entry.query_hash, hits: index.probe(vector, nprobe) })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elax_index::Row;
+    use elax_store::LocalStore;
+
+    fn tmp_store() -> LocalStore {
+        let dir = std::env::temp_dir().join(format!(
+            "elax-core-query-log-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()
+        ));
+        LocalStore::new(dir).unwrap()
+    }
+
+    #[test]
+    fn a_zero_sample_rate_never_samples() {
+        let config = QueryLogConfig { sample_rate: 0.0, store_vectors: false };
+        assert!(!should_sample(&config, hash_vector(&[1.0, 2.0])));
+    }
+
+    #[test]
+    fn a_sample_rate_of_one_always_samples() {
+        let config = QueryLogConfig { sample_rate: 1.0, store_vectors: false };
+        assert!(should_sample(&config, hash_vector(&[1.0, 2.0])));
+    }
+
+    #[test]
+    fn the_same_vector_always_makes_the_same_sampling_decision() {
+        let config = QueryLogConfig { sample_rate: 0.5, store_vectors: false };
+        let hash = hash_vector(&[3.0, -1.5, 0.25]);
+        assert_eq!(should_sample(&config, hash), should_sample(&config, hash));
+    }
+
+    #[test]
+    fn query_log_round_trips_through_the_store() {
+        let store = tmp_store();
+        let mut log = QueryLog::default();
+        log.record(QueryLogEntry {
+            recorded_at_unix_secs: 1_700_000_000,
+            query_hash: hash_vector(&[1.0, 0.0]),
+            vector: Some(vec![1.0, 0.0]),
+            top_k: 5,
+            nprobe: 2,
+            latency_micros: Some(250),
+        });
+        log.save(&store, "ns1").unwrap();
+
+        let loaded = QueryLog::load(&store, "ns1").unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.entries[0].vector, Some(vec![1.0, 0.0]));
+    }
+
+    #[test]
+    fn query_log_defaults_to_empty_when_nothing_saved_yet() {
+        let store = tmp_store();
+        let loaded = QueryLog::load(&store, "missing").unwrap();
+        assert!(loaded.entries.is_empty());
+    }
+
+    #[test]
+    fn replay_skips_entries_that_did_not_keep_their_vector() {
+        let rows = vec![Row::new("a", vec![1.0, 0.0]), Row::new("b", vec![0.0, 1.0])];
+        let index = IvfIndex::build(rows, 2);
+        let log = QueryLog {
+            entries: vec![
+                QueryLogEntry {
+                    recorded_at_unix_secs: 1,
+                    query_hash: 1,
+                    vector: Some(vec![1.0, 0.0]),
+                    top_k: 1,
+                    nprobe: 2,
+                    latency_micros: None,
+                },
+                QueryLogEntry {
+                    recorded_at_unix_secs: 2,
+                    query_hash: 2,
+                    vector: None,
+                    top_k: 1,
+                    nprobe: 2,
+                    latency_micros: None,
+                },
+            ],
+        };
+
+        let replayed = replay(&log, &index, 2);
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].query_hash, 1);
+        assert_eq!(replayed[0].hits[0].0, "a");
+    }
+}
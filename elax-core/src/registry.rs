@@ -0,0 +1,2496 @@
+//! Owns every namespace in the process and enforces per-namespace quotas on
+//! write.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+
+use elax_store::WalReader;
+
+use crate::attr_order::{self, AttrOrder};
+use crate::attr_schema::{AttrSchema, AttrType};
+use crate::document::Document;
+use crate::embedder::{Embedder, EmbeddingModel};
+use crate::error::{CoreError, Result};
+use crate::filter::FilterExpr;
+use crate::flush_policy::FlushPolicy;
+use crate::id_gen::{generate_id, IdStrategy, SnowflakeGenerator};
+use crate::metrics::NamespaceMetrics;
+use crate::namespace::Namespace;
+use crate::notifier::{WebhookDispatcher, WebhookEvent};
+use crate::pipeline::{run_pipeline, PipelineStep};
+use crate::query_log::QueryLogConfig;
+use crate::settings::AnnParams;
+use crate::text_expansion::TextSearchConfig;
+use crate::tiered::PlanHint;
+use crate::view::{apply_projection, ViewConfig};
+
+/// Tuning knobs for [`NamespaceRegistry::load_from_wal`].
+#[derive(Debug, Clone, Copy)]
+pub struct WalLoadOptions {
+    /// How many records to JSON-decode concurrently per chunk — the
+    /// sequential apply into the row map stays single-threaded since
+    /// decoding is the expensive part for a namespace with hundreds of
+    /// thousands of records, not the insert.
+    pub max_parallelism: usize,
+}
+
+impl Default for WalLoadOptions {
+    fn default() -> Self {
+        Self { max_parallelism: 8 }
+    }
+}
+
+/// Resource limits for one namespace. `None` means unlimited.
+#[derive(Debug, Clone, Default)]
+pub struct Quota {
+    pub max_rows: Option<usize>,
+    pub max_bytes: Option<u64>,
+    pub max_dim: Option<usize>,
+    /// Largest `top_k` a query against this namespace may request. Guards
+    /// against an unbounded candidate-scoring cost from a caller-chosen
+    /// `top_k`, the same way `max_dim` guards write-time vector size. See
+    /// [`NamespaceRegistry::validate_top_k`].
+    pub max_top_k: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceConfig {
+    pub quota: Quota,
+    pub vector_precision: elax_index::VectorPrecision,
+    /// Required vector length for every document in this namespace. `None`
+    /// leaves the namespace dimension-agnostic (the pre-existing behavior).
+    pub dimension: Option<usize>,
+    /// L2-normalize vectors at write time, so dot-product scoring behaves
+    /// like cosine similarity at query time.
+    pub normalize: bool,
+    /// Attribute keys whose type is declared up front rather than inferred
+    /// from the first write that defines them. A write whose value doesn't
+    /// match a declared (or already-inferred) type is rejected — see
+    /// [`crate::attr_schema::AttrSchema`].
+    pub attr_schema: HashMap<String, AttrType>,
+    /// This namespace's default ANN probe parameters, used by
+    /// [`NamespaceRegistry::effective_ann_params`] when a query doesn't
+    /// specify its own. `None` falls back to the process-wide
+    /// [`RuntimeSettings::ann_params`](crate::settings::RuntimeSettings::ann_params).
+    pub ann_params: Option<AnnParams>,
+    /// The embedding model this namespace's vectors are expected to come
+    /// from. `None` leaves the namespace model-agnostic (the pre-existing
+    /// behavior) — a write or query declaring a model is only checked
+    /// against this when it's set. See
+    /// [`NamespaceRegistry::check_embedding_model`].
+    pub embedding_model: Option<EmbeddingModel>,
+    /// How this namespace fills in `id` for a write that omits it. Defaults
+    /// to [`IdStrategy::ClientSupplied`] — an empty id fails the write, the
+    /// pre-existing behavior.
+    pub id_strategy: IdStrategy,
+    /// This namespace's default filter/vector execution order, used by
+    /// [`NamespaceRegistry::effective_plan_hint`] for a clause that doesn't
+    /// force its own [`crate::tiered::QueryClause::plan_hint`]. `None` falls
+    /// back to the caller-supplied default (ordinarily
+    /// [`PlanHint::FilterFirst`]).
+    pub default_plan_hint: Option<PlanHint>,
+    /// This namespace's part-cutting thresholds, used by an indexing pass
+    /// in place of the process-wide default. `None` falls back to the
+    /// caller-supplied default, the same `Option` override shape as
+    /// [`Self::ann_params`].
+    pub flush_policy: Option<FlushPolicy>,
+    /// Extra preprocessing steps run over a write's vector, in order, after
+    /// the `dimension`/`normalize` checks above but before the row is
+    /// accepted. Empty by default — the pre-existing behavior. See
+    /// [`crate::pipeline::PipelineStep`].
+    pub ingest_pipeline: Vec<PipelineStep>,
+    /// This namespace's query-sampling knobs, used by
+    /// [`NamespaceRegistry::effective_query_log_config`] in place of the
+    /// caller-supplied default. `None` falls back to that default, the same
+    /// `Option` override shape as [`Self::ann_params`]. A caller wired up to
+    /// a search entry point samples and persists entries via
+    /// [`crate::query_log::QueryLog`] itself — this config only decides the
+    /// rate.
+    pub query_log: Option<QueryLogConfig>,
+    /// This namespace's stop-word/synonym expansion config, used by
+    /// [`NamespaceRegistry::effective_text_search_config`] in place of the
+    /// caller-supplied default. `None` falls back to that default, the same
+    /// `Option` override shape as [`Self::ann_params`]. See
+    /// [`crate::text_expansion`] — nothing in this crate calls `expand` yet,
+    /// since there's no free-text match clause in [`FilterExpr`] for it to
+    /// feed.
+    pub text_search: Option<TextSearchConfig>,
+}
+
+/// Scale `vector` in place to unit L2 norm. A zero vector is left
+/// unchanged, since there's no direction to normalize to.
+pub(crate) fn normalize_l2(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for x in vector.iter_mut() {
+            *x /= norm;
+        }
+    }
+}
+
+/// Row count and estimated storage for a namespace, relative to its quota.
+#[derive(Debug, Clone)]
+pub struct NamespaceStats {
+    pub row_count: usize,
+    pub bytes_used: u64,
+    pub quota: Quota,
+    /// The effective attribute schema inferred (or declared) so far.
+    pub attr_schema: AttrSchema,
+    /// The ANN probe parameters a query against this namespace gets when it
+    /// doesn't specify its own — this namespace's configured default, or
+    /// the process-wide default passed into [`NamespaceRegistry::stats`].
+    /// Surfaced here so debug/admin output can show what a caller actually
+    /// gets without it having to also know the process-wide default.
+    pub effective_ann_params: AnnParams,
+    /// Rows with an empty vector, awaiting re-embedding after a
+    /// [`NamespaceRegistry::wipe_vectors`] call.
+    pub pending_reembed: usize,
+}
+
+/// Estimated on-disk footprint of a document's vector plus its attribute
+/// payload — used for [`Quota::max_bytes`] accounting. It measures the same
+/// vector-plus-attribute payload that [`crate::flush_policy::should_flush`]'s
+/// `bytes` argument describes, though nothing in this crate passes it
+/// there.
+fn estimate_bytes(doc: &Document) -> u64 {
+    let vector_bytes = (doc.vector.len() * 4) as u64;
+    let attribute_bytes = serde_json::to_vec(&doc.attributes).map(|v| v.len() as u64).unwrap_or(0);
+    vector_bytes + attribute_bytes
+}
+
+/// Which rows `delete_by_query`/`update_by_query` act on: an optional
+/// attribute [`FilterExpr`] and/or an optional similarity threshold against
+/// a query vector. A row must satisfy both when present.
+#[derive(Debug, Clone, Default)]
+pub struct QueryFilter {
+    pub expr: Option<FilterExpr>,
+    /// `(query_vector, min_score)` — only rows scoring at or above
+    /// `min_score` against `query_vector` match.
+    pub similar_to: Option<(Vec<f32>, f32)>,
+}
+
+fn query_filter_matches(filter: &QueryFilter, doc: &Document) -> bool {
+    if let Some(expr) = &filter.expr {
+        if !expr.matches(doc) {
+            return false;
+        }
+    }
+    if let Some((query, min_score)) = &filter.similar_to {
+        if elax_index::score(query, &doc.vector) < *min_score {
+            return false;
+        }
+    }
+    true
+}
+
+/// Resume point for [`NamespaceRegistry::query_by_filter`]'s keyset
+/// pagination: the resolved sort keys (one per [`AttrOrder`] in the
+/// `order_by` the previous page used, in order) and id of the last row it
+/// returned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryCursor {
+    after_keys: Vec<serde_json::Value>,
+    after_id: String,
+}
+
+/// One page of [`NamespaceRegistry::query_by_filter`]'s results, plus the
+/// cursor to fetch the next page — `None` once there isn't one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPage {
+    pub rows: Vec<Document>,
+    pub next_cursor: Option<QueryCursor>,
+}
+
+/// A registered [`ViewConfig`] plus the cached set of ids in its namespace
+/// currently matching its filter — the bitmap `NamespaceRegistry` keeps up
+/// to date incrementally on every write/delete/update rather than
+/// recomputing per query, the same "maintain, don't recompute" shape
+/// [`crate::query_cache::QueryEmbeddingCache`] uses for embeddings.
+#[derive(Clone)]
+struct View {
+    config: ViewConfig,
+    bitmap: HashSet<String>,
+}
+
+/// Split `target` into a namespace and, if it names one, a view:
+/// `"docs@active"` is namespace `"docs"`, view `"active"`; `"docs"` alone
+/// has no view. Views are matched after alias resolution, so `target`'s
+/// namespace half still goes through [`NamespaceRegistry::resolve`].
+fn split_view_target(target: &str) -> (&str, Option<&str>) {
+    match target.split_once('@') {
+        Some((namespace, view)) => (namespace, Some(view)),
+        None => (target, None),
+    }
+}
+
+/// How many distinct `request_id`s `apply_write_idempotent` remembers per
+/// namespace before forgetting the oldest. Bounded rather than kept
+/// forever, since nothing here ever prunes by time or sequence.
+const DEFAULT_IDEMPOTENCY_WINDOW: usize = 10_000;
+
+/// Bounded memory of recently-applied idempotency keys for one namespace.
+#[derive(Default)]
+struct IdempotencyWindow {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl IdempotencyWindow {
+    fn new(capacity: usize) -> Self {
+        Self {
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Record `request_id`, returning `true` if it had already been seen
+    /// (in which case the caller should skip re-applying the write).
+    fn check_and_record(&mut self, request_id: &str) -> bool {
+        if self.seen.contains(request_id) {
+            return true;
+        }
+        self.seen.insert(request_id.to_string());
+        self.order.push_back(request_id.to_string());
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        false
+    }
+}
+
+#[derive(Default)]
+pub struct NamespaceRegistry {
+    namespaces: HashMap<String, Namespace>,
+    configs: HashMap<String, NamespaceConfig>,
+    embedder: Option<Arc<dyn Embedder>>,
+    idempotency: HashMap<String, IdempotencyWindow>,
+    webhooks: Option<WebhookDispatcher>,
+    schemas: HashMap<String, AttrSchema>,
+    aliases: HashMap<String, String>,
+    snowflake: SnowflakeGenerator,
+    views: HashMap<String, HashMap<String, View>>,
+    /// `Some` while [`Self::apply_transaction`] is running: webhook events
+    /// `apply_write` would otherwise dispatch immediately are buffered here
+    /// instead, so a subscriber never hears about a namespace the
+    /// transaction went on to roll back. `None` (the default) dispatches
+    /// as soon as each write lands, same as before transactions existed.
+    pending_events: Option<Vec<WebhookEvent>>,
+}
+
+impl NamespaceRegistry {
+    pub fn configure(&mut self, namespace: impl Into<String>, config: NamespaceConfig) {
+        self.configs.insert(namespace.into(), config);
+    }
+
+    /// Point `alias` at `target`, so every namespace-taking method below
+    /// resolves a call made with `alias` to `target` instead. Useful for
+    /// blue/green reindexing: build up a new namespace under its own name,
+    /// then flip the alias to it atomically once it's caught up, without
+    /// callers having to know the underlying name changed.
+    pub fn set_alias(&mut self, alias: impl Into<String>, target: impl Into<String>) {
+        self.aliases.insert(alias.into(), target.into());
+    }
+
+    /// Remove `alias`, returning `true` if it was set. Calls made with
+    /// `alias` after this resolve to the literal namespace of that name (if
+    /// any) instead.
+    pub fn remove_alias(&mut self, alias: &str) -> bool {
+        self.aliases.remove(alias).is_some()
+    }
+
+    /// `alias`'s target, or `alias` itself if it isn't an alias — the
+    /// one-hop lookup every namespace-taking method runs before touching
+    /// `self.namespaces`.
+    fn resolve<'a>(&'a self, namespace: &'a str) -> &'a str {
+        self.aliases.get(namespace).map(|target| target.as_str()).unwrap_or(namespace)
+    }
+
+    /// Register `view` against `namespace`: a stored filter (and optional
+    /// attribute projection) a query can target by name — `"docs@active"`
+    /// instead of re-sending `config.filter` every call — via
+    /// [`Self::query_by_filter`], [`Self::count_by_query`], or
+    /// [`Self::exists_by_query`]. Builds the initial bitmap by scanning
+    /// `namespace`'s current rows once; from here on, `apply_write`,
+    /// `delete_by_query`, and `update_by_query` keep it in sync
+    /// incrementally, so this scan never repeats in full.
+    pub fn create_view(&mut self, namespace: &str, view: impl Into<String>, config: ViewConfig) -> Result<()> {
+        let namespace = self.resolve(namespace).to_string();
+        let ns = self
+            .namespaces
+            .get(namespace.as_str())
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.clone()))?;
+        let bitmap: HashSet<String> = ns
+            .rows
+            .values()
+            .filter(|doc| config.filter.matches(doc))
+            .map(|doc| doc.id.clone())
+            .collect();
+        self.views.entry(namespace).or_default().insert(view.into(), View { config, bitmap });
+        Ok(())
+    }
+
+    /// Unregister `view` from `namespace`, returning whether it was
+    /// registered. Queries that targeted `"<namespace>@<view>"` fail with
+    /// [`CoreError::ViewNotFound`] afterward.
+    pub fn drop_view(&mut self, namespace: &str, view: &str) -> bool {
+        let namespace = self.resolve(namespace).to_string();
+        self.views.get_mut(&namespace).is_some_and(|views| views.remove(view).is_some())
+    }
+
+    /// Re-evaluate every view registered on `namespace` against `doc`,
+    /// adding or removing its id from each view's bitmap as its filter now
+    /// does or doesn't match — called after every write that might change
+    /// a row's attributes (a fresh insert or `update_by_query`'s patch), so
+    /// a view's bitmap never drifts from what its filter would compute.
+    fn sync_views_for_write(&mut self, namespace: &str, doc: &Document) {
+        let Some(views) = self.views.get_mut(namespace) else { return };
+        for view in views.values_mut() {
+            if view.config.filter.matches(doc) {
+                view.bitmap.insert(doc.id.clone());
+            } else {
+                view.bitmap.remove(&doc.id);
+            }
+        }
+    }
+
+    /// Drop `id` from every view registered on `namespace`, called after
+    /// `delete_by_query` removes it from the namespace itself.
+    fn sync_views_for_removal(&mut self, namespace: &str, id: &str) {
+        let Some(views) = self.views.get_mut(namespace) else { return };
+        for view in views.values_mut() {
+            view.bitmap.remove(id);
+        }
+    }
+
+    /// Split `target` into its resolved namespace and, if it named one,
+    /// the registered [`View`] — the shared first step of every read
+    /// method that accepts `"<namespace>@<view>"`.
+    fn resolve_view<'a>(&'a self, target: &'a str) -> Result<(&'a str, Option<&'a View>)> {
+        let (namespace, view_name) = split_view_target(target);
+        let namespace = self.resolve(namespace);
+        let view = match view_name {
+            Some(view_name) => Some(
+                self.views
+                    .get(namespace)
+                    .and_then(|views| views.get(view_name))
+                    .ok_or_else(|| CoreError::ViewNotFound {
+                        namespace: namespace.to_string(),
+                        view: view_name.to_string(),
+                    })?,
+            ),
+            None => None,
+        };
+        Ok((namespace, view))
+    }
+
+    /// Install the embedder used to resolve `doc.embedding` hooks on
+    /// upsert. Without one, a write carrying only a hook (no vector) fails.
+    pub fn set_embedder(&mut self, embedder: Arc<dyn Embedder>) {
+        self.embedder = Some(embedder);
+    }
+
+    /// Register a webhook dispatcher to notify on namespace lifecycle
+    /// events (currently: `NamespaceCreated`, fired the first time a write
+    /// lands in a namespace that didn't exist yet). Part-publication and
+    /// compaction events belong to the indexer, which has no wiring for
+    /// either yet (see `crate::indexer::run_once`'s elided body).
+    pub fn set_webhooks(&mut self, dispatcher: WebhookDispatcher) {
+        self.webhooks = Some(dispatcher);
+    }
+
+    /// Set the node id this registry's [`IdStrategy::Snowflake`]-generated
+    /// ids are stamped with. Only matters for deployments running more than
+    /// one indexer process, so their generated ids never collide; defaults
+    /// to `0` otherwise.
+    pub fn set_node_id(&mut self, node_id: u64) {
+        self.snowflake = SnowflakeGenerator::new(node_id);
+    }
+
+    fn bytes_used(namespace: &Namespace) -> u64 {
+        namespace.rows.values().map(estimate_bytes).sum()
+    }
+
+    /// Apply a write, rejecting it with `CoreError::QuotaExceeded` if it
+    /// would push the namespace past its configured rows/bytes/dimension
+    /// limits. If `doc` carries an [`crate::document::EmbeddingHook`]
+    /// instead of a vector, the configured embedder computes the vector
+    /// before any quota checks run. If `doc.id` is empty, it's filled in
+    /// per the namespace's configured [`IdStrategy`] before anything else
+    /// runs, so quota/schema checks and the row map both see the final id.
+    /// Returns that id — generated or, for the common case, simply
+    /// `doc.id` echoed back.
+    pub fn apply_write(&mut self, namespace: &str, doc: Document) -> Result<String> {
+        self.apply_write_with_metrics(namespace, doc, None)
+    }
+
+    /// `apply_write`, but if `metrics` is given, the write's (possibly
+    /// normalized) vector norm also feeds its
+    /// [`NamespaceMetrics::drift`](crate::metrics::NamespaceMetrics::drift)
+    /// tracker, so operators can watch for embedding distribution drift
+    /// over time.
+    pub fn apply_write_with_metrics(
+        &mut self,
+        namespace: &str,
+        mut doc: Document,
+        metrics: Option<&NamespaceMetrics>,
+    ) -> Result<String> {
+        let namespace = self.resolve(namespace).to_string();
+        let namespace = namespace.as_str();
+        let config = self.configs.get(namespace).cloned().unwrap_or_default();
+
+        if doc.id.is_empty() {
+            doc.id = generate_id(config.id_strategy, &self.snowflake)?;
+        }
+
+        if let Some(hook) = doc.embedding.take() {
+            let embedder = self.embedder.as_ref().ok_or_else(|| {
+                CoreError::EmbeddingFailed("no embedder configured for this registry".to_string())
+            })?;
+            doc.vector = embedder.embed(&hook.text, &hook.model)?;
+        }
+
+        let namespace_existed = self.namespaces.contains_key(namespace);
+
+        if let (Some(expected), Some(found)) = (&config.embedding_model, &doc.embedding_model) {
+            if expected != found {
+                return Err(CoreError::EmbeddingModelMismatch {
+                    namespace: namespace.to_string(),
+                    expected: expected.clone(),
+                    found: found.clone(),
+                });
+            }
+        }
+
+        crate::pipeline::reject_non_finite(&doc.vector)?;
+
+        if let Some(expected) = config.dimension {
+            if doc.vector.len() != expected {
+                return Err(CoreError::DimensionMismatch {
+                    expected,
+                    found: doc.vector.len(),
+                });
+            }
+        }
+        if config.normalize {
+            normalize_l2(&mut doc.vector);
+        }
+        run_pipeline(&mut doc, &config.ingest_pipeline)?;
+
+        self.schemas
+            .entry(namespace.to_string())
+            .or_insert_with(|| AttrSchema::new(config.attr_schema.clone()))
+            .observe(&doc.attributes)?;
+
+        if let Some(max_dim) = config.quota.max_dim {
+            if doc.vector.len() > max_dim {
+                return Err(CoreError::QuotaExceeded(format!(
+                    "vector dimension {} exceeds max_dim {max_dim}",
+                    doc.vector.len()
+                )));
+            }
+        }
+
+        // Deferred until every check above has passed: a brand-new
+        // namespace shouldn't show up in `self.namespaces` (and so in
+        // `stats()`, or as having already fired its `NamespaceCreated`
+        // webhook) on the strength of a write that ultimately got rejected.
+        // Until then, an absent namespace reads as empty for the row-count
+        // and byte-budget checks below, same as a present-but-empty one
+        // would.
+        let is_new_row = match self.namespaces.get(namespace) {
+            Some(ns) => !ns.rows.contains_key(&doc.id),
+            None => true,
+        };
+        if is_new_row {
+            if let Some(max_rows) = config.quota.max_rows {
+                let existing_rows = self.namespaces.get(namespace).map_or(0, Namespace::len);
+                if existing_rows + 1 > max_rows {
+                    return Err(CoreError::QuotaExceeded(format!(
+                        "namespace {namespace} is at its max_rows quota of {max_rows}"
+                    )));
+                }
+            }
+        }
+        if let Some(max_bytes) = config.quota.max_bytes {
+            let existing_bytes = self.namespaces.get(namespace).map_or(0, Self::bytes_used);
+            let projected = existing_bytes + estimate_bytes(&doc);
+            if projected > max_bytes {
+                return Err(CoreError::QuotaExceeded(format!(
+                    "namespace {namespace} is at its max_bytes quota of {max_bytes}"
+                )));
+            }
+        }
+
+        if let Some(metrics) = metrics {
+            let norm = doc.vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+            metrics.drift.observe(norm, None);
+        }
+
+        let id = doc.id.clone();
+        let ns = self
+            .namespaces
+            .entry(namespace.to_string())
+            .or_insert_with(|| Namespace::new(namespace));
+        let view_snapshot = self.views.contains_key(namespace).then(|| doc.clone());
+        ns.upsert(doc);
+        if let Some(doc) = view_snapshot {
+            self.sync_views_for_write(namespace, &doc);
+        }
+        if !namespace_existed {
+            let event = WebhookEvent::NamespaceCreated { namespace: namespace.to_string() };
+            match &mut self.pending_events {
+                // Inside a transaction: hold the event until the whole
+                // thing commits, in case a later write rolls this
+                // namespace back out of existence.
+                Some(pending) => pending.push(event),
+                None => {
+                    if let Some(webhooks) = &self.webhooks {
+                        webhooks.dispatch(&event);
+                    }
+                }
+            }
+        }
+        Ok(id)
+    }
+
+    /// Like `apply_write`, but deduplicates retried writes carrying the
+    /// same `request_id`: once a `request_id` has been applied to
+    /// `namespace`, a later call with the same id is a no-op that returns
+    /// `doc.id` straight back (empty if it was server-assigned the first
+    /// time around — by construction a retry can't see what that was)
+    /// rather than re-applying the write, within a bounded retention
+    /// window (`DEFAULT_IDEMPOTENCY_WINDOW` most-recent ids per
+    /// namespace). Meant for network-retried write batches.
+    pub fn apply_write_idempotent(&mut self, namespace: &str, doc: Document, request_id: &str) -> Result<String> {
+        if self.idempotency.get(namespace).is_some_and(|window| window.seen.contains(request_id)) {
+            return Ok(doc.id);
+        }
+        let id = self.apply_write(namespace, doc)?;
+        self.idempotency
+            .entry(namespace.to_string())
+            .or_insert_with(|| IdempotencyWindow::new(DEFAULT_IDEMPOTENCY_WINDOW))
+            .check_and_record(request_id);
+        Ok(id)
+    }
+
+    /// Apply writes spanning one or more namespaces so that either every
+    /// write lands or none do — useful for callers (e.g. one storing chunks
+    /// and metadata in separate namespaces) who must never observe a
+    /// partial batch. Namespaces are in-memory only today (see
+    /// [`Namespace`]'s doc comment), so atomicity here is a snapshot of the
+    /// affected namespaces' rows, schemas, and views taken up front and
+    /// restored on the first failure, rather than a two-phase commit staged
+    /// through a WAL — there is no WAL wired to `apply_write` for that to
+    /// stage into yet. Any `NamespaceCreated` webhook a write would fire is
+    /// held back until the whole transaction commits, so a subscriber never
+    /// hears about a namespace a later failure rolled back out of
+    /// existence. Returns the id each write landed under, in the same order
+    /// as `writes`.
+    pub fn apply_transaction(&mut self, writes: Vec<(String, Document)>) -> Result<Vec<String>> {
+        let touched: HashSet<String> = writes.iter().map(|(namespace, _)| self.resolve(namespace).to_string()).collect();
+        let rows_snapshot: HashMap<String, HashMap<String, Document>> = touched
+            .iter()
+            .filter_map(|namespace| {
+                self.namespaces
+                    .get(namespace)
+                    .map(|ns| (namespace.clone(), ns.rows.clone()))
+            })
+            .collect();
+        let schema_snapshot: HashMap<String, AttrSchema> = touched
+            .iter()
+            .filter_map(|namespace| self.schemas.get(namespace).map(|schema| (namespace.clone(), schema.clone())))
+            .collect();
+        let views_snapshot: HashMap<String, HashMap<String, View>> = touched
+            .iter()
+            .filter_map(|namespace| self.views.get(namespace).map(|views| (namespace.clone(), views.clone())))
+            .collect();
+
+        self.pending_events = Some(Vec::new());
+        let mut ids = Vec::with_capacity(writes.len());
+        for (namespace, doc) in writes {
+            match self.apply_write(&namespace, doc) {
+                Ok(id) => ids.push(id),
+                Err(err) => {
+                    self.restore_snapshot(&touched, &rows_snapshot, &schema_snapshot, &views_snapshot);
+                    self.pending_events = None;
+                    return Err(err);
+                }
+            }
+        }
+        let events = self.pending_events.take().unwrap_or_default();
+        if let Some(webhooks) = &self.webhooks {
+            for event in &events {
+                webhooks.dispatch(event);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Roll every namespace in `touched` back to its pre-transaction state
+    /// across rows, schema, and views — everything `apply_write` mutates as
+    /// a side effect — dropping namespaces that didn't exist before the
+    /// transaction began.
+    fn restore_snapshot(
+        &mut self,
+        touched: &HashSet<String>,
+        rows_snapshot: &HashMap<String, HashMap<String, Document>>,
+        schema_snapshot: &HashMap<String, AttrSchema>,
+        views_snapshot: &HashMap<String, HashMap<String, View>>,
+    ) {
+        for namespace in touched {
+            match rows_snapshot.get(namespace) {
+                Some(rows) => {
+                    if let Some(ns) = self.namespaces.get_mut(namespace) {
+                        ns.rows = rows.clone();
+                    }
+                }
+                None => {
+                    self.namespaces.remove(namespace);
+                }
+            }
+
+            match schema_snapshot.get(namespace) {
+                Some(schema) => {
+                    self.schemas.insert(namespace.clone(), schema.clone());
+                }
+                None => {
+                    self.schemas.remove(namespace);
+                }
+            }
+
+            match views_snapshot.get(namespace) {
+                Some(views) => {
+                    self.views.insert(namespace.clone(), views.clone());
+                }
+                None => {
+                    self.views.remove(namespace);
+                }
+            }
+        }
+    }
+
+    /// Apply writes to a single namespace independently of one another, so
+    /// one bad document (e.g. rejected by its
+    /// [`NamespaceConfig::ingest_pipeline`]) doesn't take the rest of the
+    /// batch down with it — unlike [`Self::apply_transaction`], which is
+    /// explicitly all-or-nothing. Returns one `Result` per input document,
+    /// in the same order as `docs`.
+    pub fn apply_write_batch(&mut self, namespace: &str, docs: Vec<Document>) -> Vec<Result<String>> {
+        docs.into_iter().map(|doc| self.apply_write(namespace, doc)).collect()
+    }
+
+    /// Delete every row in `namespace` matching `filter`, returning how
+    /// many rows were removed.
+    pub fn delete_by_query(&mut self, namespace: &str, filter: &QueryFilter) -> Result<usize> {
+        let namespace = self.resolve(namespace).to_string();
+        let namespace = namespace.as_str();
+        let ns = self
+            .namespaces
+            .get_mut(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        let ids: Vec<String> = ns
+            .rows
+            .values()
+            .filter(|doc| query_filter_matches(filter, doc))
+            .map(|doc| doc.id.clone())
+            .collect();
+        for id in &ids {
+            ns.rows.remove(id);
+        }
+        for id in &ids {
+            self.sync_views_for_removal(namespace, id);
+        }
+        Ok(ids.len())
+    }
+
+    /// Merge `patch`'s attribute keys into every row in `namespace`
+    /// matching `filter`, returning how many rows were updated. `patch`
+    /// must be a JSON object; other attribute keys on matched rows are
+    /// left untouched.
+    pub fn update_by_query(
+        &mut self,
+        namespace: &str,
+        filter: &QueryFilter,
+        patch: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<usize> {
+        let namespace = self.resolve(namespace).to_string();
+        let namespace = namespace.as_str();
+        let ns = self
+            .namespaces
+            .get_mut(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        let mut updated_docs: Vec<Document> = Vec::new();
+        for doc in ns.rows.values_mut() {
+            if !query_filter_matches(filter, doc) {
+                continue;
+            }
+            if !doc.attributes.is_object() {
+                doc.attributes = serde_json::Value::Object(serde_json::Map::new());
+            }
+            let attrs = doc.attributes.as_object_mut().unwrap();
+            for (key, value) in patch {
+                attrs.insert(key.clone(), value.clone());
+            }
+            updated_docs.push(doc.clone());
+        }
+        for doc in &updated_docs {
+            self.sync_views_for_write(namespace, doc);
+        }
+        Ok(updated_docs.len())
+    }
+
+    /// List every row in `namespace` matching `filter`, ordered by
+    /// `order_by` (later keys only matter once every earlier one ties), a
+    /// page of up to `limit` at a time — the pure-metadata counterpart to a
+    /// vector search, for rows with no vector (or ones a caller doesn't
+    /// want to rank by similarity), the same audience
+    /// `delete_by_query`/`update_by_query` already serve. `cursor` resumes
+    /// after the last row a previous page returned; `None` starts from the
+    /// beginning. Ties on every key break by ascending id, so `(order_by,
+    /// id)` forms a total order and a keyset cursor never skips or repeats
+    /// a row — no `OFFSET`-style rescan of already-returned rows the way
+    /// a page-number cursor would need. `namespace` may target a
+    /// registered view as `"<namespace>@<view>"`, in which case only rows
+    /// in the view's bitmap are considered and returned rows' attributes
+    /// go through the view's projection, if it has one.
+    pub fn query_by_filter(
+        &self,
+        namespace: &str,
+        filter: &QueryFilter,
+        order_by: &[AttrOrder],
+        cursor: Option<&QueryCursor>,
+        limit: usize,
+    ) -> Result<QueryPage> {
+        let (namespace, view) = self.resolve_view(namespace)?;
+        let ns = self
+            .namespaces
+            .get(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+
+        let mut matched: Vec<(&Document, Vec<serde_json::Value>)> = ns
+            .rows
+            .values()
+            .filter(|doc| query_filter_matches(filter, doc))
+            .filter(|doc| view.is_none_or(|view| view.bitmap.contains(&doc.id)))
+            .map(|doc| (doc, attr_order::sort_keys(&doc.attributes, &doc.id, order_by)))
+            .collect();
+        matched.sort_by(|(a, a_keys), (b, b_keys)| attr_order::compare_keys(a_keys, &a.id, b_keys, &b.id, order_by));
+
+        let start = match cursor {
+            Some(cursor) => matched
+                .iter()
+                .position(|(doc, keys)| {
+                    attr_order::compare_keys(keys, &doc.id, &cursor.after_keys, &cursor.after_id, order_by)
+                        == std::cmp::Ordering::Greater
+                })
+                .unwrap_or(matched.len()),
+            None => 0,
+        };
+
+        let page = &matched[start..matched.len().min(start + limit)];
+        let rows: Vec<Document> = page
+            .iter()
+            .map(|(doc, _)| {
+                let mut doc = (*doc).clone();
+                if let Some(view) = view {
+                    doc.attributes = apply_projection(&doc.attributes, view.config.projection.as_deref());
+                }
+                doc
+            })
+            .collect();
+        let next_cursor = if start + rows.len() < matched.len() {
+            page.last().map(|(doc, keys)| QueryCursor { after_keys: keys.clone(), after_id: doc.id.clone() })
+        } else {
+            None
+        };
+        Ok(QueryPage { rows, next_cursor })
+    }
+
+    /// Count rows in `namespace` matching `filter`, without scoring,
+    /// sorting, or cloning any of them — the lightweight counterpart to
+    /// `query_by_filter` for callers (e.g. a UI facet count) that only need
+    /// the total, not the rows themselves. Accepts the same
+    /// `"<namespace>@<view>"` targeting as `query_by_filter`.
+    pub fn count_by_query(&self, namespace: &str, filter: &QueryFilter) -> Result<usize> {
+        let (namespace, view) = self.resolve_view(namespace)?;
+        let ns = self
+            .namespaces
+            .get(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        Ok(ns
+            .rows
+            .values()
+            .filter(|doc| query_filter_matches(filter, doc))
+            .filter(|doc| view.is_none_or(|view| view.bitmap.contains(&doc.id)))
+            .count())
+    }
+
+    /// Whether `namespace` has a row with `id`. An O(1) lookup rather than
+    /// a full scan, since the id is already the row map's key.
+    pub fn exists(&self, namespace: &str, id: &str) -> Result<bool> {
+        let namespace = self.resolve(namespace);
+        let ns = self
+            .namespaces
+            .get(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        Ok(ns.rows.contains_key(id))
+    }
+
+    /// Whether any row in `namespace` matches `filter`, short-circuiting on
+    /// the first match rather than scanning (and scoring/sorting) every row
+    /// the way `query_by_filter` does — for a validation check (e.g. "does
+    /// a row with this attribute already exist?") that only needs a yes or
+    /// no answer. Accepts the same `"<namespace>@<view>"` targeting as
+    /// `query_by_filter`.
+    pub fn exists_by_query(&self, namespace: &str, filter: &QueryFilter) -> Result<bool> {
+        let (namespace, view) = self.resolve_view(namespace)?;
+        let ns = self
+            .namespaces
+            .get(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        Ok(ns
+            .rows
+            .values()
+            .filter(|doc| view.is_none_or(|view| view.bitmap.contains(&doc.id)))
+            .any(|doc| query_filter_matches(filter, doc)))
+    }
+
+    /// Replace an existing row's vector in place, leaving its attributes
+    /// untouched — the vector analogue of `update_by_query`'s attribute
+    /// patch, for callers re-embedding a document (e.g. after a model
+    /// upgrade) who don't want to resend attributes just to change the
+    /// vector. Subject to the same dimension/normalize config as a full
+    /// write.
+    pub fn patch_vector(&mut self, namespace: &str, id: &str, mut vector: Vec<f32>) -> Result<()> {
+        let namespace = self.resolve(namespace).to_string();
+        let namespace = namespace.as_str();
+        let config = self.configs.get(namespace).cloned().unwrap_or_default();
+        if let Some(expected) = config.dimension {
+            if vector.len() != expected {
+                return Err(CoreError::DimensionMismatch {
+                    expected,
+                    found: vector.len(),
+                });
+            }
+        }
+        if config.normalize {
+            normalize_l2(&mut vector);
+        }
+        crate::pipeline::reject_non_finite(&vector)?;
+
+        let ns = self
+            .namespaces
+            .get_mut(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        let doc = ns.rows.get_mut(id).ok_or_else(|| CoreError::RowNotFound {
+            namespace: namespace.to_string(),
+            id: id.to_string(),
+        })?;
+        doc.vector = vector;
+        Ok(())
+    }
+
+    /// Replay `wal_path`'s durable records into `namespace`'s row map at
+    /// load time, decoding up to `options.max_parallelism` records at once
+    /// on scoped threads — the same chunked-`thread::scope` shape
+    /// [`elax_index::search_namespace_with_options`] uses for concurrent
+    /// part reads — before applying them to the map in log order, since
+    /// decoding (JSON deserialization) is what gets expensive on a
+    /// namespace with hundreds of thousands of records, not the
+    /// sequential insert. `on_progress(applied, total)` runs after each
+    /// chunk so a long replay doesn't look hung. Bypasses `apply_write`'s
+    /// quota/schema/normalize checks, the same way a direct `Namespace`
+    /// row-map restore should: those only make sense gating new writes,
+    /// not restoring ones already accepted before the process restarted.
+    pub fn load_from_wal(
+        &mut self,
+        namespace: &str,
+        wal_path: &Path,
+        options: &WalLoadOptions,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<usize> {
+        let namespace = self.resolve(namespace).to_string();
+        let records = WalReader::recover(wal_path)?;
+        let total = records.len();
+        let chunk_size = options.max_parallelism.max(1);
+
+        let ns = self
+            .namespaces
+            .entry(namespace.clone())
+            .or_insert_with(|| Namespace::new(namespace.as_str()));
+
+        let mut applied = 0;
+        for chunk in records.chunks(chunk_size) {
+            let decoded: Vec<Result<Document>> = std::thread::scope(|scope| {
+                chunk
+                    .iter()
+                    .map(|payload| scope.spawn(|| serde_json::from_slice::<Document>(payload).map_err(CoreError::from)))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .map(|handle| handle.join().expect("wal decode thread panicked"))
+                    .collect()
+            });
+            for doc in decoded {
+                ns.upsert(doc?);
+                applied += 1;
+            }
+            on_progress(applied, total);
+        }
+        Ok(applied)
+    }
+
+    /// Clear every row's vector in `namespace` while keeping its id and
+    /// attributes, for rotating to a new embedding model without
+    /// re-importing attribute payloads. Returns how many rows were wiped.
+    /// Rows are left with an empty vector (the same sentinel
+    /// [`Document::with_embedding`] uses before a hook resolves) until a
+    /// caller re-embeds them one at a time via `patch_vector` — the
+    /// "re-embedding stream" is just repeated `patch_vector` calls.
+    pub fn wipe_vectors(&mut self, namespace: &str) -> Result<usize> {
+        let namespace = self.resolve(namespace).to_string();
+        let namespace = namespace.as_str();
+        let ns = self
+            .namespaces
+            .get_mut(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        let mut wiped = 0;
+        for doc in ns.rows.values_mut() {
+            if !doc.vector.is_empty() {
+                doc.vector.clear();
+                wiped += 1;
+            }
+        }
+        Ok(wiped)
+    }
+
+    fn rows_for_dedupe(&self, namespace: &str) -> Result<Vec<elax_index::Row>> {
+        let namespace = self.resolve(namespace);
+        let ns = self
+            .namespaces
+            .get(namespace)
+            .ok_or_else(|| CoreError::NamespaceNotFound(namespace.to_string()))?;
+        Ok(ns
+            .rows
+            .values()
+            .map(|doc| elax_index::Row::new(doc.id.clone(), doc.vector.clone()))
+            .collect())
+    }
+
+    /// Find groups of near-duplicate rows in `namespace` (vectors within
+    /// `threshold` similarity of each other), without deleting anything.
+    pub fn find_duplicates(&self, namespace: &str, threshold: f32) -> Result<Vec<Vec<String>>> {
+        let rows = self.rows_for_dedupe(namespace)?;
+        Ok(elax_index::find_near_duplicate_clusters(&rows, threshold))
+    }
+
+    /// Delete every row in a near-duplicate cluster except one keeper per
+    /// cluster, returning the ids removed. Useful for cleaning up scraped
+    /// corpora without a second indexing pass.
+    pub fn dedupe(&mut self, namespace: &str, threshold: f32) -> Result<Vec<String>> {
+        let rows = self.rows_for_dedupe(namespace)?;
+        let namespace = self.resolve(namespace).to_string();
+        let to_remove = elax_index::find_near_duplicates_to_remove(&rows, threshold);
+        let ns = self.namespaces.get_mut(&namespace).unwrap();
+        for id in &to_remove {
+            ns.rows.remove(id);
+        }
+        Ok(to_remove)
+    }
+
+    pub fn stats(&self, namespace: &str, default_ann_params: AnnParams) -> Option<NamespaceStats> {
+        let namespace = self.resolve(namespace);
+        let ns = self.namespaces.get(namespace)?;
+        let quota = self.configs.get(namespace).map(|c| c.quota.clone()).unwrap_or_default();
+        let attr_schema = self.schemas.get(namespace).cloned().unwrap_or_default();
+        Some(NamespaceStats {
+            row_count: ns.len(),
+            bytes_used: Self::bytes_used(ns),
+            quota,
+            attr_schema,
+            effective_ann_params: self.effective_ann_params(namespace, default_ann_params),
+            pending_reembed: ns.rows.values().filter(|doc| doc.vector.is_empty()).count(),
+        })
+    }
+
+    /// Check `model` (the embedding model a query declares it was run
+    /// against) against `namespace`'s configured model, if it has one. A
+    /// namespace without a configured model accepts any query model — the
+    /// same opt-in shape as `dimension`/`normalize`. Callers run this
+    /// before embedding `ANN_TEXT` query text or scoring a caller-supplied
+    /// vector, so a stale client (or one pointed at the wrong model after
+    /// an upgrade) gets a clear error instead of silently low-quality
+    /// scores.
+    pub fn check_embedding_model(&self, namespace: &str, model: &EmbeddingModel) -> Result<()> {
+        let namespace = self.resolve(namespace);
+        if let Some(expected) = self.configs.get(namespace).and_then(|c| c.embedding_model.as_ref()) {
+            if expected != model {
+                return Err(CoreError::EmbeddingModelMismatch {
+                    namespace: namespace.to_string(),
+                    expected: expected.clone(),
+                    found: model.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// This namespace's configured default ANN probe parameters, or
+    /// `default` if it hasn't configured its own.
+    pub fn effective_ann_params(&self, namespace: &str, default: AnnParams) -> AnnParams {
+        let namespace = self.resolve(namespace);
+        self.configs
+            .get(namespace)
+            .and_then(|config| config.ann_params)
+            .unwrap_or(default)
+    }
+
+    /// This namespace's configured part-cutting thresholds, or `default` if
+    /// it hasn't configured its own. An indexing pass reads this instead of
+    /// a process-wide [`crate::flush_policy::FlushPolicy`] so one namespace
+    /// with oversized documents can cut smaller parts without affecting
+    /// every other namespace.
+    pub fn effective_flush_policy(&self, namespace: &str, default: crate::flush_policy::FlushPolicy) -> crate::flush_policy::FlushPolicy {
+        let namespace = self.resolve(namespace);
+        self.configs
+            .get(namespace)
+            .and_then(|config| config.flush_policy)
+            .unwrap_or(default)
+    }
+
+    /// This namespace's configured query-sampling rate, or `default` if
+    /// unconfigured, the same override shape as [`Self::effective_flush_policy`].
+    pub fn effective_query_log_config(&self, namespace: &str, default: QueryLogConfig) -> QueryLogConfig {
+        let namespace = self.resolve(namespace);
+        self.configs
+            .get(namespace)
+            .and_then(|config| config.query_log)
+            .unwrap_or(default)
+    }
+
+    /// This namespace's configured stop-word/synonym expansion pack, or
+    /// `default` if it hasn't configured its own, the same override shape
+    /// as [`Self::effective_query_log_config`].
+    pub fn effective_text_search_config(&self, namespace: &str, default: TextSearchConfig) -> TextSearchConfig {
+        let namespace = self.resolve(namespace);
+        self.configs
+            .get(namespace)
+            .and_then(|config| config.text_search.clone())
+            .unwrap_or(default)
+    }
+
+    /// This namespace's configured default filter/vector execution order,
+    /// or `default` if it hasn't configured its own. A clause's own
+    /// [`crate::tiered::QueryClause::plan_hint`] still overrides this when set.
+    pub fn effective_plan_hint(&self, namespace: &str, default: PlanHint) -> PlanHint {
+        let namespace = self.resolve(namespace);
+        self.configs
+            .get(namespace)
+            .and_then(|config| config.default_plan_hint)
+            .unwrap_or(default)
+    }
+
+    /// Reject `top_k` with `CoreError::QuotaExceeded` if it exceeds
+    /// `namespace`'s configured `quota.max_top_k`. A namespace without one
+    /// accepts any `top_k`, the pre-existing behavior — callers serving
+    /// analytics-style queries for thousands of neighbors should run this
+    /// before picking [`elax_index::SearchMode::Streamed`] for
+    /// [`crate::tiered::TieredNamespace::search_with_mode`], not instead of it:
+    /// this only bounds how large a request is accepted, not how it's executed.
+    pub fn validate_top_k(&self, namespace: &str, top_k: usize) -> Result<()> {
+        let namespace = self.resolve(namespace);
+        if let Some(max_top_k) = self.configs.get(namespace).and_then(|config| config.quota.max_top_k) {
+            if top_k > max_top_k {
+                return Err(CoreError::QuotaExceeded(format!(
+                    "requested top_k {top_k} exceeds namespace {namespace}'s max_top_k quota of {max_top_k}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attr_order::{NullsOrder, SortDirection};
+
+    #[test]
+    fn rejects_writes_past_max_rows() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                quota: Quota {
+                    max_rows: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        let err = registry
+            .apply_write("docs", Document::new("b", vec![1.0]))
+            .unwrap_err();
+        assert!(matches!(err, CoreError::QuotaExceeded(_)));
+    }
+
+    #[test]
+    fn overwriting_an_existing_id_does_not_count_against_max_rows() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                quota: Quota {
+                    max_rows: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        registry.apply_write("docs", Document::new("a", vec![2.0])).unwrap();
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn rejects_writes_with_the_wrong_dimension() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                dimension: Some(3),
+                ..Default::default()
+            },
+        );
+        let err = registry
+            .apply_write("docs", Document::new("a", vec![1.0, 0.0]))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::DimensionMismatch { expected: 3, found: 2 }
+        ));
+    }
+
+    #[test]
+    fn a_rejected_first_write_leaves_no_phantom_namespace_behind() {
+        use crate::notifier::{Notifier, WebhookEvent};
+        use std::sync::Mutex as StdMutex;
+
+        #[derive(Default)]
+        struct RecordingNotifier {
+            seen: StdMutex<Vec<WebhookEvent>>,
+        }
+        impl Notifier for RecordingNotifier {
+            fn notify(&self, event: &WebhookEvent) -> Result<()> {
+                self.seen.lock().unwrap().push(event.clone());
+                Ok(())
+            }
+        }
+
+        let mut registry = NamespaceRegistry::default();
+        let dispatcher = WebhookDispatcher::default();
+        let recorder = Arc::new(RecordingNotifier::default());
+        dispatcher.register(recorder.clone());
+        registry.set_webhooks(dispatcher);
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                dimension: Some(3),
+                ..Default::default()
+            },
+        );
+
+        // The very first write to "docs" is rejected for the wrong
+        // dimension, so "docs" must not exist yet afterward, and no
+        // NamespaceCreated webhook should have fired for it.
+        registry
+            .apply_write("docs", Document::new("a", vec![1.0, 0.0]))
+            .unwrap_err();
+        assert!(registry.stats("docs", AnnParams::default()).is_none());
+        assert!(recorder.seen.lock().unwrap().is_empty());
+
+        // The next, successful write must still see "docs" as new and fire
+        // the webhook it would have fired the first time around.
+        registry
+            .apply_write("docs", Document::new("b", vec![1.0, 0.0, 0.0]))
+            .unwrap();
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+        let seen = recorder.seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(matches!(&seen[0], WebhookEvent::NamespaceCreated { namespace } if namespace == "docs"));
+    }
+
+    #[test]
+    fn normalize_scales_vectors_to_unit_length() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                normalize: true,
+                ..Default::default()
+            },
+        );
+        registry
+            .apply_write("docs", Document::new("a", vec![3.0, 4.0]))
+            .unwrap();
+
+        let ns = registry.namespaces.get("docs").unwrap();
+        let vector = &ns.rows.get("a").unwrap().vector;
+        assert!((vector[0] - 0.6).abs() < 1e-6);
+        assert!((vector[1] - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn delete_by_query_removes_only_matching_rows() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: vec![1.0],
+                attributes: serde_json::json!({"status": "archived"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+        registry
+            .apply_write("docs", Document {
+                id: "b".to_string(),
+                vector: vec![1.0],
+                attributes: serde_json::json!({"status": "active"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+
+        let deleted = registry
+            .delete_by_query(
+                "docs",
+                &QueryFilter {
+                    expr: Some(crate::filter::FilterExpr::AttrEq {
+                        key: "status".to_string(),
+                        value: serde_json::json!("archived"),
+                    }),
+                    similar_to: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(deleted, 1);
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn update_by_query_merges_patch_into_matching_rows_only() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        registry.apply_write("docs", Document::new("b", vec![1.0])).unwrap();
+
+        let mut patch = serde_json::Map::new();
+        patch.insert("tag".to_string(), serde_json::json!("reviewed"));
+
+        let updated = registry
+            .update_by_query(
+                "docs",
+                &QueryFilter {
+                    similar_to: Some((vec![1.0], 0.5)),
+                    expr: None,
+                },
+                &patch,
+            )
+            .unwrap();
+
+        assert_eq!(updated, 2);
+        assert_eq!(
+            registry.namespaces.get("docs").unwrap().rows.get("a").unwrap().attributes["tag"],
+            serde_json::json!("reviewed")
+        );
+    }
+
+    fn metadata_doc(id: &str, rank: i64) -> Document {
+        Document {
+            id: id.to_string(),
+            vector: Vec::new(),
+            attributes: serde_json::json!({"rank": rank}),
+            embedding: None,
+            embedding_model: None,
+        }
+    }
+
+    #[test]
+    fn query_by_filter_orders_attribute_only_rows_ascending() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", metadata_doc("b", 2)).unwrap();
+        registry.apply_write("docs", metadata_doc("a", 1)).unwrap();
+        registry.apply_write("docs", metadata_doc("c", 3)).unwrap();
+
+        let page = registry
+            .query_by_filter(
+                "docs",
+                &QueryFilter::default(),
+                &[AttrOrder { key: "rank".to_string(), direction: SortDirection::Asc, nulls: NullsOrder::Last }],
+                None,
+                10,
+            )
+            .unwrap();
+
+        assert_eq!(page.rows.iter().map(|doc| doc.id.clone()).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn query_by_filter_paginates_with_a_keyset_cursor() {
+        let mut registry = NamespaceRegistry::default();
+        for (id, rank) in [("a", 1), ("b", 2), ("c", 3)] {
+            registry.apply_write("docs", metadata_doc(id, rank)).unwrap();
+        }
+        let order_by = vec![AttrOrder { key: "rank".to_string(), direction: SortDirection::Asc, nulls: NullsOrder::Last }];
+
+        let first = registry.query_by_filter("docs", &QueryFilter::default(), &order_by, None, 2).unwrap();
+        assert_eq!(first.rows.iter().map(|doc| doc.id.clone()).collect::<Vec<_>>(), vec!["a", "b"]);
+        let cursor = first.next_cursor.expect("more rows remain");
+
+        let second = registry
+            .query_by_filter("docs", &QueryFilter::default(), &order_by, Some(&cursor), 2)
+            .unwrap();
+        assert_eq!(second.rows.iter().map(|doc| doc.id.clone()).collect::<Vec<_>>(), vec!["c"]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn query_by_filter_rank_by_id_descending_breaks_ties_by_id() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        registry.apply_write("docs", Document::new("b", vec![1.0])).unwrap();
+        registry.apply_write("docs", Document::new("c", vec![1.0])).unwrap();
+
+        let page = registry
+            .query_by_filter(
+                "docs",
+                &QueryFilter::default(),
+                &[AttrOrder { key: "id".to_string(), direction: SortDirection::Desc, nulls: NullsOrder::Last }],
+                None,
+                10,
+            )
+            .unwrap();
+
+        assert_eq!(page.rows.iter().map(|doc| doc.id.clone()).collect::<Vec<_>>(), vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn query_by_filter_only_matches_rows_passing_the_filter() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "active"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+        registry
+            .apply_write("docs", Document {
+                id: "b".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "archived"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+
+        let page = registry
+            .query_by_filter(
+                "docs",
+                &QueryFilter {
+                    expr: Some(crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") }),
+                    similar_to: None,
+                },
+                &[AttrOrder { key: "id".to_string(), direction: SortDirection::Asc, nulls: NullsOrder::Last }],
+                None,
+                10,
+            )
+            .unwrap();
+
+        assert_eq!(page.rows.len(), 1);
+        assert_eq!(page.rows[0].id, "a");
+    }
+
+    #[test]
+    fn query_by_filter_rows_missing_the_sorted_attribute_sort_last() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", metadata_doc("has_rank", 1)).unwrap();
+        registry.apply_write("docs", Document::new("no_rank", Vec::new())).unwrap();
+
+        let page = registry
+            .query_by_filter(
+                "docs",
+                &QueryFilter::default(),
+                &[AttrOrder { key: "rank".to_string(), direction: SortDirection::Asc, nulls: NullsOrder::Last }],
+                None,
+                10,
+            )
+            .unwrap();
+
+        assert_eq!(page.rows.iter().map(|doc| doc.id.clone()).collect::<Vec<_>>(), vec!["has_rank", "no_rank"]);
+    }
+
+    #[test]
+    fn count_by_query_counts_only_rows_passing_the_filter() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "active"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+        registry
+            .apply_write("docs", Document {
+                id: "b".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "archived"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+
+        let total = registry.count_by_query("docs", &QueryFilter::default()).unwrap();
+        let active = registry
+            .count_by_query(
+                "docs",
+                &QueryFilter {
+                    expr: Some(crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") }),
+                    similar_to: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(total, 2);
+        assert_eq!(active, 1);
+    }
+
+    #[test]
+    fn count_by_query_on_a_missing_namespace_is_an_error() {
+        let registry = NamespaceRegistry::default();
+        assert!(registry.count_by_query("missing", &QueryFilter::default()).is_err());
+    }
+
+    #[test]
+    fn exists_finds_a_row_by_id_and_nothing_else() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", Vec::new())).unwrap();
+
+        assert!(registry.exists("docs", "a").unwrap());
+        assert!(!registry.exists("docs", "b").unwrap());
+    }
+
+    #[test]
+    fn exists_by_query_short_circuits_on_the_first_match() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "active"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+
+        let filter = QueryFilter {
+            expr: Some(crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") }),
+            similar_to: None,
+        };
+        assert!(registry.exists_by_query("docs", &filter).unwrap());
+
+        let no_match = QueryFilter {
+            expr: Some(crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("archived") }),
+            similar_to: None,
+        };
+        assert!(!registry.exists_by_query("docs", &no_match).unwrap());
+    }
+
+    #[test]
+    fn create_view_builds_its_bitmap_from_existing_rows() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "active"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+        registry
+            .apply_write("docs", Document {
+                id: "b".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "archived"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+
+        registry
+            .create_view(
+                "docs",
+                "active",
+                ViewConfig {
+                    filter: crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") },
+                    projection: None,
+                },
+            )
+            .unwrap();
+
+        let page = registry.query_by_filter("docs@active", &QueryFilter::default(), &[], None, 10).unwrap();
+        assert_eq!(page.rows.iter().map(|doc| doc.id.clone()).collect::<Vec<_>>(), vec!["a"]);
+    }
+
+    #[test]
+    fn a_view_bitmap_tracks_writes_that_change_membership() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .create_view(
+                "docs",
+                "active",
+                ViewConfig {
+                    filter: crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") },
+                    projection: None,
+                },
+            )
+            .unwrap_err();
+        // Creating a view requires the namespace to already exist.
+        registry.apply_write("docs", Document::new("a", Vec::new())).unwrap();
+        registry
+            .create_view(
+                "docs",
+                "active",
+                ViewConfig {
+                    filter: crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") },
+                    projection: None,
+                },
+            )
+            .unwrap();
+
+        // Still not active: the bitmap should stay empty.
+        assert_eq!(registry.count_by_query("docs@active", &QueryFilter::default()).unwrap(), 0);
+
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "active"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+        assert_eq!(registry.count_by_query("docs@active", &QueryFilter::default()).unwrap(), 1);
+
+        // Flipping the attribute back out of the view removes it again.
+        let mut patch = serde_json::Map::new();
+        patch.insert("status".to_string(), serde_json::json!("archived"));
+        registry.update_by_query("docs", &QueryFilter::default(), &patch).unwrap();
+        assert_eq!(registry.count_by_query("docs@active", &QueryFilter::default()).unwrap(), 0);
+    }
+
+    #[test]
+    fn a_view_bitmap_drops_deleted_ids() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "active"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+        registry
+            .create_view(
+                "docs",
+                "active",
+                ViewConfig {
+                    filter: crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") },
+                    projection: None,
+                },
+            )
+            .unwrap();
+
+        registry.delete_by_query("docs", &QueryFilter::default()).unwrap();
+        assert_eq!(registry.count_by_query("docs@active", &QueryFilter::default()).unwrap(), 0);
+    }
+
+    #[test]
+    fn a_views_projection_strips_attributes_down_to_the_listed_keys() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write("docs", Document {
+                id: "a".to_string(),
+                vector: Vec::new(),
+                attributes: serde_json::json!({"status": "active", "tenant": "acme", "secret": "shh"}),
+                embedding: None,
+                embedding_model: None,
+            })
+            .unwrap();
+        registry
+            .create_view(
+                "docs",
+                "active",
+                ViewConfig {
+                    filter: crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") },
+                    projection: Some(vec!["tenant".to_string()]),
+                },
+            )
+            .unwrap();
+
+        let page = registry.query_by_filter("docs@active", &QueryFilter::default(), &[], None, 10).unwrap();
+        assert_eq!(page.rows[0].attributes, serde_json::json!({"tenant": "acme"}));
+    }
+
+    #[test]
+    fn querying_an_unregistered_view_fails() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", Vec::new())).unwrap();
+        let err = registry
+            .query_by_filter("docs@missing", &QueryFilter::default(), &[], None, 10)
+            .unwrap_err();
+        assert!(matches!(err, CoreError::ViewNotFound { .. }));
+    }
+
+    #[test]
+    fn drop_view_removes_it_and_reports_whether_it_existed() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", Vec::new())).unwrap();
+        registry
+            .create_view(
+                "docs",
+                "active",
+                ViewConfig {
+                    filter: crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") },
+                    projection: None,
+                },
+            )
+            .unwrap();
+
+        assert!(registry.drop_view("docs", "active"));
+        assert!(!registry.drop_view("docs", "active"));
+        assert!(matches!(
+            registry.query_by_filter("docs@active", &QueryFilter::default(), &[], None, 10).unwrap_err(),
+            CoreError::ViewNotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn patch_vector_replaces_the_vector_and_keeps_attributes() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "a".to_string(),
+                    vector: vec![1.0, 0.0],
+                    attributes: serde_json::json!({"status": "active"}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap();
+
+        registry.patch_vector("docs", "a", vec![0.0, 1.0]).unwrap();
+
+        let ns = registry.namespaces.get("docs").unwrap();
+        let doc = ns.get("a").unwrap();
+        assert_eq!(doc.vector, vec![0.0, 1.0]);
+        assert_eq!(doc.attributes, serde_json::json!({"status": "active"}));
+    }
+
+    #[test]
+    fn patch_vector_on_an_unknown_row_fails() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        let err = registry.patch_vector("docs", "missing", vec![1.0]).unwrap_err();
+        assert!(matches!(err, CoreError::RowNotFound { .. }));
+    }
+
+    #[test]
+    fn patch_vector_rejects_the_wrong_dimension() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                dimension: Some(2),
+                ..Default::default()
+            },
+        );
+        registry.apply_write("docs", Document::new("a", vec![1.0, 0.0])).unwrap();
+        let err = registry.patch_vector("docs", "a", vec![1.0]).unwrap_err();
+        assert!(matches!(err, CoreError::DimensionMismatch { expected: 2, found: 1 }));
+    }
+
+    #[test]
+    fn delete_by_query_on_unknown_namespace_fails() {
+        let mut registry = NamespaceRegistry::default();
+        let err = registry
+            .delete_by_query("missing", &QueryFilter::default())
+            .unwrap_err();
+        assert!(matches!(err, CoreError::NamespaceNotFound(_)));
+    }
+
+    struct StubEmbedder;
+
+    impl Embedder for StubEmbedder {
+        fn embed(&self, text: &str, _model: &str) -> Result<Vec<f32>> {
+            Ok(vec![text.len() as f32])
+        }
+    }
+
+    #[test]
+    fn embedding_hook_resolves_to_a_vector_before_quota_checks() {
+        let mut registry = NamespaceRegistry::default();
+        registry.set_embedder(Arc::new(StubEmbedder));
+
+        registry
+            .apply_write(
+                "docs",
+                Document::with_embedding(
+                    "a",
+                    crate::document::EmbeddingHook {
+                        text: "hello".to_string(),
+                        model: "test-model".to_string(),
+                    },
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn embedding_hook_without_embedder_fails() {
+        let mut registry = NamespaceRegistry::default();
+        let err = registry
+            .apply_write(
+                "docs",
+                Document::with_embedding(
+                    "a",
+                    crate::document::EmbeddingHook {
+                        text: "hello".to_string(),
+                        model: "test-model".to_string(),
+                    },
+                ),
+            )
+            .unwrap_err();
+        assert!(matches!(err, CoreError::EmbeddingFailed(_)));
+    }
+
+    #[test]
+    fn apply_write_idempotent_ignores_a_retried_request_id() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write_idempotent("docs", Document::new("a", vec![1.0]), "req-1")
+            .unwrap();
+        // A retry with the same request_id and a different payload must
+        // not overwrite the original row.
+        registry
+            .apply_write_idempotent("docs", Document::new("a", vec![99.0]), "req-1")
+            .unwrap();
+
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+        assert_eq!(
+            registry.namespaces.get("docs").unwrap().get("a").unwrap().vector,
+            vec![1.0]
+        );
+    }
+
+    #[test]
+    fn dedupe_removes_all_but_one_row_per_near_duplicate_cluster() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", vec![1.0, 0.0])).unwrap();
+        registry.apply_write("docs", Document::new("b", vec![0.99, 0.01])).unwrap();
+        registry.apply_write("docs", Document::new("c", vec![0.0, 1.0])).unwrap();
+
+        let removed = registry.dedupe("docs", 0.95).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 2);
+    }
+
+    #[test]
+    fn find_duplicates_on_unknown_namespace_fails() {
+        let registry = NamespaceRegistry::default();
+        let err = registry.find_duplicates("missing", 0.9).unwrap_err();
+        assert!(matches!(err, CoreError::NamespaceNotFound(_)));
+    }
+
+    #[test]
+    fn stats_exposes_the_schema_inferred_from_the_first_write() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "a".to_string(),
+                    vector: vec![1.0],
+                    attributes: serde_json::json!({"status": "active", "score": 0.5}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap();
+
+        let schema = registry.stats("docs", AnnParams::default()).unwrap().attr_schema;
+        assert_eq!(schema.fields().get("status"), Some(&AttrType::Keyword));
+        assert_eq!(schema.fields().get("score"), Some(&AttrType::Numeric));
+    }
+
+    #[test]
+    fn a_namespaces_configured_ann_params_override_the_process_wide_default() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                ann_params: Some(AnnParams { nlist: 512, nprobe: 64, ..Default::default() }),
+                ..Default::default()
+            },
+        );
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+
+        let stats = registry.stats("docs", AnnParams::default()).unwrap();
+        assert_eq!(stats.effective_ann_params, AnnParams { nlist: 512, nprobe: 64, ..Default::default() });
+    }
+
+    #[test]
+    fn an_unconfigured_namespace_falls_back_to_the_process_wide_default() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+
+        let stats = registry.stats("docs", AnnParams { nlist: 256, nprobe: 16, ..Default::default() }).unwrap();
+        assert_eq!(stats.effective_ann_params, AnnParams { nlist: 256, nprobe: 16, ..Default::default() });
+    }
+
+    #[test]
+    fn a_write_with_a_conflicting_attribute_type_is_rejected() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "a".to_string(),
+                    vector: vec![1.0],
+                    attributes: serde_json::json!({"score": 0.5}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap();
+
+        let err = registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "b".to_string(),
+                    vector: vec![1.0],
+                    attributes: serde_json::json!({"score": "high"}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, CoreError::AttrTypeMismatch { .. }));
+    }
+
+    #[test]
+    fn set_webhooks_fires_namespace_created_only_on_first_write() {
+        use crate::notifier::{Notifier, WebhookEvent};
+        use std::sync::Mutex as StdMutex;
+
+        #[derive(Default)]
+        struct RecordingNotifier {
+            seen: StdMutex<Vec<WebhookEvent>>,
+        }
+        impl Notifier for RecordingNotifier {
+            fn notify(&self, event: &WebhookEvent) -> Result<()> {
+                self.seen.lock().unwrap().push(event.clone());
+                Ok(())
+            }
+        }
+
+        let mut registry = NamespaceRegistry::default();
+        let dispatcher = WebhookDispatcher::default();
+        let recorder = Arc::new(RecordingNotifier::default());
+        dispatcher.register(recorder.clone());
+        registry.set_webhooks(dispatcher);
+
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        registry.apply_write("docs", Document::new("b", vec![1.0])).unwrap();
+
+        let seen = recorder.seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(matches!(&seen[0], WebhookEvent::NamespaceCreated { namespace } if namespace == "docs"));
+    }
+
+    #[test]
+    fn apply_transaction_commits_every_namespace_on_success() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_transaction(vec![
+                ("chunks".to_string(), Document::new("a", vec![1.0])),
+                ("metadata".to_string(), Document::new("a", vec![1.0])),
+            ])
+            .unwrap();
+
+        assert_eq!(registry.stats("chunks", AnnParams::default()).unwrap().row_count, 1);
+        assert_eq!(registry.stats("metadata", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn apply_transaction_rolls_back_every_namespace_on_failure() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "metadata",
+            NamespaceConfig {
+                dimension: Some(3),
+                ..Default::default()
+            },
+        );
+        // "chunks" starts empty; "metadata" already has one row that the
+        // failing write in the same transaction must not disturb.
+        registry
+            .apply_write("metadata", Document::new("existing", vec![1.0, 0.0, 0.0]))
+            .unwrap();
+
+        let err = registry
+            .apply_transaction(vec![
+                ("chunks".to_string(), Document::new("a", vec![1.0])),
+                // Wrong dimension: this write fails, so "chunks" must roll
+                // back to not having namespace "chunks" at all.
+                ("metadata".to_string(), Document::new("a", vec![1.0, 0.0])),
+            ])
+            .unwrap_err();
+
+        assert!(matches!(err, CoreError::DimensionMismatch { .. }));
+        assert!(registry.stats("chunks", AnnParams::default()).is_none());
+        assert_eq!(registry.stats("metadata", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn apply_transaction_does_not_leak_a_webhook_for_a_namespace_it_rolls_back() {
+        use crate::notifier::{Notifier, WebhookEvent};
+        use std::sync::Mutex as StdMutex;
+
+        #[derive(Default)]
+        struct RecordingNotifier {
+            seen: StdMutex<Vec<WebhookEvent>>,
+        }
+        impl Notifier for RecordingNotifier {
+            fn notify(&self, event: &WebhookEvent) -> Result<()> {
+                self.seen.lock().unwrap().push(event.clone());
+                Ok(())
+            }
+        }
+
+        let mut registry = NamespaceRegistry::default();
+        let dispatcher = WebhookDispatcher::default();
+        let recorder = Arc::new(RecordingNotifier::default());
+        dispatcher.register(recorder.clone());
+        registry.set_webhooks(dispatcher);
+        registry.configure(
+            "metadata",
+            NamespaceConfig {
+                dimension: Some(3),
+                ..Default::default()
+            },
+        );
+
+        // "chunks" doesn't exist yet, so the first write in the transaction
+        // would fire a NamespaceCreated webhook under the old (non-deferred)
+        // dispatch — but the second write fails, so the whole transaction,
+        // including that event, must roll back.
+        registry
+            .apply_transaction(vec![
+                ("chunks".to_string(), Document::new("a", vec![1.0])),
+                ("metadata".to_string(), Document::new("a", vec![1.0, 0.0])),
+            ])
+            .unwrap_err();
+
+        assert!(registry.stats("chunks", AnnParams::default()).is_none());
+        assert!(recorder.seen.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn apply_transaction_rolls_back_schema_observations_on_failure() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "metadata",
+            NamespaceConfig {
+                dimension: Some(3),
+                ..Default::default()
+            },
+        );
+        registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "existing".to_string(),
+                    vector: vec![1.0],
+                    attributes: serde_json::json!({"status": "active"}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap();
+
+        // The "docs" write in this transaction introduces a brand-new
+        // attribute key ("priority"); the "metadata" write then fails, so
+        // that schema observation must not stick around afterward.
+        registry
+            .apply_transaction(vec![
+                (
+                    "docs".to_string(),
+                    Document {
+                        id: "b".to_string(),
+                        vector: vec![1.0],
+                        attributes: serde_json::json!({"priority": 1}),
+                        embedding: None,
+                        embedding_model: None,
+                    },
+                ),
+                ("metadata".to_string(), Document::new("a", vec![1.0, 0.0])),
+            ])
+            .unwrap_err();
+
+        // If the rolled-back transaction's schema observation had stuck,
+        // "priority" would already be known as numeric and this differently
+        // typed write would be rejected with AttrTypeMismatch instead of
+        // being accepted as the schema's first sighting of the key.
+        registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "c".to_string(),
+                    vector: vec![1.0],
+                    attributes: serde_json::json!({"priority": "not-a-number"}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn apply_transaction_rolls_back_view_bitmaps_on_failure() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "metadata",
+            NamespaceConfig {
+                dimension: Some(3),
+                ..Default::default()
+            },
+        );
+        registry.apply_write("docs", Document::new("existing", vec![1.0])).unwrap();
+        registry
+            .create_view(
+                "docs",
+                "active",
+                ViewConfig {
+                    filter: crate::filter::FilterExpr::AttrEq { key: "status".to_string(), value: serde_json::json!("active") },
+                    projection: None,
+                },
+            )
+            .unwrap();
+
+        registry
+            .apply_transaction(vec![
+                (
+                    "docs".to_string(),
+                    Document {
+                        id: "b".to_string(),
+                        vector: vec![1.0],
+                        attributes: serde_json::json!({"status": "active"}),
+                        embedding: None,
+                        embedding_model: None,
+                    },
+                ),
+                ("metadata".to_string(), Document::new("a", vec![1.0, 0.0])),
+            ])
+            .unwrap_err();
+
+        assert_eq!(registry.count_by_query("docs@active", &QueryFilter::default()).unwrap(), 0);
+    }
+
+    #[test]
+    fn writes_and_reads_through_an_alias_land_on_the_target_namespace() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs-v2", Document::new("a", vec![1.0])).unwrap();
+        registry.set_alias("docs", "docs-v2");
+
+        registry.apply_write("docs", Document::new("b", vec![2.0])).unwrap();
+
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 2);
+        assert_eq!(registry.stats("docs-v2", AnnParams::default()).unwrap().row_count, 2);
+        assert!(registry.stats("docs", AnnParams::default()).is_some());
+    }
+
+    #[test]
+    fn removing_an_alias_makes_the_name_resolve_to_itself_again() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs-v2", Document::new("a", vec![1.0])).unwrap();
+        registry.set_alias("docs", "docs-v2");
+        assert!(registry.remove_alias("docs"));
+
+        registry.apply_write("docs", Document::new("b", vec![2.0])).unwrap();
+
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+        assert_eq!(registry.stats("docs-v2", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn wipe_vectors_clears_vectors_but_keeps_attributes() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "a".to_string(),
+                    vector: vec![1.0, 0.0],
+                    attributes: serde_json::json!({"title": "hello"}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap();
+
+        let wiped = registry.wipe_vectors("docs").unwrap();
+        assert_eq!(wiped, 1);
+
+        let ns = registry.namespaces.get("docs").unwrap();
+        let doc = ns.get("a").unwrap();
+        assert!(doc.vector.is_empty());
+        assert_eq!(doc.attributes, serde_json::json!({"title": "hello"}));
+
+        let stats = registry.stats("docs", AnnParams::default()).unwrap();
+        assert_eq!(stats.pending_reembed, 1);
+        assert_eq!(stats.row_count, 1);
+    }
+
+    #[test]
+    fn wipe_vectors_is_idempotent_on_an_already_empty_vector() {
+        let mut registry = NamespaceRegistry::default();
+        registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        registry.wipe_vectors("docs").unwrap();
+        let wiped_again = registry.wipe_vectors("docs").unwrap();
+        assert_eq!(wiped_again, 0);
+    }
+
+    #[test]
+    fn patch_vector_re_embeds_a_wiped_row() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write(
+                "docs",
+                Document {
+                    id: "a".to_string(),
+                    vector: vec![1.0, 0.0],
+                    attributes: serde_json::json!({"title": "hello"}),
+                    embedding: None,
+                    embedding_model: None,
+                },
+            )
+            .unwrap();
+        registry.wipe_vectors("docs").unwrap();
+
+        registry.patch_vector("docs", "a", vec![0.0, 1.0]).unwrap();
+
+        let stats = registry.stats("docs", AnnParams::default()).unwrap();
+        assert_eq!(stats.pending_reembed, 0);
+        let ns = registry.namespaces.get("docs").unwrap();
+        assert_eq!(ns.get("a").unwrap().vector, vec![0.0, 1.0]);
+    }
+
+    fn tmp_wal_path(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "elax-core-wal-load-{name}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("docs.wal")
+    }
+
+    #[test]
+    fn load_from_wal_replays_every_record_in_order_with_progress_updates() {
+        let path = tmp_wal_path("basic");
+        let mut writer = elax_store::WalWriter::create(&path).unwrap();
+        writer.append(&serde_json::to_vec(&Document::new("a", vec![1.0])).unwrap()).unwrap();
+        writer.append(&serde_json::to_vec(&Document::new("b", vec![2.0])).unwrap()).unwrap();
+        writer.append(&serde_json::to_vec(&Document::new("c", vec![3.0])).unwrap()).unwrap();
+
+        let mut registry = NamespaceRegistry::default();
+        let mut progress = Vec::new();
+        let applied = registry
+            .load_from_wal("docs", &path, &WalLoadOptions { max_parallelism: 2 }, |done, total| {
+                progress.push((done, total))
+            })
+            .unwrap();
+
+        assert_eq!(applied, 3);
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 3);
+        assert_eq!(progress, vec![(2, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn load_from_wal_bypasses_quota_checks() {
+        let path = tmp_wal_path("quota");
+        let mut writer = elax_store::WalWriter::create(&path).unwrap();
+        writer.append(&serde_json::to_vec(&Document::new("a", vec![1.0])).unwrap()).unwrap();
+        writer.append(&serde_json::to_vec(&Document::new("b", vec![2.0])).unwrap()).unwrap();
+
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                quota: Quota { max_rows: Some(1), ..Default::default() },
+                ..Default::default()
+            },
+        );
+
+        let applied = registry
+            .load_from_wal("docs", &path, &WalLoadOptions::default(), |_, _| {})
+            .unwrap();
+        assert_eq!(applied, 2);
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 2);
+    }
+
+    #[test]
+    fn load_from_wal_on_a_missing_file_applies_nothing() {
+        let dir = tmp_wal_path("missing");
+        let missing = dir.parent().unwrap().join("does-not-exist.wal");
+        let mut registry = NamespaceRegistry::default();
+        let applied = registry
+            .load_from_wal("docs", &missing, &WalLoadOptions::default(), |_, _| {})
+            .unwrap();
+        assert_eq!(applied, 0);
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 0);
+    }
+
+    fn test_model(name: &str) -> EmbeddingModel {
+        EmbeddingModel {
+            name: name.to_string(),
+            dimension: 3,
+            metric: crate::tiered::Metric::Cosine,
+            normalized: true,
+        }
+    }
+
+    #[test]
+    fn a_write_declaring_the_configured_model_is_accepted() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                embedding_model: Some(test_model("text-embed-v3")),
+                ..Default::default()
+            },
+        );
+
+        let mut doc = Document::new("a", vec![1.0, 0.0, 0.0]);
+        doc.embedding_model = Some(test_model("text-embed-v3"));
+        registry.apply_write("docs", doc).unwrap();
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn a_write_declaring_a_different_model_than_configured_is_rejected() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                embedding_model: Some(test_model("text-embed-v3")),
+                ..Default::default()
+            },
+        );
+
+        let mut doc = Document::new("a", vec![1.0, 0.0, 0.0]);
+        doc.embedding_model = Some(test_model("text-embed-v2"));
+        let err = registry.apply_write("docs", doc).unwrap_err();
+        assert!(matches!(err, CoreError::EmbeddingModelMismatch { .. }));
+    }
+
+    #[test]
+    fn a_write_with_no_declared_model_skips_the_guard_even_if_one_is_configured() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                embedding_model: Some(test_model("text-embed-v3")),
+                ..Default::default()
+            },
+        );
+
+        registry.apply_write("docs", Document::new("a", vec![1.0, 0.0, 0.0])).unwrap();
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn check_embedding_model_rejects_a_query_against_a_different_model() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                embedding_model: Some(test_model("text-embed-v3")),
+                ..Default::default()
+            },
+        );
+
+        let err = registry
+            .check_embedding_model("docs", &test_model("text-embed-v2"))
+            .unwrap_err();
+        assert!(matches!(err, CoreError::EmbeddingModelMismatch { .. }));
+        registry.check_embedding_model("docs", &test_model("text-embed-v3")).unwrap();
+    }
+
+    #[test]
+    fn check_embedding_model_accepts_anything_when_unconfigured() {
+        let registry = NamespaceRegistry::default();
+        registry.check_embedding_model("docs", &test_model("any-model")).unwrap();
+    }
+
+    #[test]
+    fn wipe_vectors_on_unknown_namespace_fails() {
+        let mut registry = NamespaceRegistry::default();
+        let err = registry.wipe_vectors("missing").unwrap_err();
+        assert!(matches!(err, CoreError::NamespaceNotFound(_)));
+    }
+
+    #[test]
+    fn apply_write_idempotent_applies_distinct_request_ids() {
+        let mut registry = NamespaceRegistry::default();
+        registry
+            .apply_write_idempotent("docs", Document::new("a", vec![1.0]), "req-1")
+            .unwrap();
+        registry
+            .apply_write_idempotent("docs", Document::new("b", vec![2.0]), "req-2")
+            .unwrap();
+
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 2);
+    }
+
+    #[test]
+    fn a_write_with_an_empty_id_fails_without_an_id_strategy_configured() {
+        let mut registry = NamespaceRegistry::default();
+        let err = registry.apply_write("docs", Document::new("", vec![1.0])).unwrap_err();
+        assert!(matches!(err, CoreError::MissingDocumentId));
+    }
+
+    #[test]
+    fn uuid_v7_strategy_assigns_and_returns_a_fresh_id() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                id_strategy: IdStrategy::UuidV7,
+                ..Default::default()
+            },
+        );
+
+        let id = registry.apply_write("docs", Document::new("", vec![1.0])).unwrap();
+        assert!(!id.is_empty());
+        assert_eq!(registry.stats("docs", AnnParams::default()).unwrap().row_count, 1);
+    }
+
+    #[test]
+    fn a_client_supplied_id_is_kept_even_with_a_generation_strategy_configured() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                id_strategy: IdStrategy::Snowflake,
+                ..Default::default()
+            },
+        );
+
+        let id = registry.apply_write("docs", Document::new("a", vec![1.0])).unwrap();
+        assert_eq!(id, "a");
+    }
+
+    #[test]
+    fn apply_transaction_returns_generated_ids_in_order() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                id_strategy: IdStrategy::Snowflake,
+                ..Default::default()
+            },
+        );
+
+        let ids = registry
+            .apply_transaction(vec![
+                ("docs".to_string(), Document::new("", vec![1.0])),
+                ("docs".to_string(), Document::new("b", vec![2.0])),
+                ("docs".to_string(), Document::new("", vec![3.0])),
+            ])
+            .unwrap();
+
+        assert_eq!(ids.len(), 3);
+        assert!(!ids[0].is_empty());
+        assert_eq!(ids[1], "b");
+        assert!(!ids[2].is_empty());
+        assert_ne!(ids[0], ids[2]);
+    }
+
+    #[test]
+    fn validate_top_k_rejects_requests_past_the_configured_quota() {
+        let mut registry = NamespaceRegistry::default();
+        registry.configure(
+            "docs",
+            NamespaceConfig {
+                quota: Quota { max_top_k: Some(100), ..Default::default() },
+                ..Default::default()
+            },
+        );
+
+        registry.validate_top_k("docs", 100).unwrap();
+        let err = registry.validate_top_k("docs", 101).unwrap_err();
+        assert!(matches!(err, CoreError::QuotaExceeded(_)));
+    }
+
+    #[test]
+    fn validate_top_k_accepts_anything_when_unconfigured() {
+        let registry = NamespaceRegistry::default();
+        registry.validate_top_k("docs", 1_000_000).unwrap();
+    }
+
+    #[test]
+    fn apply_write_with_metrics_feeds_the_drift_tracker() {
+        let mut registry = NamespaceRegistry::default();
+        let metrics = crate::metrics::NamespaceMetrics::default();
+
+        registry
+            .apply_write_with_metrics("docs", Document::new("a", vec![3.0, 4.0]), Some(&metrics))
+            .unwrap();
+        metrics.drift.record_baseline();
+        assert!(!metrics.drift.should_retrain(crate::settings::DriftThresholds::default()));
+
+        for i in 0..10 {
+            registry
+                .apply_write_with_metrics(
+                    "docs",
+                    Document::new(format!("b{i}"), vec![30.0, 40.0]),
+                    Some(&metrics),
+                )
+                .unwrap();
+        }
+        assert!(metrics.drift.should_retrain(crate::settings::DriftThresholds::default()));
+    }
+}
@@ -0,0 +1,193 @@
+//! Server-side reranking: a second-pass scorer, typically a cross-encoder,
+//! applied to the top candidates of a vector/BM25 merge before results go
+//! back to the client.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+use elax_index::ScoredRow;
+
+/// One candidate handed to a [`Reranker`]: its id (for re-attaching the
+/// score afterwards) plus whatever attributes the caller wants the model to
+/// see — on-disk parts don't carry attributes, so callers supply them from
+/// whatever row store still has them (e.g. the memtable or FTS index).
+#[derive(Debug, Clone, Serialize)]
+pub struct RerankCandidate {
+    pub id: String,
+    pub attributes: serde_json::Value,
+}
+
+/// Reorders retrieval candidates against the original query text.
+/// Implementations may call out to a cross-encoder model server (see
+/// [`HttpReranker`]) or be swapped for a deterministic stub in tests.
+pub trait Reranker: Send + Sync {
+    /// Returns one relevance score per candidate, in the same order as
+    /// `candidates`.
+    fn rerank(&self, query_text: &str, model: &str, candidates: &[RerankCandidate]) -> Result<Vec<f32>>;
+}
+
+#[derive(Serialize)]
+struct RerankRequestBody<'a> {
+    model: &'a str,
+    query: &'a str,
+    documents: &'a [serde_json::Value],
+}
+
+#[derive(Deserialize)]
+struct RerankResponseBody {
+    scores: Vec<f32>,
+}
+
+/// Calls an HTTP cross-encoder reranking endpoint over plain HTTP/1.1,
+/// mirroring [`crate::embedder::HttpEmbedder`]'s std-only transport.
+#[derive(Debug, Clone)]
+pub struct HttpReranker {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+    pub api_key: Option<String>,
+}
+
+impl HttpReranker {
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            path: "/v1/rerank".to_string(),
+            api_key: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+impl Reranker for HttpReranker {
+    fn rerank(&self, query_text: &str, model: &str, candidates: &[RerankCandidate]) -> Result<Vec<f32>> {
+        let documents: Vec<serde_json::Value> =
+            candidates.iter().map(|c| c.attributes.clone()).collect();
+        let body = serde_json::to_vec(&RerankRequestBody {
+            model,
+            query: query_text,
+            documents: &documents,
+        })?;
+
+        let mut head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.path,
+            self.host,
+            body.len()
+        );
+        if let Some(api_key) = &self.api_key {
+            head.push_str(&format!("Authorization: Bearer {api_key}\r\n"));
+        }
+        head.push_str("\r\n");
+
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&body)?;
+
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+
+        let response = String::from_utf8_lossy(&raw);
+        let payload = response
+            .split("\r\n\r\n")
+            .nth(1)
+            .ok_or_else(|| CoreError::EmbeddingFailed("malformed HTTP response".to_string()))?;
+        let parsed: RerankResponseBody = serde_json::from_str(payload)?;
+        Ok(parsed.scores)
+    }
+}
+
+/// Per-request rerank configuration: the cross-encoder model to use and how
+/// many top candidates to send to it.
+#[derive(Debug, Clone)]
+pub struct RerankConfig {
+    pub model: String,
+    pub top_n: usize,
+}
+
+/// Rerank the first `config.top_n` of `results` against `query_text`,
+/// looking up each candidate's attributes in `attributes` (rows with no
+/// entry are sent as `null`), and leave the remainder in its original
+/// order appended after the reranked prefix.
+pub fn rerank_top_n(
+    results: Vec<ScoredRow>,
+    query_text: &str,
+    attributes: &HashMap<String, serde_json::Value>,
+    reranker: &dyn Reranker,
+    config: &RerankConfig,
+) -> Result<Vec<ScoredRow>> {
+    let split = config.top_n.min(results.len());
+    let (head, tail) = results.split_at(split);
+    let tail = tail.to_vec();
+
+    let candidates: Vec<RerankCandidate> = head
+        .iter()
+        .map(|row| RerankCandidate {
+            id: row.id.clone(),
+            attributes: attributes.get(&row.id).cloned().unwrap_or(serde_json::Value::Null),
+        })
+        .collect();
+
+    let scores = reranker.rerank(query_text, &config.model, &candidates)?;
+    let mut reranked: Vec<ScoredRow> = head
+        .iter()
+        .zip(scores)
+        .map(|(row, score)| ScoredRow {
+            id: row.id.clone(),
+            score,
+        })
+        .collect();
+    reranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    reranked.extend(tail);
+    Ok(reranked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ReverseLengthReranker;
+
+    impl Reranker for ReverseLengthReranker {
+        fn rerank(&self, _query_text: &str, _model: &str, candidates: &[RerankCandidate]) -> Result<Vec<f32>> {
+            Ok(candidates
+                .iter()
+                .map(|c| c.attributes.as_str().map(|s| s.len() as f32).unwrap_or(0.0))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn rerank_top_n_reorders_only_the_requested_prefix() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: 0.9 },
+            ScoredRow { id: "b".to_string(), score: 0.8 },
+            ScoredRow { id: "c".to_string(), score: 0.1 },
+        ];
+        let mut attributes = HashMap::new();
+        attributes.insert("a".to_string(), serde_json::Value::String("x".to_string()));
+        attributes.insert("b".to_string(), serde_json::Value::String("xxxxx".to_string()));
+
+        let reranked = rerank_top_n(
+            results,
+            "query",
+            &attributes,
+            &ReverseLengthReranker,
+            &RerankConfig { model: "m".to_string(), top_n: 2 },
+        )
+        .unwrap();
+
+        assert_eq!(reranked[0].id, "b");
+        assert_eq!(reranked[1].id, "a");
+        assert_eq!(reranked[2].id, "c");
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use elax_store::{Generation, ObjectStore};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, Result};
+
+/// Well-known key under which the router manifest is persisted.
+pub const ROUTER_STATE_KEY: &str = "router.json";
+
+/// Identifier for an elax-api / indexer process participating in the cluster.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NodeId(pub String);
+
+impl NodeId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+}
+
+/// Persisted namespace-to-node assignment, monotonically fenced by `epoch`.
+///
+/// Any mutation that changes ownership bumps `epoch`; writers that observe a
+/// stale epoch must refuse to act rather than clobber a newer assignment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouterState {
+    pub epoch: u64,
+    pub assignments: HashMap<String, NodeId>,
+    /// How many shards a namespace has been split into by
+    /// [`crate::sharding::should_shard`], keyed by namespace. A namespace
+    /// absent from this map has exactly one (unsharded) shard.
+    #[serde(default)]
+    pub shard_counts: HashMap<String, usize>,
+}
+
+impl RouterState {
+    pub fn owner_of(&self, namespace: &str) -> Option<&NodeId> {
+        self.assignments.get(namespace)
+    }
+
+    /// How many shards `namespace` is currently split into — `1` if it has
+    /// never been sharded.
+    pub fn shard_count(&self, namespace: &str) -> usize {
+        self.shard_counts.get(namespace).copied().unwrap_or(1)
+    }
+
+    /// Reassign `namespace` to `node`, bumping the epoch. Used for ownership
+    /// handoff (e.g. rebalancing or failover).
+    pub fn reassign(&mut self, namespace: impl Into<String>, node: NodeId) {
+        self.epoch += 1;
+        self.assignments.insert(namespace.into(), node);
+    }
+
+    /// Record that `namespace` has been split into `shard_count` shards,
+    /// bumping the epoch the same way [`Self::reassign`] does — a stale
+    /// reader must see this the moment it reloads, since it changes how a
+    /// write should be routed (by `shard_for_id`, not to the namespace as
+    /// a whole).
+    pub fn set_shard_count(&mut self, namespace: impl Into<String>, shard_count: usize) {
+        self.epoch += 1;
+        self.shard_counts.insert(namespace.into(), shard_count);
+    }
+
+    /// Apply a state observed from a peer, only if its epoch is not older
+    /// than ours (an equal epoch is applied too). Returns an error if
+    /// `other` is stale relative to `self`.
+    pub fn merge_if_newer(&mut self, other: &RouterState) -> Result<()> {
+        if other.epoch < self.epoch {
+            return Err(CoreError::StaleEpoch {
+                got: other.epoch,
+                current: self.epoch,
+            });
+        }
+        self.epoch = other.epoch;
+        self.assignments = other.assignments.clone();
+        self.shard_counts = other.shard_counts.clone();
+        Ok(())
+    }
+
+    /// Load the router manifest from `store`, along with the generation it
+    /// was read at (`None` if the manifest does not exist yet).
+    pub fn load(store: &dyn ObjectStore) -> Result<(RouterState, Option<Generation>)> {
+        match store.get(ROUTER_STATE_KEY)? {
+            Some((bytes, generation)) => {
+                let state: RouterState = serde_json::from_slice(&bytes)?;
+                Ok((state, Some(generation)))
+            }
+            None => Ok((RouterState::default(), None)),
+        }
+    }
+
+    /// Persist `self` to `store`, failing if the manifest has moved on from
+    /// `expected_generation` since it was last read.
+    pub fn save_if_match(
+        &self,
+        store: &dyn ObjectStore,
+        expected_generation: Option<Generation>,
+    ) -> Result<Generation> {
+        let bytes = serde_json::to_vec(self)?;
+        Ok(store.put_if_match(ROUTER_STATE_KEY, expected_generation, bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reassign_bumps_epoch() {
+        let mut state = RouterState::default();
+        state.reassign("docs", NodeId::new("node-a"));
+        assert_eq!(state.epoch, 1);
+        assert_eq!(state.owner_of("docs"), Some(&NodeId::new("node-a")));
+    }
+
+    #[test]
+    fn stale_merge_is_rejected() {
+        let mut state = RouterState::default();
+        state.reassign("docs", NodeId::new("node-a"));
+        let stale = RouterState::default();
+        assert!(state.merge_if_newer(&stale).is_err());
+    }
+
+    #[test]
+    fn unsharded_namespaces_report_one_shard() {
+        let state = RouterState::default();
+        assert_eq!(state.shard_count("docs"), 1);
+    }
+
+    #[test]
+    fn set_shard_count_bumps_epoch_and_is_observed_by_a_merge() {
+        let mut state = RouterState::default();
+        state.set_shard_count("docs", 4);
+        assert_eq!(state.epoch, 1);
+        assert_eq!(state.shard_count("docs"), 4);
+
+        let mut follower = RouterState::default();
+        follower.merge_if_newer(&state).unwrap();
+        assert_eq!(follower.shard_count("docs"), 4);
+    }
+}
@@ -0,0 +1,349 @@
+//! Pluggable scoring expressions: business ranking logic (popularity,
+//! recency, ...) combined with the raw vector score, without a second
+//! service in the loop. A [`ScoreExpr`] is built up in Rust rather than
+//! parsed from a query string — callers parsing a textual DSL (e.g. the
+//! `score = vector_score * 0.8 + log(attr.popularity) * 0.2 + ...` syntax
+//! clients write) are expected to compile it down to this tree.
+//!
+//! Both [`rescore`] and [`apply_boosts`] sort their output with
+//! [`f32::total_cmp`] rather than `partial_cmp().unwrap()` — a boost or
+//! score expression can produce `NaN`/`±inf` (e.g. an attribute too large
+//! for `f32` reads as `inf` through [`ScoreExpr::Attr`], and
+//! [`ScoreExpr::Mul`] of that by `0.0` is `NaN`; or a boost multiplying by
+//! an attacker-controlled factor), and `partial_cmp` returns `None` for any
+//! comparison involving `NaN`, which would panic the sort. `total_cmp`
+//! imposes IEEE 754's total order instead: `-NaN < -inf < ... < -0.0 < 0.0
+//! < ... < inf < NaN`, so a non-finite score sorts deterministically (a
+//! positive `NaN` first and a negative `NaN` last, with `inf` and `-inf`
+//! just inside them, among these descending-by-score results) rather than
+//! crashing or reordering nondeterministically from one run to the next.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use elax_index::ScoredRow;
+
+use crate::attr_order::{self, AttrOrder};
+use crate::document::Document;
+use crate::filter::FilterExpr;
+
+/// A scoring expression, evaluated per candidate after retrieval against
+/// its vector score and attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreExpr {
+    /// The candidate's raw vector similarity score.
+    VectorScore,
+    Const(f32),
+    /// `attributes[key]` as a number; missing or non-numeric reads as 0.0.
+    Attr(String),
+    Add(Box<ScoreExpr>, Box<ScoreExpr>),
+    Mul(Box<ScoreExpr>, Box<ScoreExpr>),
+    /// Natural log of the inner expression, floored at a tiny positive
+    /// value so a zero or negative input doesn't produce NaN/-inf.
+    Log(Box<ScoreExpr>),
+    /// Exponential decay of a unix-seconds timestamp attribute: 1.0 right
+    /// at `attr`, halving every `half_life_secs` of age. Missing or future
+    /// timestamps score 0.0 and 1.0 respectively.
+    RecencyDecay { attr: String, half_life_secs: f64 },
+}
+
+impl ScoreExpr {
+    pub fn eval(&self, vector_score: f32, attributes: &serde_json::Value, now: SystemTime) -> f32 {
+        match self {
+            ScoreExpr::VectorScore => vector_score,
+            ScoreExpr::Const(value) => *value,
+            ScoreExpr::Attr(key) => attributes
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0) as f32,
+            ScoreExpr::Add(a, b) => a.eval(vector_score, attributes, now) + b.eval(vector_score, attributes, now),
+            ScoreExpr::Mul(a, b) => a.eval(vector_score, attributes, now) * b.eval(vector_score, attributes, now),
+            ScoreExpr::Log(inner) => inner.eval(vector_score, attributes, now).max(f32::MIN_POSITIVE).ln(),
+            ScoreExpr::RecencyDecay { attr, half_life_secs } => {
+                let published_at = match attributes.get(attr).and_then(|v| v.as_u64()) {
+                    Some(ts) => ts,
+                    None => return 0.0,
+                };
+                let now_secs = now
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs_f64())
+                    .unwrap_or(0.0);
+                let age_secs = (now_secs - published_at as f64).max(0.0);
+                0.5_f64.powf(age_secs / half_life_secs) as f32
+            }
+        }
+    }
+}
+
+/// Re-score every candidate in `results` with `expr`, looking up each
+/// candidate's attributes in `attributes` (rows with no entry evaluate
+/// against `null`), then re-sort descending by the new score.
+pub fn rescore(
+    results: Vec<ScoredRow>,
+    expr: &ScoreExpr,
+    attributes: &std::collections::HashMap<String, serde_json::Value>,
+    now: SystemTime,
+) -> Vec<ScoredRow> {
+    let mut rescored: Vec<ScoredRow> = results
+        .into_iter()
+        .map(|row| {
+            let attrs = attributes.get(&row.id).cloned().unwrap_or(serde_json::Value::Null);
+            let score = expr.eval(row.score, &attrs, now);
+            ScoredRow { id: row.id, score }
+        })
+        .collect();
+    rescored.sort_by(|a, b| b.score.total_cmp(&a.score));
+    rescored
+}
+
+/// Re-sort `results` descending by score, breaking a tie with `order_by`
+/// rather than leaving it to whatever order the candidates arrived in —
+/// the same attribute lookup [`rescore`] and [`apply_boosts`] use, but as
+/// a tiebreaker after score rather than the ranking signal itself. A
+/// filter-only query with no score to rank by at all uses
+/// [`crate::registry::NamespaceRegistry::query_by_filter`]'s `order_by`
+/// as its primary ranking instead of calling this.
+pub fn order_by_attrs(
+    results: Vec<ScoredRow>,
+    order_by: &[AttrOrder],
+    attributes: &HashMap<String, serde_json::Value>,
+) -> Vec<ScoredRow> {
+    let mut ordered = results;
+    ordered.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then_with(|| {
+            let a_attrs = attributes.get(&a.id).cloned().unwrap_or(serde_json::Value::Null);
+            let b_attrs = attributes.get(&b.id).cloned().unwrap_or(serde_json::Value::Null);
+            let a_keys = attr_order::sort_keys(&a_attrs, &a.id, order_by);
+            let b_keys = attr_order::sort_keys(&b_attrs, &b.id, order_by);
+            attr_order::compare_keys(&a_keys, &a.id, &b_keys, &b.id, order_by)
+        })
+    });
+    ordered
+}
+
+/// How a [`Boost`] adjusts a matching candidate's score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoostOp {
+    Multiply(f32),
+    Add(f32),
+}
+
+/// A function-score-style boost: candidates whose attributes match
+/// `filter` get `op` applied to their score, so relevance tuning (e.g.
+/// "boost `source == \"docs\"` by 1.2") doesn't require re-embedding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Boost {
+    pub filter: FilterExpr,
+    pub op: BoostOp,
+}
+
+/// Apply every boost in `boosts` to `results` in order (a row matching
+/// several boosts accumulates all of them), looking up each row's
+/// attributes in `attributes` the same way [`rescore`] does, then re-sort
+/// descending by the adjusted score.
+pub fn apply_boosts(
+    results: Vec<ScoredRow>,
+    boosts: &[Boost],
+    attributes: &HashMap<String, serde_json::Value>,
+) -> Vec<ScoredRow> {
+    let mut boosted: Vec<ScoredRow> = results
+        .into_iter()
+        .map(|row| {
+            let attrs = attributes.get(&row.id).cloned().unwrap_or(serde_json::Value::Null);
+            let doc = Document {
+                id: row.id.clone(),
+                vector: Vec::new(),
+                attributes: attrs,
+                embedding: None,
+                embedding_model: None,
+            };
+            let mut score = row.score;
+            for boost in boosts {
+                if boost.filter.matches(&doc) {
+                    score = match boost.op {
+                        BoostOp::Multiply(factor) => score * factor,
+                        BoostOp::Add(delta) => score + delta,
+                    };
+                }
+            }
+            ScoredRow { id: row.id, score }
+        })
+        .collect();
+    boosted.sort_by(|a, b| b.score.total_cmp(&a.score));
+    boosted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    #[test]
+    fn weighted_sum_of_vector_score_and_attribute() {
+        let expr = ScoreExpr::Add(
+            Box::new(ScoreExpr::Mul(Box::new(ScoreExpr::VectorScore), Box::new(ScoreExpr::Const(0.8)))),
+            Box::new(ScoreExpr::Mul(Box::new(ScoreExpr::Attr("popularity".to_string())), Box::new(ScoreExpr::Const(0.2)))),
+        );
+        let score = expr.eval(1.0, &serde_json::json!({"popularity": 0.5}), SystemTime::now());
+        assert!((score - 0.9).abs() < 1e-6);
+    }
+
+    #[test]
+    fn recency_decay_halves_at_the_half_life() {
+        let expr = ScoreExpr::RecencyDecay {
+            attr: "published_at".to_string(),
+            half_life_secs: 604_800.0,
+        };
+        let now = std::time::UNIX_EPOCH + Duration::from_secs(604_800);
+        let score = expr.eval(0.0, &serde_json::json!({"published_at": 0}), now);
+        assert!((score - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rescore_combines_attributes_by_id_and_resorts() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: 0.5 },
+            ScoredRow { id: "b".to_string(), score: 0.4 },
+        ];
+        let mut attributes = HashMap::new();
+        attributes.insert("b".to_string(), serde_json::json!({"boost": 10.0}));
+
+        let expr = ScoreExpr::Add(Box::new(ScoreExpr::VectorScore), Box::new(ScoreExpr::Attr("boost".to_string())));
+        let rescored = rescore(results, &expr, &attributes, SystemTime::now());
+
+        assert_eq!(rescored[0].id, "b");
+        assert_eq!(rescored[1].id, "a");
+    }
+
+    #[test]
+    fn multiplicative_boost_reorders_matching_candidates_above_others() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: 1.0 },
+            ScoredRow { id: "b".to_string(), score: 0.9 },
+        ];
+        let mut attributes = HashMap::new();
+        attributes.insert("b".to_string(), serde_json::json!({"source": "docs"}));
+
+        let boosts = vec![Boost {
+            filter: FilterExpr::AttrEq { key: "source".to_string(), value: serde_json::json!("docs") },
+            op: BoostOp::Multiply(1.2),
+        }];
+        let boosted = apply_boosts(results, &boosts, &attributes);
+
+        assert_eq!(boosted[0].id, "b");
+        assert!((boosted[0].score - 1.08).abs() < 1e-6);
+        assert_eq!(boosted[1].score, 1.0);
+    }
+
+    #[test]
+    fn a_row_not_matching_any_boost_is_left_unchanged() {
+        let results = vec![ScoredRow { id: "a".to_string(), score: 0.5 }];
+        let boosts = vec![Boost {
+            filter: FilterExpr::AttrEq { key: "source".to_string(), value: serde_json::json!("docs") },
+            op: BoostOp::Add(10.0),
+        }];
+        let boosted = apply_boosts(results, &boosts, &HashMap::new());
+        assert_eq!(boosted[0].score, 0.5);
+    }
+
+    #[test]
+    fn rescore_never_panics_on_nan_or_infinite_scores() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: f32::NAN },
+            ScoredRow { id: "b".to_string(), score: f32::INFINITY },
+            ScoredRow { id: "c".to_string(), score: f32::NEG_INFINITY },
+            ScoredRow { id: "d".to_string(), score: 1.0 },
+        ];
+        let rescored = rescore(results, &ScoreExpr::VectorScore, &HashMap::new(), SystemTime::now());
+        // total_cmp ranks +NaN highest and -inf lowest, so descending sort
+        // puts NaN first and -inf last.
+        assert_eq!(rescored[0].id, "a");
+        assert_eq!(rescored[1].id, "b");
+        assert_eq!(rescored[2].id, "d");
+        assert_eq!(rescored[3].id, "c");
+    }
+
+    #[test]
+    fn order_by_attrs_breaks_a_score_tie_with_the_given_key() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: 1.0 },
+            ScoredRow { id: "b".to_string(), score: 1.0 },
+        ];
+        let mut attributes = HashMap::new();
+        attributes.insert("a".to_string(), serde_json::json!({"rank": 2}));
+        attributes.insert("b".to_string(), serde_json::json!({"rank": 1}));
+
+        let order_by = vec![crate::attr_order::AttrOrder {
+            key: "rank".to_string(),
+            direction: crate::attr_order::SortDirection::Asc,
+            nulls: crate::attr_order::NullsOrder::Last,
+        }];
+        let ordered = order_by_attrs(results, &order_by, &attributes);
+        assert_eq!(ordered[0].id, "b");
+        assert_eq!(ordered[1].id, "a");
+    }
+
+    #[test]
+    fn order_by_attrs_never_overrides_a_real_score_difference() {
+        let results = vec![
+            ScoredRow { id: "a".to_string(), score: 0.1 },
+            ScoredRow { id: "b".to_string(), score: 0.9 },
+        ];
+        let mut attributes = HashMap::new();
+        attributes.insert("a".to_string(), serde_json::json!({"rank": 1}));
+        attributes.insert("b".to_string(), serde_json::json!({"rank": 2}));
+
+        let order_by = vec![crate::attr_order::AttrOrder {
+            key: "rank".to_string(),
+            direction: crate::attr_order::SortDirection::Asc,
+            nulls: crate::attr_order::NullsOrder::Last,
+        }];
+        let ordered = order_by_attrs(results, &order_by, &attributes);
+        assert_eq!(ordered[0].id, "b");
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn rescore_output_is_always_sorted_by_total_cmp_and_preserves_every_id(
+            scores in proptest::collection::vec(proptest::prelude::any::<f32>(), 1..16),
+        ) {
+            let results: Vec<ScoredRow> = scores
+                .iter()
+                .enumerate()
+                .map(|(i, &score)| ScoredRow { id: i.to_string(), score })
+                .collect();
+            let mut expected_ids: Vec<String> = results.iter().map(|r| r.id.clone()).collect();
+            expected_ids.sort();
+
+            let rescored = rescore(results, &ScoreExpr::VectorScore, &HashMap::new(), SystemTime::now());
+
+            for pair in rescored.windows(2) {
+                proptest::prop_assert!(pair[0].score.total_cmp(&pair[1].score) != std::cmp::Ordering::Less);
+            }
+            let mut got_ids: Vec<String> = rescored.iter().map(|r| r.id.clone()).collect();
+            got_ids.sort();
+            proptest::prop_assert_eq!(got_ids, expected_ids);
+        }
+
+        #[test]
+        fn apply_boosts_output_is_always_sorted_by_total_cmp(
+            scores in proptest::collection::vec(proptest::prelude::any::<f32>(), 1..16),
+            factor in proptest::prelude::any::<f32>(),
+        ) {
+            let results: Vec<ScoredRow> = scores
+                .iter()
+                .enumerate()
+                .map(|(i, &score)| ScoredRow { id: i.to_string(), score })
+                .collect();
+            let boosts = vec![Boost {
+                filter: FilterExpr::AttrEq { key: "never".to_string(), value: serde_json::json!("matches") },
+                op: BoostOp::Multiply(factor),
+            }];
+            let boosted = apply_boosts(results, &boosts, &HashMap::new());
+            for pair in boosted.windows(2) {
+                proptest::prop_assert!(pair[0].score.total_cmp(&pair[1].score) != std::cmp::Ordering::Less);
+            }
+        }
+    }
+}
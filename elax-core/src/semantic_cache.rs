@@ -0,0 +1,192 @@
+//! Caches final query *results* keyed by vector similarity instead of
+//! exact match — a repeated (or near-repeated) question against an
+//! unchanged namespace can be served straight from here instead of
+//! re-scoring. Complements [`crate::query_cache::QueryEmbeddingCache`],
+//! which only caches the embedding step for `ANN_TEXT` queries; this
+//! caches the search result itself.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use elax_index::ScoredRow;
+
+struct Entry {
+    vector: Vec<f32>,
+    /// The namespace's write sequence ([`crate::tiered::TieredNamespace::upsert`]'s
+    /// return value) when this entry was cached — a write landing after
+    /// that point means a new query at the same vector could legitimately
+    /// see different results, so it must miss rather than reuse this entry.
+    seq: u64,
+    cached_at: SystemTime,
+    results: Vec<ScoredRow>,
+}
+
+fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt()
+}
+
+/// Cumulative hit/miss counts for [`SemanticQueryCache::get`], so operators
+/// can judge whether `epsilon`/`ttl` are tuned well for their query mix.
+#[derive(Debug, Default)]
+pub struct SemanticCacheMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl SemanticCacheMetrics {
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// `hits / (hits + misses)`, or `0.0` before anything has been looked up.
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.hits() as f64;
+        let total = hits + self.misses() as f64;
+        if total == 0.0 {
+            0.0
+        } else {
+            hits / total
+        }
+    }
+}
+
+/// A small bounded cache of recent query vectors and the scored results
+/// they produced, serving a new query from an existing entry when it's
+/// within `epsilon` (Euclidean distance) of a cached vector computed at
+/// the same namespace write sequence, and not yet past `ttl`.
+/// Distance-based, not an ANN index — `capacity` is expected to stay small
+/// (recent queries for a chatbot-style workload), so a linear scan per
+/// lookup is cheap.
+pub struct SemanticQueryCache {
+    capacity: usize,
+    epsilon: f32,
+    ttl: Duration,
+    entries: Mutex<VecDeque<Entry>>,
+    pub metrics: SemanticCacheMetrics,
+}
+
+impl SemanticQueryCache {
+    pub fn new(capacity: usize, epsilon: f32, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            epsilon,
+            ttl,
+            entries: Mutex::new(VecDeque::new()),
+            metrics: SemanticCacheMetrics::default(),
+        }
+    }
+
+    /// Serve `query` from a cached entry within `epsilon` at `seq` (the
+    /// namespace's current write sequence), if one hasn't expired past
+    /// `ttl` as of `now`. Expired entries are swept on every call, so the
+    /// cache never grows past `capacity` live entries even without a
+    /// background reaper.
+    pub fn get(&self, query: &[f32], seq: u64, now: SystemTime) -> Option<Vec<ScoredRow>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|entry| now.duration_since(entry.cached_at).map(|age| age <= self.ttl).unwrap_or(true));
+
+        let hit = entries
+            .iter()
+            .find(|entry| entry.seq == seq && euclidean_distance(&entry.vector, query) <= self.epsilon)
+            .map(|entry| entry.results.clone());
+
+        if hit.is_some() {
+            self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        hit
+    }
+
+    /// Record `results` for `query` at the namespace's current write
+    /// `seq`, evicting the oldest entry if this pushes the cache past
+    /// `capacity`.
+    pub fn put(&self, query: Vec<f32>, seq: u64, results: Vec<ScoredRow>, now: SystemTime) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.push_back(Entry { vector: query, seq, cached_at: now, results });
+        while entries.len() > self.capacity {
+            entries.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: &str) -> ScoredRow {
+        ScoredRow { id: id.to_string(), score: 1.0 }
+    }
+
+    #[test]
+    fn an_identical_query_at_the_same_seq_is_a_hit() {
+        let cache = SemanticQueryCache::new(8, 0.01, Duration::from_secs(60));
+        let now = SystemTime::now();
+        cache.put(vec![1.0, 0.0], 5, vec![row("a")], now);
+
+        let hit = cache.get(&[1.0, 0.0], 5, now).unwrap();
+        assert_eq!(hit, vec![row("a")]);
+        assert_eq!(cache.metrics.hits(), 1);
+    }
+
+    #[test]
+    fn a_query_within_epsilon_is_a_hit() {
+        let cache = SemanticQueryCache::new(8, 0.1, Duration::from_secs(60));
+        let now = SystemTime::now();
+        cache.put(vec![1.0, 0.0], 5, vec![row("a")], now);
+
+        let hit = cache.get(&[1.05, 0.0], 5, now).unwrap();
+        assert_eq!(hit, vec![row("a")]);
+    }
+
+    #[test]
+    fn a_query_past_epsilon_misses() {
+        let cache = SemanticQueryCache::new(8, 0.01, Duration::from_secs(60));
+        let now = SystemTime::now();
+        cache.put(vec![1.0, 0.0], 5, vec![row("a")], now);
+
+        assert!(cache.get(&[5.0, 0.0], 5, now).is_none());
+        assert_eq!(cache.metrics.misses(), 1);
+    }
+
+    #[test]
+    fn a_write_landing_after_the_cached_seq_invalidates_the_entry() {
+        let cache = SemanticQueryCache::new(8, 0.01, Duration::from_secs(60));
+        let now = SystemTime::now();
+        cache.put(vec![1.0, 0.0], 5, vec![row("a")], now);
+
+        assert!(cache.get(&[1.0, 0.0], 6, now).is_none());
+    }
+
+    #[test]
+    fn an_entry_past_its_ttl_misses_and_is_swept() {
+        let cache = SemanticQueryCache::new(8, 0.01, Duration::from_secs(1));
+        let t0 = SystemTime::now();
+        cache.put(vec![1.0, 0.0], 5, vec![row("a")], t0);
+
+        let later = t0 + Duration::from_secs(2);
+        assert!(cache.get(&[1.0, 0.0], 5, later).is_none());
+
+        // Swept on the expiry check, not just masked — a fresh put at the
+        // same vector/seq afterward doesn't collide with a stale entry.
+        cache.put(vec![1.0, 0.0], 5, vec![row("b")], later);
+        assert_eq!(cache.get(&[1.0, 0.0], 5, later).unwrap(), vec![row("b")]);
+    }
+
+    #[test]
+    fn evicts_the_oldest_entry_past_capacity() {
+        let cache = SemanticQueryCache::new(1, 0.01, Duration::from_secs(60));
+        let now = SystemTime::now();
+        cache.put(vec![1.0, 0.0], 1, vec![row("a")], now);
+        cache.put(vec![0.0, 1.0], 1, vec![row("b")], now);
+
+        assert!(cache.get(&[1.0, 0.0], 1, now).is_none());
+        assert_eq!(cache.get(&[0.0, 1.0], 1, now).unwrap(), vec![row("b")]);
+    }
+}
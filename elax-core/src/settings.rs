@@ -0,0 +1,186 @@
+//! Runtime defaults (ANN probe params, cache capacity, indexer thresholds,
+//! rate limits) that an operator can hot-reload from an `AppConfig` file or
+//! an admin endpoint, without restarting the server. [`SettingsHandle`]
+//! holds the live values behind a mutex so readers always see a consistent
+//! snapshot and a reload is a single atomic swap.
+
+use std::sync::{Arc, Mutex};
+
+use elax_cache::CacheCapacity;
+
+/// Default ANN probe parameters a search falls back to when the caller
+/// doesn't specify its own `nlist`/`nprobe`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnnParams {
+    pub nlist: usize,
+    pub nprobe: usize,
+    /// Ramp `nprobe` up from `nprobe` and stop early once the ranking
+    /// stabilizes, instead of always probing exactly `nprobe` lists — see
+    /// [`elax_index::ivf::IvfIndex::probe_adaptive`].
+    pub adaptive: bool,
+    /// Recall target for `adaptive` probing. `probe_adaptive` has no
+    /// recall-target parameter (it stops once the `top_k`-th score moves by
+    /// no more than `epsilon`), and nothing in this crate reads this field,
+    /// `adaptive` or `max_nprobe`.
+    pub target_recall: Option<f32>,
+    /// Upper bound on how far adaptive probing may ramp `nprobe`, so a
+    /// query that never stabilizes still has a predictable worst case.
+    /// Ignored when `adaptive` is false.
+    pub max_nprobe: Option<usize>,
+}
+
+impl Default for AnnParams {
+    fn default() -> Self {
+        Self {
+            nlist: 128,
+            nprobe: 8,
+            adaptive: false,
+            target_recall: None,
+            max_nprobe: None,
+        }
+    }
+}
+
+/// How much WAL backlog accumulates before an indexer pass is worth
+/// running again. Read by an operator-driven indexing loop, the same way
+/// `replication::catch_up` is read by a polling loop rather than a
+/// background thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexerThresholds {
+    pub max_wal_bytes: u64,
+    pub max_wal_rows: usize,
+}
+
+impl Default for IndexerThresholds {
+    fn default() -> Self {
+        Self {
+            max_wal_bytes: 64 * 1024 * 1024,
+            max_wal_rows: 100_000,
+        }
+    }
+}
+
+/// How far a namespace's running vector statistics may drift from the
+/// baseline recorded at its last IVF/ERQ retraining before
+/// [`crate::metrics::DriftTracker::should_retrain`] flags it as due —
+/// fractional change, not an absolute value, since embedding magnitude
+/// varies a lot model to model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriftThresholds {
+    /// Fractional change in mean inserted-vector norm, e.g. `0.2` = 20%.
+    pub max_norm_drift: f32,
+    /// Fractional change in mean vector-to-nearest-centroid residual.
+    pub max_residual_drift: f32,
+}
+
+impl Default for DriftThresholds {
+    fn default() -> Self {
+        Self { max_norm_drift: 0.2, max_residual_drift: 0.3 }
+    }
+}
+
+/// Requests allowed per tenant per second before the API layer starts
+/// rejecting them. `None` disables rate limiting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RateLimit {
+    pub requests_per_second: Option<u32>,
+}
+
+/// The full set of hot-reloadable defaults.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuntimeSettings {
+    pub ann_params: AnnParams,
+    pub cache_capacity: CacheCapacity,
+    pub indexer_thresholds: IndexerThresholds,
+    pub rate_limit: RateLimit,
+    pub drift_thresholds: DriftThresholds,
+}
+
+/// Shared, atomically-swappable handle to the live [`RuntimeSettings`].
+/// Clone freely — clones share the same underlying values.
+#[derive(Clone)]
+pub struct SettingsHandle(Arc<Mutex<RuntimeSettings>>);
+
+impl SettingsHandle {
+    pub fn new(initial: RuntimeSettings) -> Self {
+        Self(Arc::new(Mutex::new(initial)))
+    }
+
+    pub fn current(&self) -> RuntimeSettings {
+        *self.0.lock().unwrap()
+    }
+
+    /// Atomically replace the live settings wholesale.
+    pub fn reload(&self, new: RuntimeSettings) {
+        *self.0.lock().unwrap() = new;
+    }
+
+    /// Apply a partial JSON patch from an `AppConfig` file reload or an
+    /// admin endpoint — only keys present are updated, everything else
+    /// keeps its current value, mirroring
+    /// `NamespaceRegistry::update_by_query`'s patch semantics. Unknown keys
+    /// are ignored.
+    pub fn reload_from_json(&self, patch: &serde_json::Map<String, serde_json::Value>) {
+        let mut settings = self.0.lock().unwrap();
+        if let Some(v) = patch.get("ann_nlist").and_then(serde_json::Value::as_u64) {
+            settings.ann_params.nlist = v as usize;
+        }
+        if let Some(v) = patch.get("ann_nprobe").and_then(serde_json::Value::as_u64) {
+            settings.ann_params.nprobe = v as usize;
+        }
+        if let Some(v) = patch.get("cache_max_bytes").and_then(serde_json::Value::as_u64) {
+            settings.cache_capacity.max_bytes = Some(v);
+        }
+        if let Some(v) = patch.get("cache_max_age_secs").and_then(serde_json::Value::as_u64) {
+            settings.cache_capacity.max_age_secs = Some(v);
+        }
+        if let Some(v) = patch.get("indexer_max_wal_bytes").and_then(serde_json::Value::as_u64) {
+            settings.indexer_thresholds.max_wal_bytes = v;
+        }
+        if let Some(v) = patch.get("indexer_max_wal_rows").and_then(serde_json::Value::as_u64) {
+            settings.indexer_thresholds.max_wal_rows = v as usize;
+        }
+        if let Some(v) = patch.get("rate_limit_rps").and_then(serde_json::Value::as_u64) {
+            settings.rate_limit.requests_per_second = Some(v as u32);
+        }
+        if let Some(v) = patch.get("drift_max_norm_drift").and_then(serde_json::Value::as_f64) {
+            settings.drift_thresholds.max_norm_drift = v as f32;
+        }
+        if let Some(v) = patch.get("drift_max_residual_drift").and_then(serde_json::Value::as_f64) {
+            settings.drift_thresholds.max_residual_drift = v as f32;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_swaps_all_settings_atomically() {
+        let handle = SettingsHandle::new(RuntimeSettings::default());
+        assert_eq!(handle.current().ann_params.nprobe, 8);
+
+        handle.reload(RuntimeSettings {
+            ann_params: AnnParams { nlist: 256, nprobe: 16, ..Default::default() },
+            ..RuntimeSettings::default()
+        });
+        assert_eq!(handle.current().ann_params.nlist, 256);
+        assert_eq!(handle.current().ann_params.nprobe, 16);
+    }
+
+    #[test]
+    fn reload_from_json_only_touches_present_keys() {
+        let handle = SettingsHandle::new(RuntimeSettings::default());
+        let mut patch = serde_json::Map::new();
+        patch.insert("ann_nprobe".to_string(), serde_json::json!(32));
+        patch.insert("cache_max_bytes".to_string(), serde_json::json!(1_000_000));
+
+        handle.reload_from_json(&patch);
+
+        let settings = handle.current();
+        assert_eq!(settings.ann_params.nprobe, 32);
+        assert_eq!(settings.ann_params.nlist, 128); // untouched default
+        assert_eq!(settings.cache_capacity.max_bytes, Some(1_000_000));
+    }
+}
@@ -0,0 +1,129 @@
+//! Splits a namespace that has outgrown one node's memory into `N` shards
+//! by hashing each document's id, so a single namespace can keep growing
+//! past the row/byte thresholds [`crate::registry::Quota`] would otherwise
+//! just reject writes at. [`crate::router::RouterState::shard_counts`]
+//! records the resulting layout; [`crate::tiered::search_sharded`] is the
+//! query-side counterpart that searches every shard and merges the
+//! results.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::registry::NamespaceStats;
+
+/// When a namespace should be split into more shards, and how many.
+/// `max_rows`/`max_bytes` follow [`crate::registry::Quota`]'s convention:
+/// `None` means that dimension never triggers a split on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardingPolicy {
+    pub max_rows_per_shard: Option<usize>,
+    pub max_bytes_per_shard: Option<u64>,
+    /// Shard count a namespace crossing the threshold is split into. Doesn't
+    /// grow incrementally — crossing the threshold again after a split
+    /// means raising this and resplitting, not adding one shard at a time.
+    pub shard_count: usize,
+}
+
+impl Default for ShardingPolicy {
+    fn default() -> Self {
+        Self { max_rows_per_shard: None, max_bytes_per_shard: None, shard_count: 4 }
+    }
+}
+
+/// Which shard (in `0..shard_count`) a document with this id is assigned
+/// to. Pure function of `id` and `shard_count`, so a writer and a later
+/// reader agree without consulting any shared state — the same
+/// hash-over-id approach [`crate::cluster_router::ClusterRouter`] uses for
+/// namespace-to-node placement, just one level down.
+pub fn shard_for_id(id: &str, shard_count: usize) -> usize {
+    if shard_count <= 1 {
+        return 0;
+    }
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    (hasher.finish() as usize) % shard_count
+}
+
+/// Whether an unsharded namespace with these stats has outgrown
+/// `policy`'s threshold and should be split into `policy.shard_count`
+/// shards.
+pub fn should_shard(stats: &NamespaceStats, policy: &ShardingPolicy) -> bool {
+    if let Some(max_rows) = policy.max_rows_per_shard {
+        if stats.row_count > max_rows {
+            return true;
+        }
+    }
+    if let Some(max_bytes) = policy.max_bytes_per_shard {
+        if stats.bytes_used > max_bytes {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attr_schema::AttrSchema;
+    use crate::registry::Quota;
+    use crate::settings::AnnParams;
+
+    fn stats(row_count: usize, bytes_used: u64) -> NamespaceStats {
+        NamespaceStats {
+            row_count,
+            bytes_used,
+            quota: Quota::default(),
+            attr_schema: AttrSchema::default(),
+            effective_ann_params: AnnParams::default(),
+            pending_reembed: 0,
+        }
+    }
+
+    #[test]
+    fn shard_assignment_is_stable_and_within_range() {
+        for id in ["a", "b", "some-doc-id", "another one"] {
+            let shard = shard_for_id(id, 4);
+            assert!(shard < 4);
+            assert_eq!(shard, shard_for_id(id, 4));
+        }
+    }
+
+    #[test]
+    fn a_single_shard_always_maps_to_zero() {
+        assert_eq!(shard_for_id("anything", 1), 0);
+        assert_eq!(shard_for_id("anything", 0), 0);
+    }
+
+    #[test]
+    fn spreads_many_ids_across_every_shard() {
+        let mut seen = [false; 4];
+        for i in 0..1000 {
+            seen[shard_for_id(&format!("doc-{i}"), 4)] = true;
+        }
+        assert!(seen.iter().all(|&hit| hit));
+    }
+
+    #[test]
+    fn does_not_shard_below_the_configured_threshold() {
+        let policy = ShardingPolicy { max_rows_per_shard: Some(1_000_000), ..Default::default() };
+        assert!(!should_shard(&stats(100, 1_000), &policy));
+    }
+
+    #[test]
+    fn shards_once_row_count_crosses_the_threshold() {
+        let policy = ShardingPolicy { max_rows_per_shard: Some(1_000), ..Default::default() };
+        assert!(should_shard(&stats(1_001, 0), &policy));
+    }
+
+    #[test]
+    fn shards_once_byte_usage_crosses_the_threshold() {
+        let policy = ShardingPolicy { max_bytes_per_shard: Some(1_000), ..Default::default() };
+        assert!(should_shard(&stats(0, 1_001), &policy));
+    }
+
+    #[test]
+    fn an_unconfigured_policy_never_triggers_a_split() {
+        let policy = ShardingPolicy::default();
+        assert!(!should_shard(&stats(usize::MAX, u64::MAX), &policy));
+    }
+}
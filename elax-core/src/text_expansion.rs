@@ -0,0 +1,286 @@
+//! Stop-word filtering and synonym expansion for free-text query terms,
+//! configurable per namespace with per-field overrides (the same `Option`/
+//! default-plus-override shape as [`crate::registry::NamespaceConfig`]'s
+//! other per-namespace knobs). There's no free-text match clause in
+//! [`crate::filter::FilterExpr`] yet — only attribute equality/comparison —
+//! so this module doesn't plug into a query executor itself. It's the
+//! preprocessing primitive a term-matching clause would call into once one
+//! exists: turning a tokenized query into OR groups of terms (each group's
+//! extras at a lower boost than the original term), with configured stop
+//! words dropped entirely.
+//!
+//! Not every field should go through that pipeline at all: an id, enum
+//! code, or email is a single opaque value, not prose, and tokenizing,
+//! stop-wording, or synonym-expanding it would only produce spurious
+//! partial matches. [`FieldKind::Keyword`] marks a field as exact-match —
+//! the same declared-up-front shape as [`crate::attr_schema::AttrType`].
+
+use std::collections::{HashMap, HashSet};
+
+/// How a field's text is matched. Mirrors the declared-vs-inferred typing
+/// [`crate::attr_schema::AttrSchema`] uses for attribute values, but for
+/// text matching rather than JSON value type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FieldKind {
+    /// Tokenized prose: stop words are dropped and surviving terms are
+    /// synonym-expanded, per [`TextSearchConfig::expand`].
+    #[default]
+    Text,
+    /// An untokenized, exact-match value. Bypasses stop-word filtering and
+    /// synonym expansion entirely — every input term becomes its own
+    /// single-term group, matched verbatim.
+    Keyword,
+}
+
+/// One term in an expanded OR group, with the weight it should contribute
+/// relative to the original query term (boost `1.0`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoostedTerm {
+    pub term: String,
+    pub boost: f32,
+}
+
+/// BM25 `k1`/`b` tuning for one field. `k1` controls how quickly a term's
+/// contribution saturates as its frequency in the document grows; `b`
+/// controls how much document-length normalization discounts long
+/// documents. There's no term-frequency index in this crate for a BM25
+/// executor to read yet — this is config metadata ready for whatever
+/// lexical scorer (e.g. Tantivy) consumes it, the same "schema before the
+/// engine" shape as [`FieldKind`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bm25Params {
+    pub k1: f32,
+    pub b: f32,
+}
+
+impl Default for Bm25Params {
+    /// Robertson/Zaragoza's commonly-used defaults (also Lucene's and
+    /// Tantivy's), for a field with no explicit tuning.
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+/// Stop words and synonym expansions for a single namespace or field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LanguagePackConfig {
+    /// Terms dropped from a query entirely — never expanded, never matched.
+    pub stop_words: HashSet<String>,
+    /// `term -> its synonyms`, each synonym boosted lower than the term it
+    /// stands in for so an exact match still ranks above an expansion.
+    pub synonyms: HashMap<String, Vec<BoostedTerm>>,
+}
+
+impl LanguagePackConfig {
+    pub fn is_stop_word(&self, term: &str) -> bool {
+        self.stop_words.contains(term)
+    }
+
+    fn synonyms_for(&self, term: &str) -> &[BoostedTerm] {
+        self.synonyms.get(term).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// A namespace's text expansion config: a default pack plus per-field
+/// overrides, the same shape [`crate::attr_schema::AttrSchema`] uses for
+/// per-field typing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextSearchConfig {
+    pub default: LanguagePackConfig,
+    pub fields: HashMap<String, LanguagePackConfig>,
+    /// Fields declared [`FieldKind::Keyword`]. A field with no entry here
+    /// is [`FieldKind::Text`], the pre-existing (only) behavior.
+    pub field_kinds: HashMap<String, FieldKind>,
+    /// Per-field BM25 `k1`/`b` overrides. A field with no entry here
+    /// scores with [`Bm25Params::default`] — e.g. a short `title` field
+    /// wants a lower `b` than a long `body` field, which this is for.
+    pub bm25_params: HashMap<String, Bm25Params>,
+    /// BM25F-style per-field weights, for combining more than one field's
+    /// BM25 score into a single relevance score with
+    /// [`combine_field_scores`]. A field with no entry here weighs `1.0`.
+    pub field_weights: HashMap<String, f32>,
+}
+
+impl TextSearchConfig {
+    fn pack_for(&self, field: &str) -> &LanguagePackConfig {
+        self.fields.get(field).unwrap_or(&self.default)
+    }
+
+    pub fn kind_of(&self, field: &str) -> FieldKind {
+        self.field_kinds.get(field).copied().unwrap_or_default()
+    }
+
+    pub fn bm25_params(&self, field: &str) -> Bm25Params {
+        self.bm25_params.get(field).copied().unwrap_or_default()
+    }
+
+    pub fn field_weight(&self, field: &str) -> f32 {
+        self.field_weights.get(field).copied().unwrap_or(1.0)
+    }
+
+    /// Expand already-tokenized, already-lowercased `tokens` for `field`
+    /// into one OR group per surviving term.
+    ///
+    /// For a [`FieldKind::Text`] field, that's the term itself at boost
+    /// `1.0` followed by its configured synonyms at their own boosts, with
+    /// stop words dropped from the output rather than becoming a
+    /// (matchless) empty group. For a [`FieldKind::Keyword`] field, every
+    /// term becomes its own single-term group matched verbatim — no
+    /// stop-word filtering, no synonym expansion.
+    pub fn expand(&self, field: &str, tokens: &[String]) -> Vec<Vec<BoostedTerm>> {
+        if self.kind_of(field) == FieldKind::Keyword {
+            return tokens
+                .iter()
+                .map(|term| vec![BoostedTerm { term: term.clone(), boost: 1.0 }])
+                .collect();
+        }
+
+        let pack = self.pack_for(field);
+        tokens
+            .iter()
+            .filter(|term| !pack.is_stop_word(term))
+            .map(|term| {
+                let mut group = vec![BoostedTerm { term: term.clone(), boost: 1.0 }];
+                group.extend(pack.synonyms_for(term).iter().cloned());
+                group
+            })
+            .collect()
+    }
+}
+
+/// Combine one BM25 score per field into a single BM25F-style relevance
+/// score: the weighted sum `Σ config.field_weight(field) * score`.
+/// `field_scores` comes from whatever per-field BM25 executor actually
+/// scored each field against the query.
+pub fn combine_field_scores(config: &TextSearchConfig, field_scores: &HashMap<String, f32>) -> f32 {
+    field_scores.iter().map(|(field, score)| config.field_weight(field) * score).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn stop_words_are_dropped_from_the_expansion() {
+        let config = TextSearchConfig {
+            default: LanguagePackConfig {
+                stop_words: HashSet::from(["the".to_string()]),
+                synonyms: HashMap::new(),
+            },
+            fields: HashMap::new(),
+            field_kinds: HashMap::new(),
+            bm25_params: HashMap::new(),
+            field_weights: HashMap::new(),
+        };
+
+        let expanded = config.expand("body", &tokens(&["the", "cluster"]));
+        assert_eq!(expanded.len(), 1);
+        assert_eq!(expanded[0][0].term, "cluster");
+    }
+
+    #[test]
+    fn a_term_expands_into_an_or_group_with_its_synonyms_at_a_lower_boost() {
+        let config = TextSearchConfig {
+            default: LanguagePackConfig {
+                stop_words: HashSet::new(),
+                synonyms: HashMap::from([(
+                    "k8s".to_string(),
+                    vec![BoostedTerm { term: "kubernetes".to_string(), boost: 0.5 }],
+                )]),
+            },
+            fields: HashMap::new(),
+            field_kinds: HashMap::new(),
+            bm25_params: HashMap::new(),
+            field_weights: HashMap::new(),
+        };
+
+        let expanded = config.expand("body", &tokens(&["k8s"]));
+        assert_eq!(
+            expanded[0],
+            vec![
+                BoostedTerm { term: "k8s".to_string(), boost: 1.0 },
+                BoostedTerm { term: "kubernetes".to_string(), boost: 0.5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_field_override_replaces_the_namespace_default_rather_than_merging() {
+        let config = TextSearchConfig {
+            default: LanguagePackConfig {
+                stop_words: HashSet::from(["the".to_string()]),
+                synonyms: HashMap::new(),
+            },
+            fields: HashMap::from([(
+                "title".to_string(),
+                LanguagePackConfig { stop_words: HashSet::new(), synonyms: HashMap::new() },
+            )]),
+            field_kinds: HashMap::new(),
+            bm25_params: HashMap::new(),
+            field_weights: HashMap::new(),
+        };
+
+        let expanded = config.expand("title", &tokens(&["the", "cluster"]));
+        assert_eq!(expanded.len(), 2);
+    }
+
+    #[test]
+    fn a_keyword_field_is_matched_verbatim_even_if_it_would_otherwise_be_a_stop_word() {
+        let config = TextSearchConfig {
+            default: LanguagePackConfig {
+                stop_words: HashSet::from(["a".to_string()]),
+                synonyms: HashMap::from([("a".to_string(), vec![BoostedTerm { term: "alpha".to_string(), boost: 0.5 }])]),
+            },
+            fields: HashMap::new(),
+            field_kinds: HashMap::from([("status_code".to_string(), FieldKind::Keyword)]),
+            bm25_params: HashMap::new(),
+            field_weights: HashMap::new(),
+        };
+
+        let expanded = config.expand("status_code", &tokens(&["a"]));
+        assert_eq!(expanded, vec![vec![BoostedTerm { term: "a".to_string(), boost: 1.0 }]]);
+    }
+
+    #[test]
+    fn a_field_with_no_declared_kind_defaults_to_text() {
+        let config = TextSearchConfig::default();
+        assert_eq!(config.kind_of("body"), FieldKind::Text);
+    }
+
+    #[test]
+    fn a_field_with_no_bm25_override_gets_the_standard_defaults() {
+        let config = TextSearchConfig::default();
+        assert_eq!(config.bm25_params("body"), Bm25Params { k1: 1.2, b: 0.75 });
+    }
+
+    #[test]
+    fn an_explicit_bm25_override_replaces_the_default() {
+        let config = TextSearchConfig {
+            bm25_params: HashMap::from([("title".to_string(), Bm25Params { k1: 1.5, b: 0.3 })]),
+            ..Default::default()
+        };
+        assert_eq!(config.bm25_params("title"), Bm25Params { k1: 1.5, b: 0.3 });
+        assert_eq!(config.bm25_params("body"), Bm25Params::default());
+    }
+
+    #[test]
+    fn combine_field_scores_is_a_weighted_sum_across_fields() {
+        let config = TextSearchConfig {
+            field_weights: HashMap::from([("title".to_string(), 2.0), ("body".to_string(), 1.0)]),
+            ..Default::default()
+        };
+        let field_scores = HashMap::from([("title".to_string(), 1.5), ("body".to_string(), 0.5)]);
+        assert_eq!(combine_field_scores(&config, &field_scores), 3.5);
+    }
+
+    #[test]
+    fn a_field_with_no_declared_weight_counts_once() {
+        let config = TextSearchConfig::default();
+        let field_scores = HashMap::from([("body".to_string(), 2.0)]);
+        assert_eq!(combine_field_scores(&config, &field_scores), 2.0);
+    }
+}
@@ -0,0 +1,1055 @@
+//! Tiered namespace execution: only the WAL tail lives in memory, and
+//! historical rows are searched directly from on-disk parts, LSM-style.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use elax_index::{read_part_assets, search_namespace, search_namespace_with_options, Row, ScoredRow, SearchMode, SearchOptions};
+
+use crate::document::Document;
+use crate::embedder::Embedder;
+use crate::error::{CoreError, Result};
+use crate::filter::FilterExpr;
+use crate::metrics::NamespaceMetrics;
+use crate::query_cache::QueryEmbeddingCache;
+use crate::registry::normalize_l2;
+
+fn to_row(doc: &Document) -> Row {
+    Row::new(doc.id.clone(), doc.vector.clone())
+}
+
+/// Columnar result of [`TieredNamespace::fetch_vectors`]: parallel `ids`
+/// and `vectors` (`vectors[i]` is `ids[i]`'s vector), in the same relative
+/// order as the ids that were requested, skipping any that weren't found
+/// rather than padding a hole — the shape an offline reranking/clustering
+/// job can feed straight into a matrix without going through per-row
+/// `Document`s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorBatch {
+    pub ids: Vec<String>,
+    pub vectors: Vec<Vec<f32>>,
+}
+
+/// What to rank a search by: a precomputed vector, or `ANN_TEXT` — a search
+/// phrase that [`TieredNamespace::search_by`] embeds on the fly via the
+/// namespace's configured [`Embedder`].
+#[derive(Debug, Clone)]
+pub enum RankBy {
+    Vector(Vec<f32>),
+    AnnText(String),
+}
+
+/// The similarity metric a query clause scores against. Dot product is the
+/// index's native scorer; Cosine L2-normalizes the query vector first so
+/// vector magnitude doesn't skew the ranking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum Metric {
+    #[default]
+    DotProduct,
+    Cosine,
+}
+
+/// One leg of a multi-clause hybrid query: what to rank by, how many
+/// candidates this clause contributes before fusion, how much its score
+/// counts toward the fused total, and an optional filter narrowing it to
+/// matching rows.
+#[derive(Debug, Clone)]
+pub struct QueryClause {
+    pub rank_by: RankBy,
+    pub top_k: usize,
+    pub weight: f32,
+    pub filter: Option<FilterExpr>,
+    pub metric: Metric,
+    /// Force a specific execution order for `filter`, overriding whatever
+    /// [`NamespaceRegistry::effective_plan_hint`](crate::registry::NamespaceRegistry::effective_plan_hint)
+    /// would otherwise pick — an escape hatch for working around a planner
+    /// misestimate mid-incident without a code change. `None` lets the
+    /// planner's own default (`PlanHint::FilterFirst`) stand.
+    pub plan_hint: Option<PlanHint>,
+}
+
+/// How `search_multi` orders scoring against a clause's `filter` on
+/// memtable rows. On-disk parts carry no attributes, so none of these
+/// change part-sourced candidates — see [`TieredNamespace::live_memtable_rows`]'s
+/// doc comment. Recorded per clause in [`ClauseExplain`] so operators can
+/// see, after the fact, which plan actually ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum PlanHint {
+    /// Narrow to matching rows before scoring any of them — cheap when
+    /// `filter` is selective, and the only option that guarantees a full
+    /// `top_k` matching rows if that many exist. The planner's own choice
+    /// when a clause doesn't force one.
+    #[default]
+    FilterFirst,
+    /// Score every live row first, truncate to `top_k`, then drop any
+    /// survivor that fails `filter` — cheaper when `filter` matches almost
+    /// everything, at the cost of a result set that can come up short of
+    /// `top_k` once filtered.
+    VectorFirst,
+    /// Score every live row and keep the top `top_k` with `filter` ignored
+    /// entirely — useful to rule out a slow or misbehaving filter
+    /// expression during an incident, not just its ordering.
+    BruteForce,
+}
+
+/// One clause's resolved plan, as recorded by `search_multi` when an
+/// `explain` sink is given: which [`PlanHint`] actually ran, and whether it
+/// came from an override (`plan_hint` or a namespace's `default_plan_hint`)
+/// rather than the planner's own `FilterFirst` default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClauseExplain {
+    pub plan_used: PlanHint,
+    pub overrode_planner: bool,
+}
+
+/// How many superseded versions of a single id `upsert` retains in
+/// `history` before the oldest is dropped — bounds its memory the same way
+/// `DEFAULT_IDEMPOTENCY_WINDOW` bounds `IdempotencyWindow` in
+/// `registry.rs`. This is also the retention window `search_as_of` can look
+/// back through: once a version falls out of `history` it cannot be
+/// reconstructed.
+const DEFAULT_VERSION_RETENTION: usize = 32;
+
+/// A namespace whose memtable holds only recently-written rows; anything
+/// already materialized into a part is searched from `parts_dir` instead of
+/// being kept resident.
+pub struct TieredNamespace {
+    pub name: String,
+    pub memtable: HashMap<String, Document>,
+    pub parts_dir: PathBuf,
+    pub part_names: Vec<String>,
+    /// Next sequence number `upsert` will assign. Monotonically increasing
+    /// within this namespace's lifetime; not persisted, so it resets (and
+    /// `history` empties) across a process restart the same way the
+    /// memtable does.
+    next_seq: u64,
+    /// Per-id superseded versions, most recent last, bounded to
+    /// `DEFAULT_VERSION_RETENTION` entries — the state `search_as_of` scans
+    /// to reconstruct a historical snapshot.
+    history: HashMap<String, VecDeque<(u64, Document)>>,
+}
+
+impl TieredNamespace {
+    pub fn new(name: impl Into<String>, parts_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            memtable: HashMap::new(),
+            parts_dir: parts_dir.into(),
+            part_names: Vec::new(),
+            next_seq: 0,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Write `doc`, returning the sequence number this version was assigned
+    /// — pass it to `search_as_of` later to read back exactly this state.
+    pub fn upsert(&mut self, doc: Document) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        let versions = self.history.entry(doc.id.clone()).or_default();
+        versions.push_back((seq, doc.clone()));
+        while versions.len() > DEFAULT_VERSION_RETENTION {
+            versions.pop_front();
+        }
+
+        self.memtable.insert(doc.id.clone(), doc);
+        seq
+    }
+
+    /// Copy-on-write clone named `name`: the new namespace starts with an
+    /// empty memtable (a fresh write path) but shares `part_names`, so both
+    /// namespaces read the same on-disk parts until either one compacts or
+    /// adopts new parts of its own. Useful for blue/green reindexing — build
+    /// up the clone, then have callers switch to reading from it (e.g. via
+    /// an alias) once it's caught up.
+    pub fn clone_into(&self, name: impl Into<String>) -> TieredNamespace {
+        TieredNamespace {
+            name: name.into(),
+            memtable: HashMap::new(),
+            parts_dir: self.parts_dir.clone(),
+            part_names: self.part_names.clone(),
+            next_seq: 0,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Drop `part_name`'s rows from the memtable once the indexer has
+    /// materialized them into a part, and start searching them from disk.
+    pub fn adopt_part(&mut self, part_name: impl Into<String>, materialized_ids: &[String]) {
+        for id in materialized_ids {
+            self.memtable.remove(id);
+        }
+        self.part_names.push(part_name.into());
+    }
+
+    /// Memtable rows live (unexpired) and, if `filter` is given, matching
+    /// it. Filters only ever see memtable attributes — on-disk parts carry
+    /// no attributes, the same limitation [`Self::export`] documents.
+    fn live_memtable_rows(&self, filter: Option<&FilterExpr>) -> Vec<Row> {
+        let now = SystemTime::now();
+        self.memtable
+            .values()
+            .filter(|doc| !doc.is_expired(now))
+            .filter(|doc| filter.is_none_or(|f| f.matches(doc)))
+            .map(to_row)
+            .collect()
+    }
+
+    /// Merge memtable and part results into a single top-k ranking.
+    /// Memtable rows whose TTL (the `expires_at` attribute) has passed
+    /// `SystemTime::now()` are excluded, even though they haven't been
+    /// physically purged yet — see [`Self::sweep_expired`].
+    pub fn search(&self, query: &[f32], top_k: usize) -> Result<Vec<ScoredRow>> {
+        crate::pipeline::reject_non_finite(query)?;
+        let memtable_rows = self.live_memtable_rows(None);
+        Ok(search_namespace(
+            query,
+            top_k,
+            &memtable_rows,
+            &self.parts_dir,
+            &self.part_names,
+        )?)
+    }
+
+    /// `search`, but with an explicit [`SearchMode`] — pass
+    /// [`SearchMode::Streamed`] for a `top_k` large enough that collecting
+    /// and sorting every candidate (`search`'s default) would dominate the
+    /// query, e.g. an analytics-style call for thousands of neighbors. See
+    /// [`NamespaceRegistry::validate_top_k`](crate::registry::NamespaceRegistry::validate_top_k)
+    /// for the guardrail that should run before a caller picks `top_k` this
+    /// large in the first place.
+    pub fn search_with_mode(&self, query: &[f32], top_k: usize, mode: SearchMode) -> Result<Vec<ScoredRow>> {
+        crate::pipeline::reject_non_finite(query)?;
+        let memtable_rows = self.live_memtable_rows(None);
+        Ok(search_namespace_with_options(
+            query,
+            top_k,
+            &memtable_rows,
+            &self.parts_dir,
+            &self.part_names,
+            &SearchOptions { mode, ..Default::default() },
+        )?)
+    }
+
+    /// `search`, but against the namespace's state as of `as_of_seq` (a
+    /// sequence number `upsert` previously returned) rather than its
+    /// current state. Only memtable history is versioned — parts carry no
+    /// validity ranges, so a row already materialized out of the memtable
+    /// is read at its current (not historical) value, and `as_of_seq`
+    /// points older than `DEFAULT_VERSION_RETENTION` writes ago for a given
+    /// id can no longer be reconstructed for that id.
+    pub fn search_as_of(&self, query: &[f32], top_k: usize, as_of_seq: u64) -> Result<Vec<ScoredRow>> {
+        crate::pipeline::reject_non_finite(query)?;
+        let now = SystemTime::now();
+        let memtable_rows: Vec<Row> = self
+            .history
+            .iter()
+            .filter(|(id, _)| self.memtable.contains_key(id.as_str()))
+            .filter_map(|(_, versions)| versions.iter().rev().find(|(seq, _)| *seq <= as_of_seq))
+            .map(|(_, doc)| doc)
+            .filter(|doc| !doc.is_expired(now))
+            .map(to_row)
+            .collect();
+        Ok(search_namespace(
+            query,
+            top_k,
+            &memtable_rows,
+            &self.parts_dir,
+            &self.part_names,
+        )?)
+    }
+
+    /// Physically remove memtable rows whose TTL has passed `now`, the way
+    /// a background expiration sweep would before the next part is
+    /// materialized — tombstoning rows on disk is left to the indexer's
+    /// part compaction, which this memtable-only sweep mirrors. Returns
+    /// the ids removed.
+    pub fn sweep_expired(&mut self, now: SystemTime) -> Vec<String> {
+        let expired: Vec<String> = self
+            .memtable
+            .values()
+            .filter(|doc| doc.is_expired(now))
+            .map(|doc| doc.id.clone())
+            .collect();
+        for id in &expired {
+            self.memtable.remove(id);
+        }
+        expired
+    }
+
+    /// Every live document in this namespace — the memtable plus all
+    /// materialized parts — in stable id order, for bulk migration or
+    /// reprocessing without hammering the query path. Expired rows are
+    /// excluded the same way [`Self::search`] excludes them; parts carry
+    /// no attributes, so exported part rows have an empty `attributes`.
+    pub fn export(&self) -> Result<Vec<Document>> {
+        let now = SystemTime::now();
+        let mut docs: Vec<Document> = self
+            .memtable
+            .values()
+            .filter(|doc| !doc.is_expired(now))
+            .cloned()
+            .collect();
+
+        for part_name in &self.part_names {
+            let rows = read_part_assets(&self.parts_dir, part_name)?;
+            docs.extend(rows.into_iter().map(|row| Document::new(row.id, row.vector)));
+        }
+
+        docs.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(docs)
+    }
+
+    /// In-memory bytes the memtable's vectors occupy, the tiered
+    /// equivalent of `NamespaceRegistry`'s row-map accounting — an id plus
+    /// its fp32 vector per row, the same estimate `export`'s row-at-a-time
+    /// decode makes real rather than a part-format-aware one.
+    pub fn memtable_bytes(&self) -> usize {
+        self.memtable.values().map(|doc| doc.id.len() + doc.vector.len() * 4).sum()
+    }
+
+    /// Total on-disk size, in bytes, of every part this namespace has
+    /// materialized, for capacity reporting without a caller having to
+    /// know the part file layout. The WAL (wherever a caller's writer
+    /// points it) and any object-store copy of these same parts are not
+    /// counted here — see [`elax_store::local::NamespaceHandle::disk_bytes`]
+    /// for the local WAL/manifest side of that accounting.
+    pub fn parts_disk_bytes(&self) -> Result<u64> {
+        let mut total = 0u64;
+        for part_name in &self.part_names {
+            total += elax_index::part_asset_bytes(&self.parts_dir, part_name)?;
+        }
+        Ok(total)
+    }
+
+    /// Bulk-fetch `ids`' vectors without scoring, sorting, or hydrating
+    /// attributes — for an offline reranking/clustering job that needs many
+    /// rows' vectors and would otherwise have to pay for a full
+    /// [`Self::export`]. Checks the memtable first, then consults on-disk
+    /// parts (newest-to-oldest, so a part a row has been superseded in
+    /// never shadows its memtable version) through `cache`, stopping as
+    /// soon as every id has been found. Ids with no matching row are
+    /// silently omitted from the result rather than erroring, the same way
+    /// a missing attribute sorts as `null` elsewhere in this crate.
+    pub fn fetch_vectors(&self, ids: &[String], cache: &elax_index::PartAssetCache) -> Result<VectorBatch> {
+        let mut found: HashMap<&str, Vec<f32>> = HashMap::new();
+        for id in ids {
+            if let Some(doc) = self.memtable.get(id) {
+                found.insert(id.as_str(), doc.vector.clone());
+            }
+        }
+
+        for part_name in self.part_names.iter().rev() {
+            if found.len() == ids.len() {
+                break;
+            }
+            let reader = cache.get_or_open(&self.parts_dir, part_name)?;
+            for (row, row_id) in reader.meta().ids.iter().enumerate() {
+                if found.contains_key(row_id.as_str()) {
+                    continue;
+                }
+                if let Some(id) = ids.iter().find(|id| *id == row_id) {
+                    found.insert(id.as_str(), reader.vector(row)?);
+                }
+            }
+        }
+
+        let mut batch = VectorBatch { ids: Vec::new(), vectors: Vec::new() };
+        for id in ids {
+            if let Some(vector) = found.remove(id.as_str()) {
+                batch.ids.push(id.clone());
+                batch.vectors.push(vector);
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Resolve `rank_by` into a query vector, embedding `ANN_TEXT` phrases
+    /// via `embedder` and `cache` on demand.
+    fn resolve_rank_by(
+        rank_by: RankBy,
+        model: &str,
+        embedder: Option<&dyn Embedder>,
+        cache: Option<&QueryEmbeddingCache>,
+    ) -> Result<Vec<f32>> {
+        match rank_by {
+            RankBy::Vector(vector) => Ok(vector),
+            RankBy::AnnText(text) => {
+                let embedder = embedder.ok_or_else(|| {
+                    CoreError::EmbeddingFailed("no embedder configured for ANN_TEXT".to_string())
+                })?;
+                match cache {
+                    Some(cache) => Ok(cache.get_or_embed(&text, model, embedder)?),
+                    None => Ok(embedder.embed(&text, model)?),
+                }
+            }
+        }
+    }
+
+    /// Resolve `rank_by` into a query vector — embedding `ANN_TEXT` phrases
+    /// via `embedder` and `cache` on demand — then search as usual.
+    pub fn search_by(
+        &self,
+        rank_by: RankBy,
+        model: &str,
+        embedder: Option<&dyn Embedder>,
+        cache: Option<&QueryEmbeddingCache>,
+        top_k: usize,
+    ) -> Result<Vec<ScoredRow>> {
+        let query = Self::resolve_rank_by(rank_by, model, embedder, cache)?;
+        self.search(&query, top_k)
+    }
+
+    /// Run every clause independently — each resolving its own `rank_by`,
+    /// scoring against its own metric, and pulling its own `top_k`
+    /// candidates — then fuse them by summing each clause's weighted score
+    /// per id (ids a clause didn't return contribute 0 to it), and return
+    /// the fused top-k, breaking a tied score by `order_by` (see
+    /// [`crate::scoring::order_by_attrs`]) rather than leaving it to
+    /// whatever order the ids happened to land in the fused map — on-disk
+    /// parts carry no attributes (see [`PlanHint`]'s doc comment), so a row
+    /// sourced from one reads as every `order_by` key missing. If `metrics`
+    /// is given, every filtered clause's observed matched/total ratio feeds
+    /// its [`NamespaceMetrics::filter_selectivity`] tracker. If `explain`
+    /// is given, one [`ClauseExplain`] per clause is pushed onto it, in
+    /// order, recording which [`PlanHint`] that clause actually ran under.
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_multi(
+        &self,
+        clauses: &[QueryClause],
+        model: &str,
+        embedder: Option<&dyn Embedder>,
+        cache: Option<&QueryEmbeddingCache>,
+        top_k: usize,
+        order_by: &[crate::attr_order::AttrOrder],
+        metrics: Option<&NamespaceMetrics>,
+        mut explain: Option<&mut Vec<ClauseExplain>>,
+    ) -> Result<Vec<ScoredRow>> {
+        let mut fused: HashMap<String, f32> = HashMap::new();
+        for clause in clauses {
+            let mut query = Self::resolve_rank_by(clause.rank_by.clone(), model, embedder, cache)?;
+            if clause.metric == Metric::Cosine {
+                normalize_l2(&mut query);
+            }
+            crate::pipeline::reject_non_finite(&query)?;
+
+            let plan = clause.plan_hint.unwrap_or_default();
+            let memtable_rows = match plan {
+                PlanHint::FilterFirst => self.live_memtable_rows(clause.filter.as_ref()),
+                PlanHint::VectorFirst | PlanHint::BruteForce => self.live_memtable_rows(None),
+            };
+            if plan == PlanHint::FilterFirst && clause.filter.is_some() {
+                if let Some(metrics) = metrics {
+                    let total = self.live_memtable_rows(None).len();
+                    metrics.filter_selectivity.observe(total, memtable_rows.len());
+                }
+            }
+
+            let mut results = search_namespace(&query, clause.top_k, &memtable_rows, &self.parts_dir, &self.part_names)?;
+            if let (PlanHint::VectorFirst, Some(filter)) = (plan, &clause.filter) {
+                let total = results.len();
+                results.retain(|row| self.memtable.get(&row.id).is_none_or(|doc| filter.matches(doc)));
+                if let Some(metrics) = metrics {
+                    metrics.filter_selectivity.observe(total, results.len());
+                }
+            }
+
+            if let Some(explain) = explain.as_deref_mut() {
+                explain.push(ClauseExplain {
+                    plan_used: plan,
+                    overrode_planner: plan != PlanHint::FilterFirst,
+                });
+            }
+
+            for row in results {
+                *fused.entry(row.id).or_insert(0.0) += row.score * clause.weight;
+            }
+        }
+
+        let rows: Vec<ScoredRow> = fused.into_iter().map(|(id, score)| ScoredRow { id, score }).collect();
+        let mut rows = if order_by.is_empty() {
+            let mut rows = rows;
+            rows.sort_by(|a, b| b.score.total_cmp(&a.score));
+            rows
+        } else {
+            let attributes: HashMap<String, serde_json::Value> = rows
+                .iter()
+                .filter_map(|row| self.memtable.get(&row.id).map(|doc| (row.id.clone(), doc.attributes.clone())))
+                .collect();
+            crate::scoring::order_by_attrs(rows, order_by, &attributes)
+        };
+        rows.truncate(top_k);
+        Ok(rows)
+    }
+}
+
+/// One hit from [`search_across`]: which namespace it came from, and a
+/// score normalized to `[0, 1]` within that namespace's own result set so
+/// namespaces scored on different scales (different metrics, different
+/// embedding models) merge fairly instead of one dominating purely because
+/// its raw scores happen to be larger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceHit {
+    pub namespace: String,
+    pub id: String,
+    pub score: f32,
+}
+
+/// Fan `query` out to every namespace in `namespaces`, min-max normalize
+/// each namespace's own scores independently, and merge into one global
+/// top-`top_k` ranking. For a per-tenant-per-collection layout where each
+/// namespace is searched with its own [`TieredNamespace::search`] call
+/// today, this is the multi-namespace equivalent.
+pub fn search_across(namespaces: &[&TieredNamespace], query: &[f32], top_k: usize) -> Result<Vec<NamespaceHit>> {
+    let mut hits = Vec::new();
+    for ns in namespaces {
+        let results = ns.search(query, top_k)?;
+        if results.is_empty() {
+            continue;
+        }
+        let min = results.iter().map(|row| row.score).fold(f32::INFINITY, f32::min);
+        let max = results.iter().map(|row| row.score).fold(f32::NEG_INFINITY, f32::max);
+        let range = max - min;
+        for row in results {
+            let score = if range > 0.0 { (row.score - min) / range } else { 1.0 };
+            hits.push(NamespaceHit { namespace: ns.name.clone(), id: row.id, score });
+        }
+    }
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(top_k);
+    Ok(hits)
+}
+
+/// Fan `query` out to every shard of one namespace and merge by raw score
+/// into a global top-`top_k`. Unlike [`search_across`], scores are *not*
+/// normalized per shard — every shard holds a disjoint slice of the same
+/// namespace (see [`crate::sharding::shard_for_id`]), scored with the same
+/// metric and embedding model, so raw scores are already comparable.
+pub fn search_sharded(shards: &[&TieredNamespace], query: &[f32], top_k: usize) -> Result<Vec<ScoredRow>> {
+    let mut rows = Vec::new();
+    for shard in shards {
+        rows.extend(shard.search(query, top_k)?);
+    }
+    rows.sort_by(|a, b| b.score.total_cmp(&a.score));
+    rows.truncate(top_k);
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-core-tiered-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn search_finds_memtable_rows_without_any_parts() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        ns.upsert(Document::new("b", vec![0.0, 1.0]));
+
+        let results = ns.search(&[1.0, 0.0], 1).unwrap();
+        assert_eq!(results[0].id, "a");
+    }
+
+    #[test]
+    fn search_with_mode_streamed_matches_the_default_collect_mode() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        ns.upsert(Document::new("b", vec![0.0, 1.0]));
+        ns.upsert(Document::new("c", vec![0.9, 0.1]));
+
+        let collect = ns.search(&[1.0, 0.0], 2).unwrap();
+        let streamed = ns.search_with_mode(&[1.0, 0.0], 2, elax_index::SearchMode::Streamed).unwrap();
+        assert_eq!(collect, streamed);
+    }
+
+    #[test]
+    fn adopt_part_evicts_materialized_rows_from_memtable() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        elax_index::build_part(
+            &ns.parts_dir,
+            "part-0",
+            &[Row::new("a", vec![1.0, 0.0])],
+            elax_index::VectorPrecision::F32,
+        )
+        .unwrap();
+
+        ns.adopt_part("part-0", &["a".to_string()]);
+        assert!(ns.memtable.is_empty());
+
+        let results = ns.search(&[1.0, 0.0], 1).unwrap();
+        assert_eq!(results[0].id, "a");
+    }
+
+    #[test]
+    fn memtable_bytes_counts_only_resident_rows() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        assert_eq!(ns.memtable_bytes(), 0);
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        assert_eq!(ns.memtable_bytes(), "a".len() + 2 * 4);
+    }
+
+    #[test]
+    fn parts_disk_bytes_counts_materialized_parts_and_ignores_the_memtable() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        assert_eq!(ns.parts_disk_bytes().unwrap(), 0);
+
+        elax_index::build_part(
+            &ns.parts_dir,
+            "part-0",
+            &[Row::new("a", vec![1.0, 0.0])],
+            elax_index::VectorPrecision::F32,
+        )
+        .unwrap();
+        ns.adopt_part("part-0", &["a".to_string()]);
+
+        assert!(ns.parts_disk_bytes().unwrap() > 0);
+    }
+
+    #[test]
+    fn fetch_vectors_reads_from_memtable_and_parts_and_skips_missing_ids() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+
+        elax_index::build_part(
+            &ns.parts_dir,
+            "part-0",
+            &[Row::new("b", vec![0.0, 1.0])],
+            elax_index::VectorPrecision::F32,
+        )
+        .unwrap();
+        ns.adopt_part("part-0", &["b".to_string()]);
+
+        let cache = elax_index::PartAssetCache::new();
+        let batch = ns
+            .fetch_vectors(&["a".to_string(), "b".to_string(), "missing".to_string()], &cache)
+            .unwrap();
+
+        assert_eq!(batch.ids, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(batch.vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+    }
+
+    #[test]
+    fn clone_into_shares_parts_but_starts_with_an_empty_memtable() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        elax_index::build_part(
+            &ns.parts_dir,
+            "part-0",
+            &[Row::new("a", vec![1.0, 0.0])],
+            elax_index::VectorPrecision::F32,
+        )
+        .unwrap();
+        ns.adopt_part("part-0", &["a".to_string()]);
+        ns.upsert(Document::new("b", vec![0.0, 1.0]));
+
+        let clone = ns.clone_into("docs-v2");
+        assert_eq!(clone.name, "docs-v2");
+        assert_eq!(clone.parts_dir, ns.parts_dir);
+        assert_eq!(clone.part_names, ns.part_names);
+        assert!(clone.memtable.is_empty());
+
+        let results = clone.search(&[1.0, 0.0], 1).unwrap();
+        assert_eq!(results[0].id, "a");
+    }
+
+    #[test]
+    fn search_as_of_reads_back_a_superseded_version() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        let seq_a1 = ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        ns.upsert(Document::new("a", vec![0.0, 1.0]));
+
+        let results = ns.search_as_of(&[1.0, 0.0], 1, seq_a1).unwrap();
+        assert_eq!(results[0].id, "a");
+        assert!((results[0].score - 1.0).abs() < 1e-6);
+
+        let current = ns.search(&[1.0, 0.0], 1).unwrap();
+        assert!((current[0].score - 0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn search_as_of_excludes_ids_not_yet_written_at_that_sequence() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        let seq_b = ns.upsert(Document::new("b", vec![0.0, 1.0]));
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+
+        let results = ns.search_as_of(&[1.0, 0.0], 10, seq_b).unwrap();
+        assert!(results.iter().all(|r| r.id != "a"));
+        assert!(results.iter().any(|r| r.id == "b"));
+    }
+
+    #[test]
+    fn search_across_merges_namespaces_and_tags_each_hit_with_its_source() {
+        let mut docs = TieredNamespace::new("docs", tmp_dir());
+        docs.upsert(Document::new("a", vec![1.0, 0.0]));
+
+        let mut images = TieredNamespace::new("images", tmp_dir());
+        images.upsert(Document::new("b", vec![1.0, 0.0]));
+
+        let hits = search_across(&[&docs, &images], &[1.0, 0.0], 10).unwrap();
+        assert_eq!(hits.len(), 2);
+        let namespaces: Vec<&str> = hits.iter().map(|h| h.namespace.as_str()).collect();
+        assert!(namespaces.contains(&"docs"));
+        assert!(namespaces.contains(&"images"));
+    }
+
+    #[test]
+    fn search_across_normalizes_each_namespaces_scores_independently() {
+        let mut small_scale = TieredNamespace::new("small", tmp_dir());
+        small_scale.upsert(Document::new("a", vec![0.01, 0.0]));
+        small_scale.upsert(Document::new("b", vec![0.02, 0.0]));
+
+        let hits = search_across(&[&small_scale], &[1.0, 0.0], 10).unwrap();
+        assert!(hits.iter().any(|h| h.score == 1.0));
+        assert!(hits.iter().any(|h| h.score == 0.0));
+    }
+
+    #[test]
+    fn search_sharded_merges_disjoint_shards_by_raw_score() {
+        let mut shard0 = TieredNamespace::new("docs-shard-0", tmp_dir());
+        shard0.upsert(Document::new("a", vec![1.0, 0.0]));
+
+        let mut shard1 = TieredNamespace::new("docs-shard-1", tmp_dir());
+        shard1.upsert(Document::new("b", vec![0.5, 0.0]));
+
+        let rows = search_sharded(&[&shard0, &shard1], &[1.0, 0.0], 10).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].id, "a");
+        assert_eq!(rows[1].id, "b");
+    }
+
+    #[test]
+    fn search_sharded_truncates_the_merged_result_to_top_k() {
+        let mut shard0 = TieredNamespace::new("docs-shard-0", tmp_dir());
+        shard0.upsert(Document::new("a", vec![1.0, 0.0]));
+
+        let mut shard1 = TieredNamespace::new("docs-shard-1", tmp_dir());
+        shard1.upsert(Document::new("b", vec![0.5, 0.0]));
+
+        let rows = search_sharded(&[&shard0, &shard1], &[1.0, 0.0], 1).unwrap();
+        assert_eq!(rows, vec![ScoredRow { id: "a".to_string(), score: 1.0 }]);
+    }
+
+    #[test]
+    fn search_excludes_expired_rows() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![1.0, 0.0],
+            attributes: serde_json::json!({"expires_at": 1}),
+            embedding: None,
+            embedding_model: None,
+        });
+        ns.upsert(Document::new("b", vec![1.0, 0.0]));
+
+        let results = ns.search(&[1.0, 0.0], 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "b");
+    }
+
+    #[test]
+    fn sweep_expired_purges_only_expired_memtable_rows() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![1.0, 0.0],
+            attributes: serde_json::json!({"expires_at": 1}),
+            embedding: None,
+            embedding_model: None,
+        });
+        ns.upsert(Document::new("b", vec![1.0, 0.0]));
+
+        let removed = ns.sweep_expired(std::time::UNIX_EPOCH + std::time::Duration::from_secs(5));
+        assert_eq!(removed, vec!["a".to_string()]);
+        assert_eq!(ns.memtable.len(), 1);
+        assert!(ns.memtable.contains_key("b"));
+    }
+
+    #[test]
+    fn export_merges_memtable_and_parts_in_id_order_and_skips_expired() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("z", vec![1.0, 0.0]));
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![0.0, 1.0],
+            attributes: serde_json::json!({"expires_at": 1}),
+            embedding: None,
+            embedding_model: None,
+        });
+        elax_index::build_part(
+            &ns.parts_dir,
+            "part-0",
+            &[Row::new("m", vec![0.0, 1.0])],
+            elax_index::VectorPrecision::F32,
+        )
+        .unwrap();
+        ns.adopt_part("part-0", &[]);
+
+        let exported = ns.export().unwrap();
+        let ids: Vec<&str> = exported.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["m", "z"]);
+    }
+
+    #[test]
+    fn search_multi_fuses_weighted_clauses() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        ns.upsert(Document::new("b", vec![0.0, 1.0]));
+
+        let clauses = vec![
+            QueryClause {
+                rank_by: RankBy::Vector(vec![1.0, 0.0]),
+                top_k: 10,
+                weight: 1.0,
+                filter: None,
+                metric: Metric::DotProduct,
+                plan_hint: None,
+            },
+            QueryClause {
+                rank_by: RankBy::Vector(vec![0.0, 1.0]),
+                top_k: 10,
+                weight: 5.0,
+                filter: None,
+                metric: Metric::DotProduct,
+                plan_hint: None,
+            },
+        ];
+
+        let results = ns.search_multi(&clauses, "model", None, None, 10, &[], None, None).unwrap();
+        assert_eq!(results[0].id, "b");
+        assert_eq!(results[1].id, "a");
+    }
+
+    #[test]
+    fn search_multi_order_by_breaks_a_tied_score() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![1.0, 0.0],
+            attributes: serde_json::json!({"rank": 2}),
+            embedding: None,
+            embedding_model: None,
+        });
+        ns.upsert(Document {
+            id: "b".to_string(),
+            vector: vec![1.0, 0.0],
+            attributes: serde_json::json!({"rank": 1}),
+            embedding: None,
+            embedding_model: None,
+        });
+
+        let clauses = vec![QueryClause {
+            rank_by: RankBy::Vector(vec![1.0, 0.0]),
+            top_k: 10,
+            weight: 1.0,
+            filter: None,
+            metric: Metric::DotProduct,
+            plan_hint: None,
+        }];
+        let order_by = vec![crate::attr_order::AttrOrder {
+            key: "rank".to_string(),
+            direction: crate::attr_order::SortDirection::Asc,
+            nulls: crate::attr_order::NullsOrder::Last,
+        }];
+
+        let results = ns.search_multi(&clauses, "model", None, None, 10, &order_by, None, None).unwrap();
+        assert_eq!(results[0].id, "b");
+        assert_eq!(results[1].id, "a");
+    }
+
+    #[test]
+    fn search_multi_clause_filter_excludes_non_matching_memtable_rows() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![1.0, 0.0],
+            attributes: serde_json::json!({"status": "archived"}),
+            embedding: None,
+            embedding_model: None,
+        });
+        ns.upsert(Document::new("b", vec![1.0, 0.0]));
+
+        let clauses = vec![QueryClause {
+            rank_by: RankBy::Vector(vec![1.0, 0.0]),
+            top_k: 10,
+            weight: 1.0,
+            filter: Some(FilterExpr::AttrEq {
+                key: "status".to_string(),
+                value: serde_json::json!("archived"),
+            }),
+            metric: Metric::DotProduct,
+            plan_hint: None,
+        }];
+
+        let results = ns.search_multi(&clauses, "model", None, None, 10, &[], None, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "a");
+    }
+
+    #[test]
+    fn search_multi_records_observed_filter_selectivity() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![1.0, 0.0],
+            attributes: serde_json::json!({"status": "archived"}),
+            embedding: None,
+            embedding_model: None,
+        });
+        ns.upsert(Document::new("b", vec![1.0, 0.0]));
+
+        let clauses = vec![QueryClause {
+            rank_by: RankBy::Vector(vec![1.0, 0.0]),
+            top_k: 10,
+            weight: 1.0,
+            filter: Some(FilterExpr::AttrEq {
+                key: "status".to_string(),
+                value: serde_json::json!("archived"),
+            }),
+            metric: Metric::DotProduct,
+            plan_hint: None,
+        }];
+
+        let metrics = NamespaceMetrics::default();
+        ns.search_multi(&clauses, "model", None, None, 10, &[], Some(&metrics), None).unwrap();
+        assert_eq!(metrics.filter_selectivity.estimate(), 0.5);
+    }
+
+    #[test]
+    fn search_multi_vector_first_filters_after_scoring_and_can_come_up_short() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![0.9, 0.1],
+            attributes: serde_json::json!({"status": "archived"}),
+            embedding: None,
+            embedding_model: None,
+        });
+        ns.upsert(Document::new("b", vec![1.0, 0.0]));
+
+        let clauses = vec![QueryClause {
+            rank_by: RankBy::Vector(vec![1.0, 0.0]),
+            top_k: 1,
+            weight: 1.0,
+            filter: Some(FilterExpr::AttrEq {
+                key: "status".to_string(),
+                value: serde_json::json!("archived"),
+            }),
+            metric: Metric::DotProduct,
+            plan_hint: Some(PlanHint::VectorFirst),
+        }];
+
+        let results = ns.search_multi(&clauses, "model", None, None, 1, &[], None, None).unwrap();
+        assert!(results.is_empty(), "top_k=1 picked the unfiltered winner \"b\", which VectorFirst then drops");
+    }
+
+    #[test]
+    fn search_multi_brute_force_ignores_filter_entirely() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document {
+            id: "a".to_string(),
+            vector: vec![1.0, 0.0],
+            attributes: serde_json::json!({"status": "archived"}),
+            embedding: None,
+            embedding_model: None,
+        });
+        ns.upsert(Document::new("b", vec![1.0, 0.0]));
+
+        let clauses = vec![QueryClause {
+            rank_by: RankBy::Vector(vec![1.0, 0.0]),
+            top_k: 10,
+            weight: 1.0,
+            filter: Some(FilterExpr::AttrEq {
+                key: "status".to_string(),
+                value: serde_json::json!("archived"),
+            }),
+            metric: Metric::DotProduct,
+            plan_hint: Some(PlanHint::BruteForce),
+        }];
+
+        let results = ns.search_multi(&clauses, "model", None, None, 10, &[], None, None).unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn search_multi_explain_records_plan_used_and_override_flag() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+
+        let clauses = vec![
+            QueryClause {
+                rank_by: RankBy::Vector(vec![1.0, 0.0]),
+                top_k: 10,
+                weight: 1.0,
+                filter: None,
+                metric: Metric::DotProduct,
+                plan_hint: None,
+            },
+            QueryClause {
+                rank_by: RankBy::Vector(vec![1.0, 0.0]),
+                top_k: 10,
+                weight: 1.0,
+                filter: None,
+                metric: Metric::DotProduct,
+                plan_hint: Some(PlanHint::BruteForce),
+            },
+        ];
+
+        let mut explain = Vec::new();
+        ns.search_multi(&clauses, "model", None, None, 10, &[], None, Some(&mut explain)).unwrap();
+        assert_eq!(
+            explain,
+            vec![
+                ClauseExplain { plan_used: PlanHint::FilterFirst, overrode_planner: false },
+                ClauseExplain { plan_used: PlanHint::BruteForce, overrode_planner: true },
+            ]
+        );
+    }
+
+    struct StubEmbedder;
+
+    impl Embedder for StubEmbedder {
+        fn embed(&self, text: &str, _model: &str) -> Result<Vec<f32>> {
+            Ok(if text == "find a" {
+                vec![1.0, 0.0]
+            } else {
+                vec![0.0, 1.0]
+            })
+        }
+    }
+
+    #[test]
+    fn ann_text_rank_by_embeds_the_query_on_the_fly() {
+        let mut ns = TieredNamespace::new("docs", tmp_dir());
+        ns.upsert(Document::new("a", vec![1.0, 0.0]));
+        ns.upsert(Document::new("b", vec![0.0, 1.0]));
+
+        let embedder = StubEmbedder;
+        let results = ns
+            .search_by(RankBy::AnnText("find a".to_string()), "test-model", Some(&embedder), None, 1)
+            .unwrap();
+        assert_eq!(results[0].id, "a");
+    }
+
+    #[test]
+    fn ann_text_rank_by_without_embedder_fails() {
+        let ns = TieredNamespace::new("docs", tmp_dir());
+        let err = ns
+            .search_by(RankBy::AnnText("find a".to_string()), "test-model", None, None, 1)
+            .unwrap_err();
+        assert!(matches!(err, CoreError::EmbeddingFailed(_)));
+    }
+}
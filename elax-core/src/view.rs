@@ -0,0 +1,64 @@
+//! Named, filtered sub-slices of a namespace ("views"): a stored
+//! [`FilterExpr`] and optional attribute projection, registered once via
+//! [`crate::registry::NamespaceRegistry::create_view`] and queried by
+//! targeting `"<namespace>@<view>"` instead of re-sending the filter on
+//! every call. This module only holds the static configuration — the
+//! moving part, a cached bitmap of matching ids that `NamespaceRegistry`
+//! keeps in sync incrementally as rows are written, deleted, or updated,
+//! lives there.
+
+use crate::filter::FilterExpr;
+
+/// What a view filters by, and which attribute keys a query against it
+/// gets back. `projection: None` returns every attribute, the same as not
+/// having a view at all; `Some(keys)` strips a matched row's attributes
+/// down to just `keys`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewConfig {
+    pub filter: FilterExpr,
+    pub projection: Option<Vec<String>>,
+}
+
+/// Strip `attributes` down to `projection`'s keys, or return it unchanged
+/// if there's no projection. A key `attributes` doesn't have is silently
+/// skipped rather than padded with `null`, the same omit-rather-than-pad
+/// shape [`crate::tiered::VectorBatch`] uses for ids it couldn't find.
+pub(crate) fn apply_projection(attributes: &serde_json::Value, projection: Option<&[String]>) -> serde_json::Value {
+    let Some(keys) = projection else {
+        return attributes.clone();
+    };
+    let mut projected = serde_json::Map::new();
+    if let Some(obj) = attributes.as_object() {
+        for key in keys {
+            if let Some(value) = obj.get(key) {
+                projected.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    serde_json::Value::Object(projected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_projection_keeps_only_the_listed_keys() {
+        let attributes = serde_json::json!({"a": 1, "b": 2, "c": 3});
+        let projected = apply_projection(&attributes, Some(&["a".to_string(), "c".to_string()]));
+        assert_eq!(projected, serde_json::json!({"a": 1, "c": 3}));
+    }
+
+    #[test]
+    fn apply_projection_with_none_returns_the_attributes_unchanged() {
+        let attributes = serde_json::json!({"a": 1});
+        assert_eq!(apply_projection(&attributes, None), attributes);
+    }
+
+    #[test]
+    fn apply_projection_skips_keys_the_row_does_not_have() {
+        let attributes = serde_json::json!({"a": 1});
+        let projected = apply_projection(&attributes, Some(&["a".to_string(), "missing".to_string()]));
+        assert_eq!(projected, serde_json::json!({"a": 1}));
+    }
+}
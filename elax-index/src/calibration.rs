@@ -0,0 +1,332 @@
+//! Empirical recall-vs-nprobe calibration, replacing the naive
+//! `recall * nlist` heuristic with a curve fit from sampled ground-truth
+//! queries.
+
+use std::collections::HashSet;
+
+use elax_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+
+use crate::ivf::{nprobe_for_recall, IvfIndex};
+use crate::Result;
+
+/// Observed recall at each sampled `nprobe`, sorted by `nprobe` ascending.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecallCurve {
+    pub points: Vec<(usize, f32)>,
+}
+
+fn recall_curve_key(namespace: &str) -> String {
+    format!("recall_curves/{namespace}.json")
+}
+
+impl RecallCurve {
+    pub fn load(store: &dyn ObjectStore, namespace: &str) -> Result<RecallCurve> {
+        match store.get(&recall_curve_key(namespace))? {
+            Some((bytes, _generation)) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(RecallCurve::default()),
+        }
+    }
+
+    pub fn save(&self, store: &dyn ObjectStore, namespace: &str) -> Result<()> {
+        let bytes = serde_json::to_vec(self)?;
+        let current = store.get(&recall_curve_key(namespace))?.map(|(_, g)| g);
+        store.put_if_match(&recall_curve_key(namespace), current, bytes)?;
+        Ok(())
+    }
+
+    /// Smallest sampled `nprobe` whose observed recall meets `target`,
+    /// falling back to the naive heuristic if nothing was sampled yet.
+    pub fn nprobe_for_target(&self, target_recall: f32, nlist: usize) -> usize {
+        self.points
+            .iter()
+            .find(|(_, recall)| *recall >= target_recall)
+            .map(|(nprobe, _)| *nprobe)
+            .unwrap_or_else(|| nprobe_for_recall(target_recall, nlist))
+    }
+}
+
+/// Recall of an IVF probe at `nprobe` against the true top-k (from a brute
+/// force scan of the same rows), averaged over `queries`.
+pub fn debug_recall(
+    index: &IvfIndex,
+    queries: &[(Vec<f32>, Vec<String> /* ground-truth top-k ids */)],
+    nprobe: usize,
+) -> f32 {
+    if queries.is_empty() {
+        return 1.0;
+    }
+    let mut total = 0.0;
+    for (query, truth) in queries {
+        let found: std::collections::HashSet<_> =
+            index.probe(query, nprobe).into_iter().map(|(id, _)| id).collect();
+        let hits = truth.iter().filter(|id| found.contains(*id)).count();
+        total += hits as f32 / truth.len().max(1) as f32;
+    }
+    total / queries.len() as f32
+}
+
+/// Sweep nprobe from 1..=nlist, sampling recall at each step, to build a
+/// per-namespace `RecallCurve`.
+pub fn calibrate(
+    index: &IvfIndex,
+    queries: &[(Vec<f32>, Vec<String>)],
+) -> RecallCurve {
+    let points = (1..=index.nlist)
+        .map(|nprobe| (nprobe, debug_recall(index, queries, nprobe)))
+        .collect();
+    RecallCurve { points }
+}
+
+/// One held-out query and the neighbor ids it's expected to return,
+/// ranked best-first — from a benchmark, not sampled from the namespace's
+/// own stored rows the way [`debug_recall`]'s queries are. Ranked order
+/// matters for [`evaluate`]'s MRR; recall/precision ignore it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundTruthQuery {
+    pub query: Vec<f32>,
+    pub expected_ids: Vec<String>,
+}
+
+/// A fixed ground-truth set, uploaded once per namespace and persisted so
+/// repeated [`evaluate`] runs measure drift against the same benchmark as
+/// the index is rebuilt, rather than [`debug_recall`]'s queries sampled
+/// from whatever rows happen to be resident right now.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroundTruthSet {
+    pub queries: Vec<GroundTruthQuery>,
+}
+
+fn ground_truth_key(namespace: &str) -> String {
+    format!("ground_truth/{namespace}.json")
+}
+
+impl GroundTruthSet {
+    pub fn load(store: &dyn ObjectStore, namespace: &str) -> Result<GroundTruthSet> {
+        match store.get(&ground_truth_key(namespace))? {
+            Some((bytes, _generation)) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(GroundTruthSet::default()),
+        }
+    }
+
+    pub fn save(&self, store: &dyn ObjectStore, namespace: &str) -> Result<()> {
+        let bytes = serde_json::to_vec(self)?;
+        let current = store.get(&ground_truth_key(namespace))?.map(|(_, g)| g);
+        store.put_if_match(&ground_truth_key(namespace), current, bytes)?;
+        Ok(())
+    }
+}
+
+/// Recall@k, precision@k and mean reciprocal rank of an IVF probe against
+/// a [`GroundTruthSet`], averaged over every query in it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EvalMetrics {
+    pub recall_at_k: f32,
+    pub precision_at_k: f32,
+    pub mrr: f32,
+}
+
+/// Evaluate `index` at `nprobe` against `ground_truth`'s queries, taking
+/// the top `k` results from each probe. A query with no expected ids
+/// contributes 0 to every metric rather than skewing the average with a
+/// divide-by-zero.
+pub fn evaluate(index: &IvfIndex, ground_truth: &GroundTruthSet, k: usize, nprobe: usize) -> EvalMetrics {
+    if ground_truth.queries.is_empty() {
+        return EvalMetrics { recall_at_k: 1.0, precision_at_k: 1.0, mrr: 1.0 };
+    }
+
+    let mut recall_sum = 0.0;
+    let mut precision_sum = 0.0;
+    let mut mrr_sum = 0.0;
+    for gt in &ground_truth.queries {
+        let found: Vec<String> = index.probe(&gt.query, nprobe).into_iter().take(k).map(|(id, _)| id).collect();
+        let expected: HashSet<&String> = gt.expected_ids.iter().collect();
+
+        let hits = found.iter().filter(|id| expected.contains(id)).count();
+        if !gt.expected_ids.is_empty() {
+            recall_sum += hits as f32 / gt.expected_ids.len() as f32;
+        }
+        if !found.is_empty() {
+            precision_sum += hits as f32 / found.len() as f32;
+        }
+        mrr_sum += found
+            .iter()
+            .position(|id| expected.contains(id))
+            .map(|rank| 1.0 / (rank as f32 + 1.0))
+            .unwrap_or(0.0);
+    }
+
+    let n = ground_truth.queries.len() as f32;
+    EvalMetrics {
+        recall_at_k: recall_sum / n,
+        precision_at_k: precision_sum / n,
+        mrr: mrr_sum / n,
+    }
+}
+
+/// One past [`evaluate`] run, timestamped by the caller rather than read
+/// from the clock here — the same explicit-`now` convention as
+/// `elax_core::flush_policy::should_flush` — so a trend
+/// dashboard can chart recall/precision/MRR drift over time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EvalRecord {
+    pub recorded_at_unix_secs: u64,
+    pub nprobe: usize,
+    pub metrics: EvalMetrics,
+}
+
+/// Append-only history of [`EvalRecord`]s for one namespace, persisted the
+/// same way [`GroundTruthSet`] and [`RecallCurve`] are.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvalHistory {
+    pub records: Vec<EvalRecord>,
+}
+
+fn eval_history_key(namespace: &str) -> String {
+    format!("eval_history/{namespace}.json")
+}
+
+impl EvalHistory {
+    pub fn load(store: &dyn ObjectStore, namespace: &str) -> Result<EvalHistory> {
+        match store.get(&eval_history_key(namespace))? {
+            Some((bytes, _generation)) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(EvalHistory::default()),
+        }
+    }
+
+    pub fn save(&self, store: &dyn ObjectStore, namespace: &str) -> Result<()> {
+        let bytes = serde_json::to_vec(self)?;
+        let current = store.get(&eval_history_key(namespace))?.map(|(_, g)| g);
+        store.put_if_match(&eval_history_key(namespace), current, bytes)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, record: EvalRecord) {
+        self.records.push(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::row::Row;
+    use elax_store::LocalStore;
+
+    fn tmp_store() -> LocalStore {
+        let dir = std::env::temp_dir().join(format!(
+            "elax-index-calibration-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        LocalStore::new(dir).unwrap()
+    }
+
+    fn three_point_index() -> IvfIndex {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.0, 1.0]),
+            Row::new("c", vec![-1.0, 0.0]),
+        ];
+        IvfIndex::build(rows, 3)
+    }
+
+    #[test]
+    fn evaluate_computes_recall_precision_and_mrr_against_ground_truth() {
+        let index = three_point_index();
+        let ground_truth = GroundTruthSet {
+            queries: vec![
+                GroundTruthQuery { query: vec![1.0, 0.0], expected_ids: vec!["a".to_string()] },
+                GroundTruthQuery { query: vec![0.0, 1.0], expected_ids: vec!["b".to_string(), "c".to_string()] },
+            ],
+        };
+
+        let metrics = evaluate(&index, &ground_truth, 1, index.nlist);
+        assert_eq!(metrics.recall_at_k, 0.75);
+        assert_eq!(metrics.precision_at_k, 1.0);
+        assert_eq!(metrics.mrr, 1.0);
+    }
+
+    #[test]
+    fn evaluate_on_an_empty_ground_truth_set_reports_perfect_metrics() {
+        let index = three_point_index();
+        let metrics = evaluate(&index, &GroundTruthSet::default(), 1, index.nlist);
+        assert_eq!(metrics, EvalMetrics { recall_at_k: 1.0, precision_at_k: 1.0, mrr: 1.0 });
+    }
+
+    #[test]
+    fn evaluate_scores_a_miss_as_zero_without_dividing_by_zero() {
+        let index = three_point_index();
+        let ground_truth = GroundTruthSet {
+            queries: vec![GroundTruthQuery { query: vec![1.0, 0.0], expected_ids: vec!["nonexistent".to_string()] }],
+        };
+
+        let metrics = evaluate(&index, &ground_truth, 1, index.nlist);
+        assert_eq!(metrics, EvalMetrics { recall_at_k: 0.0, precision_at_k: 0.0, mrr: 0.0 });
+    }
+
+    #[test]
+    fn ground_truth_set_round_trips_through_the_store() {
+        let store = tmp_store();
+        let ground_truth = GroundTruthSet {
+            queries: vec![GroundTruthQuery { query: vec![1.0, 0.0], expected_ids: vec!["a".to_string()] }],
+        };
+
+        ground_truth.save(&store, "ns1").unwrap();
+        let loaded = GroundTruthSet::load(&store, "ns1").unwrap();
+        assert_eq!(loaded.queries.len(), 1);
+        assert_eq!(loaded.queries[0].expected_ids, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn ground_truth_set_defaults_to_empty_when_nothing_saved_yet() {
+        let store = tmp_store();
+        let loaded = GroundTruthSet::load(&store, "missing").unwrap();
+        assert!(loaded.queries.is_empty());
+    }
+
+    #[test]
+    fn eval_history_records_accumulate_and_round_trip_through_the_store() {
+        let store = tmp_store();
+        let mut history = EvalHistory::default();
+        history.record(EvalRecord {
+            recorded_at_unix_secs: 1_000,
+            nprobe: 1,
+            metrics: EvalMetrics { recall_at_k: 0.5, precision_at_k: 0.5, mrr: 0.5 },
+        });
+        history.record(EvalRecord {
+            recorded_at_unix_secs: 2_000,
+            nprobe: 2,
+            metrics: EvalMetrics { recall_at_k: 1.0, precision_at_k: 1.0, mrr: 1.0 },
+        });
+        history.save(&store, "ns1").unwrap();
+
+        let loaded = EvalHistory::load(&store, "ns1").unwrap();
+        assert_eq!(loaded.records.len(), 2);
+        assert_eq!(loaded.records[1].metrics.recall_at_k, 1.0);
+    }
+
+    #[test]
+    fn calibration_curve_is_monotonic_enough_to_hit_full_recall_at_full_nprobe() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.0, 1.0]),
+            Row::new("c", vec![-1.0, 0.0]),
+        ];
+        let index = IvfIndex::build(rows, 3);
+        let queries = vec![(vec![1.0, 0.0], vec!["a".to_string()])];
+
+        let curve = calibrate(&index, &queries);
+        let (last_nprobe, last_recall) = *curve.points.last().unwrap();
+        assert_eq!(last_nprobe, index.nlist);
+        assert_eq!(last_recall, 1.0);
+    }
+
+    #[test]
+    fn falls_back_to_naive_heuristic_when_uncalibrated() {
+        let curve = RecallCurve::default();
+        assert_eq!(curve.nprobe_for_target(0.5, 100), 50);
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::part_builder::build_part;
+use crate::part_reader::read_part_assets;
+use crate::precision::VectorPrecision;
+use crate::row::Row;
+use crate::Result;
+
+/// Merge `input_parts` into a single new part named `output_part_name`,
+/// dropping any row whose id is in `tombstoned`. The input parts are left
+/// on disk untouched — the caller publishes a manifest that no longer
+/// references them, and a separate physical-GC pass (not implemented
+/// here) is what would eventually remove the now-unreferenced files.
+pub fn compact_parts(
+    dir: &Path,
+    input_parts: &[String],
+    output_part_name: &str,
+    tombstoned: &HashSet<String>,
+    precision: VectorPrecision,
+) -> Result<Vec<Row>> {
+    let mut rows = Vec::new();
+    for part_name in input_parts {
+        for row in read_part_assets(dir, part_name)? {
+            if !tombstoned.contains(&row.id) {
+                rows.push(row);
+            }
+        }
+    }
+    build_part(dir, output_part_name, &rows, precision)?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_dir() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-index-compactor-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn compaction_merges_parts_and_drops_tombstoned_rows() {
+        let dir = tmp_dir();
+        build_part(&dir, "part-0", &[Row::new("a", vec![1.0]), Row::new("b", vec![2.0])], VectorPrecision::F32).unwrap();
+        build_part(&dir, "part-1", &[Row::new("c", vec![3.0])], VectorPrecision::F32).unwrap();
+
+        let tombstoned: HashSet<String> = ["b".to_string()].into_iter().collect();
+        let rows = compact_parts(
+            &dir,
+            &["part-0".to_string(), "part-1".to_string()],
+            "part-merged",
+            &tombstoned,
+            VectorPrecision::F32,
+        )
+        .unwrap();
+
+        let mut ids: Vec<&str> = rows.iter().map(|r| r.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "c"]);
+
+        let merged = read_part_assets(&dir, "part-merged").unwrap();
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn compacting_with_no_tombstones_keeps_every_row() {
+        let dir = tmp_dir();
+        build_part(&dir, "part-0", &[Row::new("a", vec![1.0])], VectorPrecision::F32).unwrap();
+
+        let rows = compact_parts(&dir, &["part-0".to_string()], "part-merged", &HashSet::new(), VectorPrecision::F32).unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+}
@@ -0,0 +1,108 @@
+//! Near-duplicate vector detection, for maintenance operations that want
+//! to clean up scraped corpora: group rows whose vectors are within a
+//! similarity threshold of each other, optionally keeping just one per
+//! group.
+
+use std::collections::HashMap;
+
+use crate::row::Row;
+use crate::search::score;
+
+/// Union-find over row indices, used to group near-duplicates
+/// transitively: if a~b and b~c both clear the threshold, a/b/c end up in
+/// one cluster even if a~c itself doesn't.
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra] = rb;
+        }
+    }
+}
+
+/// Group `rows` into clusters of near-duplicates: any two rows whose dot-
+/// product similarity is at or above `threshold` end up in the same
+/// cluster. Singletons (no duplicate found) are omitted. O(n^2) pairwise
+/// comparisons, which is fine at the scale a maintenance operation runs
+/// at; a namespace too large for that should probe `IvfIndex` for
+/// candidate pairs instead of calling this directly.
+pub fn find_near_duplicate_clusters(rows: &[Row], threshold: f32) -> Vec<Vec<String>> {
+    let mut uf = UnionFind::new(rows.len());
+    for i in 0..rows.len() {
+        for j in (i + 1)..rows.len() {
+            if score(&rows[i].vector, &rows[j].vector) >= threshold {
+                uf.union(i, j);
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        let root = uf.find(i);
+        groups.entry(root).or_default().push(row.id.clone());
+    }
+    groups.into_values().filter(|cluster| cluster.len() > 1).collect()
+}
+
+/// Like [`find_near_duplicate_clusters`], but picks the first row in each
+/// cluster (by input order) as the keeper and returns the ids of every
+/// other member — the ids a caller would delete to dedupe the namespace
+/// down to one row per cluster.
+pub fn find_near_duplicates_to_remove(rows: &[Row], threshold: f32) -> Vec<String> {
+    find_near_duplicate_clusters(rows, threshold)
+        .into_iter()
+        .flat_map(|cluster| cluster.into_iter().skip(1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_rows_whose_similarity_clears_the_threshold() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.99, 0.01]),
+            Row::new("c", vec![0.0, 1.0]),
+        ];
+        let clusters = find_near_duplicate_clusters(&rows, 0.95);
+        assert_eq!(clusters.len(), 1);
+        let mut cluster = clusters[0].clone();
+        cluster.sort();
+        assert_eq!(cluster, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn a_row_with_no_duplicate_is_not_in_any_cluster() {
+        let rows = vec![Row::new("a", vec![1.0, 0.0]), Row::new("b", vec![0.0, 1.0])];
+        assert!(find_near_duplicate_clusters(&rows, 0.95).is_empty());
+    }
+
+    #[test]
+    fn keeping_the_first_row_per_cluster_removes_only_the_rest() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.99, 0.01]),
+            Row::new("c", vec![0.98, 0.02]),
+            Row::new("d", vec![0.0, 1.0]),
+        ];
+        let to_remove = find_near_duplicates_to_remove(&rows, 0.9);
+        assert_eq!(to_remove, vec!["b".to_string(), "c".to_string()]);
+    }
+}
@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("row {row} out of range (part has {count} rows)")]
+    RowOutOfRange { row: usize, count: usize },
+
+    #[error("store error: {0}")]
+    Store(#[from] elax_store::StoreError),
+}
+
+pub type Result<T> = std::result::Result<T, IndexError>;
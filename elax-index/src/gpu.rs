@@ -0,0 +1,268 @@
+//! Brute-force dot-product scoring, with an optional GPU-backed path.
+//!
+//! For namespaces small enough (roughly up to ~5M vectors) exhaustive
+//! scoring beats IVF routing on both recall (nothing is ever skipped) and,
+//! with enough parallelism, latency. [`CpuScorer`] is always available;
+//! [`wgpu_backend::GpuScorer`] exists only behind the `gpu` feature, since
+//! it pulls in `wgpu` (and the GPU driver stack it talks to) only for
+//! deployments that actually want it.
+
+use crate::search::score;
+
+/// Scores every query against every vector, for namespaces opting into
+/// exhaustive (rather than IVF-routed) search.
+pub trait BruteForceScorer {
+    /// Score every `vectors[i]` against every `queries[j]`. The returned
+    /// `scores[j][i]` is `queries[j] · vectors[i]`.
+    fn score_batch(&self, queries: &[Vec<f32>], vectors: &[Vec<f32>]) -> Vec<Vec<f32>>;
+}
+
+/// Plain sequential dot-product scoring. Always available, and what
+/// [`default_scorer`] falls back to when no GPU is present (or the `gpu`
+/// feature isn't compiled in at all).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuScorer;
+
+impl BruteForceScorer for CpuScorer {
+    fn score_batch(&self, queries: &[Vec<f32>], vectors: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        queries
+            .iter()
+            .map(|query| vectors.iter().map(|vector| score(query, vector)).collect())
+            .collect()
+    }
+}
+
+/// The best scorer available: a GPU-backed one if the `gpu` feature is
+/// enabled and an adapter is actually present at runtime, [`CpuScorer`]
+/// otherwise.
+pub fn default_scorer() -> Box<dyn BruteForceScorer> {
+    #[cfg(feature = "gpu")]
+    {
+        if let Some(scorer) = wgpu_backend::GpuScorer::try_new() {
+            return Box::new(scorer);
+        }
+    }
+    Box::new(CpuScorer)
+}
+
+#[cfg(feature = "gpu")]
+pub mod wgpu_backend {
+    //! The `wgpu`-backed brute-force scorer. Only compiled when the `gpu`
+    //! feature is enabled.
+
+    use super::BruteForceScorer;
+
+    const WORKGROUP_SIZE: u32 = 64;
+
+    const SHADER: &str = r#"
+        struct Dims {
+            dim: u32,
+            num_queries: u32,
+            num_vectors: u32,
+        }
+
+        @group(0) @binding(0) var<storage, read> queries: array<f32>;
+        @group(0) @binding(1) var<storage, read> vectors: array<f32>;
+        @group(0) @binding(2) var<storage, read_write> scores: array<f32>;
+        @group(0) @binding(3) var<uniform> dims: Dims;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let idx = id.x;
+            if (idx >= dims.num_queries * dims.num_vectors) {
+                return;
+            }
+            let q = idx / dims.num_vectors;
+            let v = idx % dims.num_vectors;
+            var acc: f32 = 0.0;
+            for (var d: u32 = 0u; d < dims.dim; d = d + 1u) {
+                acc = acc + queries[q * dims.dim + d] * vectors[v * dims.dim + d];
+            }
+            scores[idx] = acc;
+        }
+    "#;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+    struct Dims {
+        dim: u32,
+        num_queries: u32,
+        num_vectors: u32,
+        _padding: u32,
+    }
+
+    /// A GPU device/queue pair bound to a compute pipeline that scores
+    /// every query against every vector in one dispatch.
+    pub struct GpuScorer {
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        pipeline: wgpu::ComputePipeline,
+    }
+
+    impl GpuScorer {
+        /// Try to acquire a GPU adapter and build the scoring pipeline.
+        /// Returns `None` if no suitable adapter is present (no GPU, or a
+        /// headless environment without a software fallback), so callers
+        /// fall back to [`super::CpuScorer`].
+        pub fn try_new() -> Option<Self> {
+            pollster::block_on(Self::try_new_async())
+        }
+
+        async fn try_new_async() -> Option<Self> {
+            let instance = wgpu::Instance::default();
+            let adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions::default())
+                .await?;
+            let (device, queue) = adapter
+                .request_device(&wgpu::DeviceDescriptor::default(), None)
+                .await
+                .ok()?;
+
+            let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("brute-force-score"),
+                source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+            });
+            let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("brute-force-score"),
+                layout: None,
+                module: &shader,
+                entry_point: "main",
+            });
+
+            Some(Self { device, queue, pipeline })
+        }
+
+        fn score_batch_sync(&self, queries: &[Vec<f32>], vectors: &[Vec<f32>]) -> Vec<Vec<f32>> {
+            use wgpu::util::DeviceExt;
+
+            let dim = queries.first().map(|q| q.len()).unwrap_or(0);
+            let num_queries = queries.len() as u32;
+            let num_vectors = vectors.len() as u32;
+
+            let query_bytes: Vec<f32> = queries.iter().flatten().copied().collect();
+            let vector_bytes: Vec<f32> = vectors.iter().flatten().copied().collect();
+            let dims = Dims {
+                dim: dim as u32,
+                num_queries,
+                num_vectors,
+                _padding: 0,
+            };
+
+            let query_buf = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("queries"),
+                contents: bytemuck::cast_slice(&query_bytes),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+            let vector_buf = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("vectors"),
+                contents: bytemuck::cast_slice(&vector_bytes),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+            let dims_buf = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("dims"),
+                contents: bytemuck::cast_slice(std::slice::from_ref(&dims)),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+            let output_len = (num_queries as u64) * (num_vectors as u64) * 4;
+            let scores_buf = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("scores"),
+                size: output_len.max(4),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let readback_buf = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("readback"),
+                size: output_len.max(4),
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let layout = self.pipeline.get_bind_group_layout(0);
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("brute-force-score"),
+                layout: &layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: query_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: vector_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: scores_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: dims_buf.as_entire_binding(),
+                    },
+                ],
+            });
+
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("brute-force-score"),
+            });
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("brute-force-score"),
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                let total = (num_queries * num_vectors).max(1);
+                let workgroups = total.div_ceil(WORKGROUP_SIZE);
+                pass.dispatch_workgroups(workgroups, 1, 1);
+            }
+            encoder.copy_buffer_to_buffer(&scores_buf, 0, &readback_buf, 0, output_len.max(4));
+            self.queue.submit(Some(encoder.finish()));
+
+            let slice = readback_buf.slice(..);
+            let (tx, rx) = std::sync::mpsc::channel();
+            slice.map_async(wgpu::MapMode::Read, move |result| {
+                let _ = tx.send(result);
+            });
+            self.device.poll(wgpu::Maintain::Wait);
+            rx.recv().expect("map_async callback dropped its sender").expect("failed to map score readback buffer");
+
+            let data = slice.get_mapped_range();
+            let flat: &[f32] = bytemuck::cast_slice(&data);
+            let mut out = Vec::with_capacity(num_queries as usize);
+            for q in 0..num_queries as usize {
+                let start = q * num_vectors as usize;
+                out.push(flat[start..start + num_vectors as usize].to_vec());
+            }
+            drop(data);
+            readback_buf.unmap();
+            out
+        }
+    }
+
+    impl BruteForceScorer for GpuScorer {
+        fn score_batch(&self, queries: &[Vec<f32>], vectors: &[Vec<f32>]) -> Vec<Vec<f32>> {
+            self.score_batch_sync(queries, vectors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_scorer_matches_the_plain_dot_product() {
+        let queries = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]];
+        let scores = CpuScorer.score_batch(&queries, &vectors);
+        assert_eq!(scores, vec![vec![1.0, 0.0, 1.0], vec![0.0, 1.0, 1.0]]);
+    }
+
+    #[test]
+    fn default_scorer_falls_back_to_cpu_without_the_gpu_feature() {
+        let scorer = default_scorer();
+        let scores = scorer.score_batch(&[vec![1.0, 0.0]], &[vec![1.0, 0.0]]);
+        assert_eq!(scores, vec![vec![1.0]]);
+    }
+}
@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+
+use crate::part_builder::pack_rabitq;
+use crate::row::Row;
+use crate::search::score;
+
+/// Tuning knobs for [`IvfIndex::build_with_options`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IvfBuildOptions {
+    /// Assign each vector to its top-2 nearest lists instead of just the
+    /// nearest one (a SOAR-style spilled assignment), so a query that
+    /// narrowly misses a vector's primary list can still find it via its
+    /// secondary one. Improves recall at small `nprobe` at the cost of
+    /// roughly doubling posting-list storage.
+    pub spill: bool,
+    /// How much of each row's vector stays resident after indexing. See
+    /// [`ResidentPrecision`].
+    pub resident_precision: ResidentPrecision,
+}
+
+/// How much of a row's vector [`IvfIndex`] keeps resident once it's been
+/// indexed. Int8 and RaBitQ codes are always computed and kept regardless
+/// of this setting (they're cheap, and [`IvfIndex::probe_int8`]/
+/// [`IvfIndex::probe_rabitq`] need them); this only controls whether the
+/// original fp32 vector is *also* kept, which dominates memory on a big
+/// namespace. `Int8` and `Binary` both drop it and keep the same codes, so
+/// they save the same amount: per dimension, 1 1/8 bytes stay resident
+/// instead of 5 1/8 (roughly 4.5x less for high-dimensional vectors), at
+/// the cost of [`IvfIndex::probe`] and
+/// [`IvfIndex::probe_matryoshka`] no longer having a fp32 vector to score
+/// against — callers on a quantized index must use `probe_int8`/
+/// `probe_rabitq` for the coarse scan and fetch fp32 from the row's part
+/// (e.g. via `PartMmapReader::vector`) for the final rerank, the same
+/// fetch-on-demand split [`IvfIndex::probe_rabitq`]'s doc comment already
+/// describes for binary codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResidentPrecision {
+    #[default]
+    Full,
+    Int8,
+    Binary,
+}
+
+/// A row's int8-quantized vector: one byte per dimension plus the scale
+/// needed to recover an approximate fp32 magnitude, computed from that
+/// row's own max-abs value (so unlike a part's single part-wide
+/// `int8_scale`, quantization error doesn't grow with how skewed the
+/// namespace's vector magnitudes are).
+#[derive(Debug, Clone)]
+struct Int8Code {
+    codes: Vec<i8>,
+    scale: f32,
+}
+
+fn quantize_int8(vector: &[f32]) -> Int8Code {
+    let max_abs = vector.iter().fold(0.0_f32, |acc, v| acc.max(v.abs())).max(f32::EPSILON);
+    let scale = max_abs / 127.0;
+    let codes = vector
+        .iter()
+        .map(|&v| (v / scale).round().clamp(-127.0, 127.0) as i8)
+        .collect();
+    Int8Code { codes, scale }
+}
+
+fn int8_dot(a: &[i8], b: &[i8]) -> i32 {
+    a.iter().zip(b).map(|(&x, &y)| x as i32 * y as i32).sum()
+}
+
+/// A minimal IVF (inverted file) index: rows are bucketed into `nlist`
+/// posting lists by nearest centroid, and a query only scores the rows in
+/// the `nprobe` closest lists.
+pub struct IvfIndex {
+    pub nlist: usize,
+    centroids: Vec<Vec<f32>>,
+    postings: Vec<Vec<Row>>,
+    /// Packed RaBitQ codes, parallel to `postings` list-for-list and
+    /// row-for-row, so [`Self::probe_rabitq`] never needs to touch a row's
+    /// fp32 vector to score it.
+    rabitq_codes: Vec<Vec<Vec<u8>>>,
+    /// Int8 codes, parallel to `postings` the same way `rabitq_codes` is,
+    /// so [`Self::probe_int8`] never needs to touch a row's fp32 vector
+    /// either.
+    int8_codes: Vec<Vec<Int8Code>>,
+}
+
+impl IvfIndex {
+    /// Build an index with `nlist` lists, assigning each row to its nearest
+    /// centroid among a fixed sample of `nlist` rows (no iterative k-means;
+    /// good enough for routing, not for production-grade clustering).
+    pub fn build(rows: Vec<Row>, nlist: usize) -> Self {
+        Self::build_with_options(rows, nlist, IvfBuildOptions::default())
+    }
+
+    /// [`Self::build`], with spilled assignment configurable via `options`.
+    pub fn build_with_options(rows: Vec<Row>, nlist: usize, options: IvfBuildOptions) -> Self {
+        let nlist = nlist.max(1).min(rows.len().max(1));
+        let centroids: Vec<Vec<f32>> = rows
+            .iter()
+            .step_by((rows.len() / nlist).max(1))
+            .take(nlist)
+            .map(|r| r.vector.clone())
+            .collect();
+        let nlist = centroids.len().max(1);
+        let fanout = if options.spill { 2 } else { 1 };
+
+        let mut postings = vec![Vec::new(); nlist];
+        let mut rabitq_codes = vec![Vec::new(); nlist];
+        let mut int8_codes = vec![Vec::new(); nlist];
+        for row in rows {
+            for list in top_k_centroids(&centroids, &row.vector, fanout) {
+                rabitq_codes[list].push(pack_rabitq(&row.vector));
+                int8_codes[list].push(quantize_int8(&row.vector));
+                let mut resident = row.clone();
+                if options.resident_precision != ResidentPrecision::Full {
+                    resident.vector.clear();
+                }
+                postings[list].push(resident);
+            }
+        }
+
+        Self {
+            nlist,
+            centroids,
+            postings,
+            rabitq_codes,
+            int8_codes,
+        }
+    }
+
+    /// Probe the `nprobe` lists whose centroid is closest to `query`,
+    /// returning candidate rows scored by dot product.
+    pub fn probe(&self, query: &[f32], nprobe: usize) -> Vec<(String, f32)> {
+        self.probe_matryoshka(query, nprobe, None)
+    }
+
+    /// Matryoshka-style probe: rank lists using only the first
+    /// `coarse_dims` dimensions of `query` and each centroid, then score
+    /// every row of the probed lists on the full vector. Rows within a
+    /// list are not pre-filtered on the prefix. `coarse_dims = None` is
+    /// equivalent to [`Self::probe`].
+    /// Only sound for Matryoshka-trained embeddings, where a prefix of the
+    /// vector is itself a valid lower-dimensional embedding.
+    pub fn probe_matryoshka(
+        &self,
+        query: &[f32],
+        nprobe: usize,
+        coarse_dims: Option<usize>,
+    ) -> Vec<(String, f32)> {
+        self.probe_filtered(query, nprobe, coarse_dims, &|_| true)
+    }
+
+    /// Like [`Self::probe_matryoshka`], but rows whose id fails `allowed`
+    /// are skipped during the coarse scan rather than scored and discarded
+    /// afterward. Plain ANN-then-filter wastes the probe budget on
+    /// candidates a selective filter throws away, which can collapse
+    /// recall; skipping them during the scan instead means every id this
+    /// returns is already filter-eligible.
+    pub fn probe_filtered(
+        &self,
+        query: &[f32],
+        nprobe: usize,
+        coarse_dims: Option<usize>,
+        allowed: &dyn Fn(&str) -> bool,
+    ) -> Vec<(String, f32)> {
+        let nprobe = nprobe.min(self.nlist).max(1);
+        let coarse_query = truncate(query, coarse_dims);
+
+        let mut list_order: Vec<usize> = (0..self.centroids.len()).collect();
+        list_order.sort_by(|&a, &b| {
+            score(coarse_query, truncate(&self.centroids[b], coarse_dims))
+                .total_cmp(&score(coarse_query, truncate(&self.centroids[a], coarse_dims)))
+        });
+
+        // A spilled vector can live in more than one probed list; keep only
+        // its best score so it doesn't show up twice in the ranking.
+        let mut best: HashMap<String, f32> = HashMap::new();
+        for &list in list_order.iter().take(nprobe) {
+            for row in &self.postings[list] {
+                if !allowed(&row.id) {
+                    continue;
+                }
+                let candidate = score(query, &row.vector);
+                best.entry(row.id.clone())
+                    .and_modify(|existing| *existing = existing.max(candidate))
+                    .or_insert(candidate);
+            }
+        }
+        let mut out: Vec<(String, f32)> = best.into_iter().collect();
+        out.sort_by(|a, b| b.1.total_cmp(&a.1));
+        out
+    }
+
+    /// Ramp `nprobe` up in doubling steps from `initial_nprobe`, re-probing
+    /// after each step, and stop as soon as the `top_k`-th candidate's
+    /// score has moved by no more than `epsilon` since the previous step
+    /// (the ranking has "stabilized") or `nprobe` has reached `max_nprobe`
+    /// — whichever comes first. A latency/recall knob for callers who'd
+    /// rather stop probing once the answer quits changing than pay a
+    /// fixed large `nprobe` on every query regardless of how quickly it
+    /// converged.
+    pub fn probe_adaptive(
+        &self,
+        query: &[f32],
+        initial_nprobe: usize,
+        max_nprobe: usize,
+        top_k: usize,
+        epsilon: f32,
+    ) -> Vec<(String, f32)> {
+        let max_nprobe = max_nprobe.min(self.nlist).max(1);
+        let mut nprobe = initial_nprobe.max(1).min(max_nprobe);
+        let mut results = self.probe(query, nprobe);
+
+        loop {
+            let kth_score = results.get(top_k.saturating_sub(1).min(results.len().saturating_sub(1))).map(|(_, s)| *s);
+            if nprobe >= max_nprobe {
+                break;
+            }
+            let next_nprobe = (nprobe * 2).min(max_nprobe);
+            let next_results = self.probe(query, next_nprobe);
+            let next_kth_score = next_results
+                .get(top_k.saturating_sub(1).min(next_results.len().saturating_sub(1)))
+                .map(|(_, s)| *s);
+
+            nprobe = next_nprobe;
+            let stabilized = match (kth_score, next_kth_score) {
+                (Some(prev), Some(cur)) => (cur - prev).abs() <= epsilon,
+                (None, None) => true,
+                _ => false,
+            };
+            results = next_results;
+            if stabilized {
+                break;
+            }
+        }
+        results
+    }
+
+    /// Like [`Self::probe`], but scores candidates against their packed
+    /// RaBitQ codes instead of decoding full vectors — only the `nprobe`
+    /// selected lists are ever touched, so the codes of unprobed lists are
+    /// never read at all. Scores are an approximate Hamming-based stand-in
+    /// for dot product; callers that need exact ranking should rerank the
+    /// top results against full vectors (e.g. via `PartMmapReader::vector`).
+    pub fn probe_rabitq(&self, query: &[f32], nprobe: usize) -> Vec<(String, i32)> {
+        let nprobe = nprobe.min(self.nlist).max(1);
+        let query_code = pack_rabitq(query);
+
+        let mut list_order: Vec<usize> = (0..self.centroids.len()).collect();
+        list_order.sort_by(|&a, &b| {
+            score(query, &self.centroids[b])
+                .total_cmp(&score(query, &self.centroids[a]))
+        });
+
+        let mut best: HashMap<String, i32> = HashMap::new();
+        for &list in list_order.iter().take(nprobe) {
+            for (row, code) in self.postings[list].iter().zip(&self.rabitq_codes[list]) {
+                let candidate = rabitq_similarity(&query_code, code);
+                best.entry(row.id.clone())
+                    .and_modify(|existing| *existing = (*existing).max(candidate))
+                    .or_insert(candidate);
+            }
+        }
+        let mut out: Vec<(String, i32)> = best.into_iter().collect();
+        out.sort_by_key(|(_, similarity)| std::cmp::Reverse(*similarity));
+        out
+    }
+
+    /// Like [`Self::probe_rabitq`], but scores candidates against their
+    /// int8 codes instead of their packed RaBitQ codes — coarser savings
+    /// (4x vs. fp32, instead of RaBitQ's ~32x) but a closer approximation
+    /// of the true dot product, for callers willing to trade some of the
+    /// memory win for recall. Same caveat as `probe_rabitq`: rerank the
+    /// top results against full vectors for an exact score.
+    pub fn probe_int8(&self, query: &[f32], nprobe: usize) -> Vec<(String, f32)> {
+        let nprobe = nprobe.min(self.nlist).max(1);
+        let query_code = quantize_int8(query);
+
+        let mut list_order: Vec<usize> = (0..self.centroids.len()).collect();
+        list_order.sort_by(|&a, &b| {
+            score(query, &self.centroids[b])
+                .total_cmp(&score(query, &self.centroids[a]))
+        });
+
+        let mut best: HashMap<String, f32> = HashMap::new();
+        for &list in list_order.iter().take(nprobe) {
+            for (row, code) in self.postings[list].iter().zip(&self.int8_codes[list]) {
+                let candidate = int8_dot(&query_code.codes, &code.codes) as f32 * query_code.scale * code.scale;
+                best.entry(row.id.clone())
+                    .and_modify(|existing| *existing = existing.max(candidate))
+                    .or_insert(candidate);
+            }
+        }
+        let mut out: Vec<(String, f32)> = best.into_iter().collect();
+        out.sort_by(|a, b| b.1.total_cmp(&a.1));
+        out
+    }
+
+    /// Rough in-memory footprint of this index — centroids, posting lists
+    /// (a duplicate `Row` per spilled assignment when built with
+    /// `IvfBuildOptions::spill`), packed RaBitQ codes, and int8 codes —
+    /// for capacity planning. Counts each `Row`'s id bytes plus its fp32
+    /// vector (empty when built with a non-`Full` `resident_precision`);
+    /// doesn't account for allocator overhead or `Vec` spare capacity.
+    pub fn memory_bytes(&self) -> usize {
+        let centroid_bytes: usize = self.centroids.iter().map(|c| c.len() * 4).sum();
+        let posting_bytes: usize = self
+            .postings
+            .iter()
+            .flatten()
+            .map(|row| row.id.len() + row.vector.len() * 4)
+            .sum();
+        let rabitq_bytes: usize = self.rabitq_codes.iter().flatten().map(|code| code.len()).sum();
+        let int8_bytes: usize = self.int8_codes.iter().flatten().map(|code| code.codes.len()).sum();
+        centroid_bytes + posting_bytes + rabitq_bytes + int8_bytes
+    }
+}
+
+/// Approximate similarity between two RaBitQ sign-bit codes: bits that
+/// agree push the score up, bits that disagree push it down. Cheap
+/// stand-in for dot product that never needs the original fp32 vectors.
+fn rabitq_similarity(a: &[u8], b: &[u8]) -> i32 {
+    let mut agree = 0i32;
+    let mut total = 0i32;
+    for (&x, &y) in a.iter().zip(b) {
+        agree += (!(x ^ y)).count_ones() as i32;
+        total += 8;
+    }
+    2 * agree - total
+}
+
+fn truncate(v: &[f32], dims: Option<usize>) -> &[f32] {
+    match dims {
+        Some(dims) => &v[..dims.min(v.len())],
+        None => v,
+    }
+}
+
+/// The `k` centroids closest to `vector`, nearest first.
+fn top_k_centroids(centroids: &[Vec<f32>], vector: &[f32], k: usize) -> Vec<usize> {
+    let mut scored: Vec<(usize, f32)> = centroids
+        .iter()
+        .enumerate()
+        .map(|(i, centroid)| (i, score(vector, centroid)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().take(k.max(1)).map(|(i, _)| i).collect()
+}
+
+/// Naive linear heuristic: probe a fraction of lists proportional to the
+/// target recall. Simple, but doesn't account for how skewed the
+/// list/centroid geometry actually is for a given namespace.
+pub fn nprobe_for_recall(target_recall: f32, nlist: usize) -> usize {
+    ((target_recall.clamp(0.0, 1.0)) * nlist as f32).ceil() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_finds_nearest_row() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.0, 1.0]),
+            Row::new("c", vec![-1.0, 0.0]),
+        ];
+        let index = IvfIndex::build(rows, 3);
+        let results = index.probe(&[1.0, 0.0], 3);
+        assert!(results.iter().any(|(id, _)| id == "a"));
+    }
+
+    #[test]
+    fn memory_bytes_grows_with_more_rows() {
+        let small = IvfIndex::build(vec![Row::new("a", vec![1.0, 0.0])], 1);
+        let large = IvfIndex::build(
+            vec![
+                Row::new("a", vec![1.0, 0.0]),
+                Row::new("b", vec![0.0, 1.0]),
+                Row::new("c", vec![-1.0, 0.0]),
+            ],
+            3,
+        );
+        assert!(large.memory_bytes() > small.memory_bytes());
+    }
+
+    #[test]
+    fn naive_nprobe_scales_with_recall() {
+        assert_eq!(nprobe_for_recall(0.5, 100), 50);
+        assert_eq!(nprobe_for_recall(0.9, 100), 90);
+    }
+
+    #[test]
+    fn probe_adaptive_finds_the_same_top_result_as_a_full_probe() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.0, 1.0]),
+            Row::new("c", vec![-1.0, 0.0]),
+            Row::new("d", vec![0.0, -1.0]),
+        ];
+        let index = IvfIndex::build(rows, 4);
+        let results = index.probe_adaptive(&[1.0, 0.0], 1, 4, 1, 0.0);
+        assert_eq!(results[0].0, "a");
+    }
+
+    #[test]
+    fn probe_adaptive_never_exceeds_max_nprobe() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.0, 1.0]),
+            Row::new("c", vec![-1.0, 0.0]),
+            Row::new("d", vec![0.0, -1.0]),
+        ];
+        let index = IvfIndex::build(rows, 4);
+        // The k-th score moves from 1.0 (one list) to 0.0 (two lists), so a
+        // zero epsilon never sees it stabilize; this only terminates
+        // because the ramp hits max_nprobe.
+        let results = index.probe_adaptive(&[1.0, 0.0], 1, 2, 4, 0.0);
+        assert!(results.len() <= 2);
+    }
+
+    #[test]
+    fn probe_filtered_excludes_disallowed_ids_even_when_closest() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.9, 0.1]),
+            Row::new("c", vec![-1.0, 0.0]),
+        ];
+        let index = IvfIndex::build(rows, 3);
+        let results = index.probe_filtered(&[1.0, 0.0], 3, None, &|id| id != "a");
+        assert!(!results.iter().any(|(id, _)| id == "a"));
+        assert!(results.iter().any(|(id, _)| id == "b"));
+    }
+
+    #[test]
+    fn probe_rabitq_ranks_the_sign_aligned_row_first() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 1.0, 1.0, 1.0]),
+            Row::new("b", vec![-1.0, -1.0, -1.0, -1.0]),
+        ];
+        let index = IvfIndex::build(rows, 2);
+        let results = index.probe_rabitq(&[1.0, 1.0, 1.0, 1.0], 2);
+        assert_eq!(results[0].0, "a");
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn probe_rabitq_only_touches_the_probed_lists() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0]),
+            Row::new("b", vec![0.0, 1.0]),
+            Row::new("c", vec![-1.0, 0.0]),
+        ];
+        let index = IvfIndex::build(rows, 3);
+        let results = index.probe_rabitq(&[1.0, 0.0], 1);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "a");
+    }
+
+    #[test]
+    fn spill_assignment_lets_a_probe_of_the_secondary_list_still_find_a_borderline_vector() {
+        // "mid" is closest to a's centroid, second-closest to b's, and far
+        // from c's. A single-list probe aimed at b only finds it once
+        // spill assignment has also placed it in b's posting list.
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0, 0.0]),
+            Row::new("b", vec![0.0, 1.0, 0.0]),
+            Row::new("c", vec![0.0, 0.0, 1.0]),
+            Row::new("mid", vec![0.6, 0.4, 0.0]),
+        ];
+
+        let without_spill = IvfIndex::build(rows.clone(), 3);
+        let probe_b_only = without_spill.probe_filtered(&[0.0, 1.0, 0.0], 1, None, &|id| id == "mid");
+        assert!(probe_b_only.is_empty());
+
+        let with_spill = IvfIndex::build_with_options(rows, 3, IvfBuildOptions { spill: true, ..Default::default() });
+        let probe_b_only = with_spill.probe_filtered(&[0.0, 1.0, 0.0], 1, None, &|id| id == "mid");
+        assert!(probe_b_only.iter().any(|(id, _)| id == "mid"));
+    }
+
+    #[test]
+    fn spilled_rows_are_not_duplicated_in_probe_results() {
+        let rows = vec![Row::new("a", vec![1.0, 0.0]), Row::new("b", vec![0.0, 1.0])];
+        let index = IvfIndex::build_with_options(rows, 2, IvfBuildOptions { spill: true, ..Default::default() });
+        let results = index.probe(&[1.0, 0.0], 2);
+        let a_count = results.iter().filter(|(id, _)| id == "a").count();
+        assert_eq!(a_count, 1);
+    }
+
+    #[test]
+    fn probe_int8_ranks_the_closest_row_first() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 1.0, 1.0, 1.0]),
+            Row::new("b", vec![-1.0, -1.0, -1.0, -1.0]),
+        ];
+        let index = IvfIndex::build(rows, 2);
+        let results = index.probe_int8(&[1.0, 1.0, 1.0, 1.0], 2);
+        assert_eq!(results[0].0, "a");
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn dropping_fp32_residency_shrinks_memory_bytes_but_keeps_quantized_probes_working() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 1.0, 1.0, 1.0]),
+            Row::new("b", vec![-1.0, -1.0, -1.0, -1.0]),
+        ];
+        let full = IvfIndex::build_with_options(rows.clone(), 2, IvfBuildOptions::default());
+        let quantized_only = IvfIndex::build_with_options(
+            rows,
+            2,
+            IvfBuildOptions {
+                resident_precision: ResidentPrecision::Binary,
+                ..Default::default()
+            },
+        );
+
+        assert!(quantized_only.memory_bytes() < full.memory_bytes());
+        let results = quantized_only.probe_rabitq(&[1.0, 1.0, 1.0, 1.0], 2);
+        assert_eq!(results[0].0, "a");
+        let results = quantized_only.probe_int8(&[1.0, 1.0, 1.0, 1.0], 2);
+        assert_eq!(results[0].0, "a");
+    }
+
+    #[test]
+    fn matryoshka_coarse_scan_still_reranks_on_full_vector() {
+        let rows = vec![
+            Row::new("a", vec![1.0, 0.0, 0.0, 0.0]),
+            Row::new("b", vec![1.0, 0.0, 1.0, 0.0]),
+        ];
+        let index = IvfIndex::build(rows, 2);
+        let results = index.probe_matryoshka(&[1.0, 0.0, 1.0, 0.0], 2, Some(2));
+        assert_eq!(results[0].0, "b");
+    }
+}
@@ -0,0 +1,43 @@
+//! Immutable part file format, written by [`part_builder`] and consumed by
+//! both the decode-everything `read_part_assets` path and the mmap-based
+//! [`part_reader::PartMmapReader`], plus the tiered `search_namespace` used
+//! to merge memtable and on-disk results.
+
+pub mod calibration;
+pub mod compactor;
+pub mod dedupe;
+pub mod error;
+pub mod gpu;
+pub mod ivf;
+pub mod part_builder;
+#[cfg(feature = "mmap")]
+pub mod part_cache;
+pub mod part_reader;
+pub mod precision;
+pub mod projection;
+pub mod row;
+pub mod search;
+#[cfg(feature = "wasm")]
+pub mod wasm_query;
+
+pub use calibration::{
+    calibrate, debug_recall, evaluate, EvalHistory, EvalMetrics, EvalRecord, GroundTruthQuery, GroundTruthSet,
+    RecallCurve,
+};
+pub use compactor::compact_parts;
+pub use dedupe::{find_near_duplicate_clusters, find_near_duplicates_to_remove};
+pub use error::{IndexError, Result};
+pub use gpu::{default_scorer, BruteForceScorer, CpuScorer};
+pub use ivf::{nprobe_for_recall, IvfBuildOptions, IvfIndex, ResidentPrecision};
+pub use part_builder::{build_part, PartMeta};
+#[cfg(feature = "mmap")]
+pub use part_cache::PartAssetCache;
+pub use part_reader::{part_asset_bytes, read_part_assets};
+#[cfg(feature = "mmap")]
+pub use part_reader::PartMmapReader;
+pub use precision::VectorPrecision;
+pub use projection::Projection;
+pub use row::Row;
+pub use search::{score, search_namespace, search_namespace_with_options, ScoredRow, SearchMode, SearchOptions};
+#[cfg(feature = "wasm")]
+pub use wasm_query::search_bundle;
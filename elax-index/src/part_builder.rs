@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::Path;
+
+use crate::precision::VectorPrecision;
+use crate::row::Row;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Sidecar metadata describing a part's row count, dimensionality, storage
+/// precision and the int8 quantization scale used for its codes page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartMeta {
+    pub ids: Vec<String>,
+    pub dim: usize,
+    pub int8_scale: f32,
+    #[serde(default)]
+    pub precision: VectorPrecision,
+}
+
+impl PartMeta {
+    pub fn count(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+pub(crate) fn fp32_path(dir: &Path, part_name: &str) -> std::path::PathBuf {
+    dir.join(format!("{part_name}.fp32"))
+}
+
+pub(crate) fn int8_path(dir: &Path, part_name: &str) -> std::path::PathBuf {
+    dir.join(format!("{part_name}.int8"))
+}
+
+pub(crate) fn rabitq_path(dir: &Path, part_name: &str) -> std::path::PathBuf {
+    dir.join(format!("{part_name}.rabitq"))
+}
+
+pub(crate) fn meta_path(dir: &Path, part_name: &str) -> std::path::PathBuf {
+    dir.join(format!("{part_name}.meta.json"))
+}
+
+/// Bits-per-dimension packing for a RaBitQ-style binary code: one sign bit
+/// per dimension, MSB-first within each byte.
+pub(crate) fn pack_rabitq(vector: &[f32]) -> Vec<u8> {
+    let mut packed = vec![0u8; vector.len().div_ceil(8)];
+    for (i, &v) in vector.iter().enumerate() {
+        if v >= 0.0 {
+            packed[i / 8] |= 1 << (7 - (i % 8));
+        }
+    }
+    packed
+}
+
+/// Write the fp32 (or f16/bf16), int8 and rabitq pages plus metadata for
+/// one immutable part, given the rows that belong to it.
+pub fn build_part(
+    dir: &Path,
+    part_name: &str,
+    docs: &[Row],
+    precision: VectorPrecision,
+) -> Result<PartMeta> {
+    fs::create_dir_all(dir)?;
+
+    let dim = docs.first().map(|d| d.vector.len()).unwrap_or(0);
+    let max_abs = docs
+        .iter()
+        .flat_map(|d| d.vector.iter())
+        .fold(0.0_f32, |acc, v| acc.max(v.abs()))
+        .max(f32::EPSILON);
+    let int8_scale = max_abs / i8::MAX as f32;
+
+    let mut fp32_bytes = Vec::with_capacity(docs.len() * dim * precision.bytes_per_element());
+    let mut int8_bytes = Vec::with_capacity(docs.len() * dim);
+    let mut rabitq_bytes = Vec::new();
+    let mut ids = Vec::with_capacity(docs.len());
+
+    for doc in docs {
+        ids.push(doc.id.clone());
+        for &v in &doc.vector {
+            fp32_bytes.extend_from_slice(&precision.encode(v));
+            let q = (v / int8_scale).round().clamp(i8::MIN as f32, i8::MAX as f32) as i8;
+            int8_bytes.push(q as u8);
+        }
+        rabitq_bytes.extend_from_slice(&pack_rabitq(&doc.vector));
+    }
+
+    fs::write(fp32_path(dir, part_name), fp32_bytes)?;
+    fs::write(int8_path(dir, part_name), int8_bytes)?;
+    fs::write(rabitq_path(dir, part_name), rabitq_bytes)?;
+
+    let meta = PartMeta {
+        ids,
+        dim,
+        int8_scale,
+        precision,
+    };
+    fs::write(meta_path(dir, part_name), serde_json::to_vec(&meta)?)?;
+
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_dir() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-index-build-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn build_part_writes_expected_pages() {
+        let dir = tmp_dir();
+        let docs = vec![
+            Row::new("a", vec![1.0, -1.0, 0.5]),
+            Row::new("b", vec![0.0, 2.0, -2.0]),
+        ];
+        let meta = build_part(&dir, "part-0", &docs, VectorPrecision::F32).unwrap();
+        assert_eq!(meta.count(), 2);
+        assert_eq!(meta.dim, 3);
+        assert!(fp32_path(&dir, "part-0").exists());
+        assert!(int8_path(&dir, "part-0").exists());
+        assert!(rabitq_path(&dir, "part-0").exists());
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::part_reader::PartMmapReader;
+use crate::Result;
+
+/// Caches opened [`PartMmapReader`]s across queries, keyed by their
+/// `(dir, part_name)` path, so steady-state querying doesn't re-open and
+/// re-mmap a part's fp32/int8/rabitq pages and metadata on every request —
+/// only the first query against a part pays that cost.
+///
+/// Invalidation is epoch-driven rather than per-entry: a namespace's parts
+/// are immutable once published, but a new manifest epoch can drop or
+/// replace which parts are live, so [`Self::invalidate_if_epoch_changed`]
+/// clears everything the moment the caller observes the epoch move.
+pub struct PartAssetCache {
+    epoch: Mutex<u64>,
+    readers: Mutex<HashMap<PathBuf, Arc<PartMmapReader>>>,
+}
+
+impl PartAssetCache {
+    pub fn new() -> Self {
+        Self {
+            epoch: Mutex::new(0),
+            readers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the cached reader for `part_name` under `dir`, opening (and
+    /// caching) it on a miss.
+    pub fn get_or_open(&self, dir: &Path, part_name: &str) -> Result<Arc<PartMmapReader>> {
+        let key = dir.join(part_name);
+        if let Some(reader) = self.readers.lock().unwrap().get(&key) {
+            return Ok(reader.clone());
+        }
+
+        let reader = Arc::new(PartMmapReader::open(dir, part_name)?);
+        self.readers.lock().unwrap().insert(key, reader.clone());
+        Ok(reader)
+    }
+
+    /// Drop every cached reader if `epoch` differs from the last epoch this
+    /// cache observed; a no-op otherwise. Callers drive this once per
+    /// manifest read, so a quiet namespace costs nothing beyond the
+    /// comparison.
+    pub fn invalidate_if_epoch_changed(&self, epoch: u64) {
+        let mut current = self.epoch.lock().unwrap();
+        if *current != epoch {
+            *current = epoch;
+            self.readers.lock().unwrap().clear();
+        }
+    }
+}
+
+impl Default for PartAssetCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_builder::build_part;
+    use crate::row::Row;
+
+    fn tmp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-index-part-cache-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn a_cached_reader_survives_the_files_it_was_opened_from_disappearing() {
+        let dir = tmp_dir();
+        build_part(&dir, "part-0", &[Row::new("a", vec![1.0, 0.0])], crate::precision::VectorPrecision::F32).unwrap();
+
+        let cache = PartAssetCache::new();
+        cache.get_or_open(&dir, "part-0").unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Served from cache, so the missing files on disk don't matter.
+        let reader = cache.get_or_open(&dir, "part-0").unwrap();
+        assert_eq!(reader.vector(0).unwrap(), vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn an_epoch_change_forces_a_fresh_open() {
+        let dir = tmp_dir();
+        build_part(&dir, "part-0", &[Row::new("a", vec![1.0, 0.0])], crate::precision::VectorPrecision::F32).unwrap();
+
+        let cache = PartAssetCache::new();
+        cache.invalidate_if_epoch_changed(1);
+        cache.get_or_open(&dir, "part-0").unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        cache.invalidate_if_epoch_changed(2);
+        assert!(cache.get_or_open(&dir, "part-0").is_err());
+    }
+}
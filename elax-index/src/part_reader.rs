@@ -0,0 +1,176 @@
+use std::fs;
+use std::path::Path;
+
+use crate::row::Row;
+
+use crate::error::Result;
+use crate::part_builder::{fp32_path, int8_path, meta_path, rabitq_path, PartMeta};
+
+fn load_meta(dir: &Path, part_name: &str) -> Result<PartMeta> {
+    let bytes = fs::read(meta_path(dir, part_name))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Total on-disk size, in bytes, of every asset file `part_name` has
+/// written (meta, fp32, and whichever of int8/RaBitQ the build produced) —
+/// for capacity reporting without a caller having to know the part file
+/// layout. Any asset file that does not exist is skipped rather than
+/// erroring, so a part with no files on disk counts as 0.
+pub fn part_asset_bytes(dir: &Path, part_name: &str) -> Result<u64> {
+    let mut total = 0u64;
+    for path in [
+        meta_path(dir, part_name),
+        fp32_path(dir, part_name),
+        int8_path(dir, part_name),
+        rabitq_path(dir, part_name),
+    ] {
+        match fs::metadata(&path) {
+            Ok(metadata) => total += metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(total)
+}
+
+/// Decode an entire part's fp32 page into `Row`s. Simple and correct,
+/// but pulls the whole part into the heap even when only a handful of rows
+/// end up being scored — prefer [`PartMmapReader`] on the query path.
+pub fn read_part_assets(dir: &Path, part_name: &str) -> Result<Vec<Row>> {
+    let meta = load_meta(dir, part_name)?;
+    let fp32_bytes = fs::read(fp32_path(dir, part_name))?;
+
+    let elem_size = meta.precision.bytes_per_element();
+    let mut docs = Vec::with_capacity(meta.count());
+    for (row, id) in meta.ids.iter().enumerate() {
+        let start = row * meta.dim * elem_size;
+        let end = start + meta.dim * elem_size;
+        let vector = fp32_bytes[start..end]
+            .chunks_exact(elem_size)
+            .map(|c| meta.precision.decode(c))
+            .collect();
+        docs.push(Row::new(id.clone(), vector));
+    }
+    Ok(docs)
+}
+
+/// mmap-backed view over a part's pages. Reading a single row only touches
+/// the page(s) of the file that back it, so scoring candidates never
+/// requires deserializing (or even page-faulting in) the rest of the part.
+/// Gated behind the `mmap` feature (on by default) since `memmap2` has no
+/// wasm32 implementation — [`crate::wasm_query`] is the `wasm`-feature
+/// equivalent for a part whose bytes are already in memory.
+#[cfg(feature = "mmap")]
+pub struct PartMmapReader {
+    meta: PartMeta,
+    fp32: memmap2::Mmap,
+    int8: memmap2::Mmap,
+    rabitq: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl PartMmapReader {
+    pub fn open(dir: &Path, part_name: &str) -> Result<Self> {
+        let meta = load_meta(dir, part_name)?;
+        let fp32 = unsafe { memmap2::Mmap::map(&fs::File::open(fp32_path(dir, part_name))?)? };
+        let int8 = unsafe { memmap2::Mmap::map(&fs::File::open(int8_path(dir, part_name))?)? };
+        let rabitq = unsafe { memmap2::Mmap::map(&fs::File::open(rabitq_path(dir, part_name))?)? };
+        Ok(Self {
+            meta,
+            fp32,
+            int8,
+            rabitq,
+        })
+    }
+
+    pub fn meta(&self) -> &PartMeta {
+        &self.meta
+    }
+
+    fn check_row(&self, row: usize) -> Result<()> {
+        if row >= self.meta.count() {
+            return Err(crate::error::IndexError::RowOutOfRange {
+                row,
+                count: self.meta.count(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Decode just row `row`'s fp32 vector from the page cache.
+    pub fn vector(&self, row: usize) -> Result<Vec<f32>> {
+        self.check_row(row)?;
+        let elem_size = self.meta.precision.bytes_per_element();
+        let start = row * self.meta.dim * elem_size;
+        let end = start + self.meta.dim * elem_size;
+        Ok(self.fp32[start..end]
+            .chunks_exact(elem_size)
+            .map(|c| self.meta.precision.decode(c))
+            .collect())
+    }
+
+    /// Raw int8 codes for row `row` (scale in `meta().int8_scale`).
+    pub fn int8_codes(&self, row: usize) -> Result<&[i8]> {
+        self.check_row(row)?;
+        let start = row * self.meta.dim;
+        let end = start + self.meta.dim;
+        // SAFETY: u8 and i8 have the same size and alignment; this is a
+        // transparent reinterpretation of quantized code bytes.
+        let bytes = &self.int8[start..end];
+        Ok(unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const i8, bytes.len()) })
+    }
+
+    /// Packed RaBitQ sign-bit code for row `row`.
+    pub fn rabitq_code(&self, row: usize) -> Result<&[u8]> {
+        self.check_row(row)?;
+        let bytes_per_row = self.meta.dim.div_ceil(8);
+        let start = row * bytes_per_row;
+        Ok(&self.rabitq[start..start + bytes_per_row])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_builder::build_part;
+
+    fn tmp_dir() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-index-read-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn mmap_reader_matches_full_decode() {
+        let dir = tmp_dir();
+        let docs = vec![
+            Row::new("a", vec![1.0, -1.0, 0.5]),
+            Row::new("b", vec![0.25, 2.0, -2.0]),
+        ];
+        build_part(&dir, "part-0", &docs, crate::precision::VectorPrecision::F32).unwrap();
+
+        let decoded = read_part_assets(&dir, "part-0").unwrap();
+        let reader = PartMmapReader::open(&dir, "part-0").unwrap();
+
+        for (row, doc) in decoded.iter().enumerate() {
+            assert_eq!(reader.vector(row).unwrap(), doc.vector);
+        }
+        assert!(reader.vector(2).is_err());
+    }
+
+    #[test]
+    fn part_asset_bytes_is_nonzero_for_a_built_part_and_zero_for_a_missing_one() {
+        let dir = tmp_dir();
+        let docs = vec![Row::new("a", vec![1.0, -1.0, 0.5])];
+        build_part(&dir, "part-0", &docs, crate::precision::VectorPrecision::F32).unwrap();
+
+        assert!(part_asset_bytes(&dir, "part-0").unwrap() > 0);
+        assert_eq!(part_asset_bytes(&dir, "missing-part").unwrap(), 0);
+    }
+}
@@ -0,0 +1,50 @@
+use half::{bf16, f16};
+use serde::{Deserialize, Serialize};
+
+/// On-disk (and in some cases in-memory) storage precision for a
+/// namespace's vectors. Lower precision halves the fp32 page's footprint
+/// at the cost of converting back to f32 before scoring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum VectorPrecision {
+    #[default]
+    F32,
+    F16,
+    Bf16,
+}
+
+impl VectorPrecision {
+    pub fn bytes_per_element(&self) -> usize {
+        match self {
+            VectorPrecision::F32 => 4,
+            VectorPrecision::F16 | VectorPrecision::Bf16 => 2,
+        }
+    }
+
+    pub fn encode(&self, value: f32) -> Vec<u8> {
+        match self {
+            VectorPrecision::F32 => value.to_le_bytes().to_vec(),
+            VectorPrecision::F16 => f16::from_f32(value).to_le_bytes().to_vec(),
+            VectorPrecision::Bf16 => bf16::from_f32(value).to_le_bytes().to_vec(),
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> f32 {
+        match self {
+            VectorPrecision::F32 => f32::from_le_bytes(bytes.try_into().unwrap()),
+            VectorPrecision::F16 => f16::from_le_bytes(bytes.try_into().unwrap()).to_f32(),
+            VectorPrecision::Bf16 => bf16::from_le_bytes(bytes.try_into().unwrap()).to_f32(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f16_round_trip_is_lossy_but_close() {
+        let encoded = VectorPrecision::F16.encode(1.0 / 3.0);
+        let decoded = VectorPrecision::F16.decode(&encoded);
+        assert!((decoded - 1.0 / 3.0).abs() < 1e-3);
+    }
+}
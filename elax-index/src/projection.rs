@@ -0,0 +1,94 @@
+//! Optional dimensionality reduction for the IVF coarse scan. Vectors are
+//! projected to a smaller working dimension for centroid/list scoring, while
+//! the full fp32 vector (see [`crate::part_builder`]) is always kept around
+//! for the final rerank.
+
+use crate::row::Row;
+
+/// A trained random projection matrix, its entries drawn uniformly from
+/// `[-1, 1]`. Cheaper to train than PCA
+/// and, by the Johnson-Lindenstrauss guarantee, preserves pairwise distances
+/// well enough for a coarse scan — a fitted PCA basis is a possible future
+/// upgrade but isn't needed for the coarse stage.
+#[derive(Debug, Clone)]
+pub struct Projection {
+    input_dim: usize,
+    output_dim: usize,
+    matrix: Vec<Vec<f32>>,
+}
+
+/// A tiny deterministic PRNG so training is reproducible without pulling in
+/// a `rand` dependency.
+struct Xorshift64(u64);
+
+impl Xorshift64 {
+    fn next_f32(&mut self) -> f32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        // Map to a uniform value in [-1, 1].
+        ((x % 2_000_001) as f32 / 1_000_000.0) - 1.0
+    }
+}
+
+impl Projection {
+    /// Train a random projection matrix from `input_dim` down to
+    /// `output_dim`, seeded deterministically so retraining on the same
+    /// namespace data is reproducible.
+    pub fn train(input_dim: usize, output_dim: usize, seed: u64) -> Self {
+        let mut rng = Xorshift64(seed | 1);
+        let matrix = (0..output_dim)
+            .map(|_| (0..input_dim).map(|_| rng.next_f32()).collect())
+            .collect();
+        Self {
+            input_dim,
+            output_dim,
+            matrix,
+        }
+    }
+
+    pub fn input_dim(&self) -> usize {
+        self.input_dim
+    }
+
+    pub fn output_dim(&self) -> usize {
+        self.output_dim
+    }
+
+    /// Project a full-dimension vector down to `output_dim` for the coarse
+    /// scan. Panics if `vector.len() != input_dim`, matching the rest of the
+    /// crate's assumption that callers keep dimensions consistent per part.
+    pub fn project(&self, vector: &[f32]) -> Vec<f32> {
+        assert_eq!(vector.len(), self.input_dim);
+        self.matrix
+            .iter()
+            .map(|row| row.iter().zip(vector).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    pub fn project_row(&self, row: &Row) -> Vec<f32> {
+        self.project(&row.vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projection_reduces_dimension() {
+        let projection = Projection::train(8, 2, 42);
+        let reduced = projection.project(&[1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+        assert_eq!(reduced.len(), 2);
+    }
+
+    #[test]
+    fn same_seed_trains_identical_matrix() {
+        let a = Projection::train(4, 2, 7);
+        let b = Projection::train(4, 2, 7);
+        let v = [1.0, -1.0, 0.5, 0.25];
+        assert_eq!(a.project(&v), b.project(&v));
+    }
+}
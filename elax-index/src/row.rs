@@ -0,0 +1,17 @@
+/// A single id+vector pair as seen by the part format. Deliberately
+/// decoupled from `elax_core::Document` so this crate has no dependency on
+/// the namespace/runtime layer — callers convert at the boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub id: String,
+    pub vector: Vec<f32>,
+}
+
+impl Row {
+    pub fn new(id: impl Into<String>, vector: Vec<f32>) -> Self {
+        Self {
+            id: id.into(),
+            vector,
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::path::Path;
+use std::thread;
+
+use crate::part_reader::read_part_assets;
+use crate::row::Row;
+use crate::Result;
+
+/// A candidate row plus its similarity score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredRow {
+    pub id: String,
+    pub score: f32,
+}
+
+/// How [`search_namespace_with_options`] accumulates candidates before
+/// truncating to `top_k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Collect every candidate, sort once, then truncate — one allocation
+    /// and one `O(n log n)` sort over the whole candidate set. Cheapest
+    /// when `top_k` is small relative to the candidate count, which is the
+    /// common case; the wrong choice once `top_k` itself is large enough
+    /// that the sort dominates.
+    #[default]
+    Collect,
+    /// Keep a bounded min-heap of the best `top_k` candidates seen so far,
+    /// evicting the worst whenever a better one arrives — `O(n log k)`
+    /// instead of `O(n log n)`, and never holds more than `top_k` rows at
+    /// once. Worth the per-row heap overhead once `top_k` is large. Still
+    /// entirely in-memory — there is no spill-to-disk heap in this crate.
+    Streamed,
+}
+
+/// Tuning knobs for [`search_namespace_with_options`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// How many parts to score concurrently. `1` (the default) scores parts
+    /// one at a time, matching [`search_namespace`]'s behavior.
+    pub max_parallelism: usize,
+    /// How candidates are accumulated before truncating to `top_k`. See
+    /// [`SearchMode`].
+    pub mode: SearchMode,
+}
+
+impl SearchOptions {
+    fn effective_parallelism(&self) -> usize {
+        self.max_parallelism.max(1)
+    }
+}
+
+/// Dot-product similarity between a query and a candidate vector.
+pub fn score(query: &[f32], vector: &[f32]) -> f32 {
+    query.iter().zip(vector).map(|(a, b)| a * b).sum()
+}
+
+fn score_part(query: &[f32], dir: &Path, part_name: &str) -> Result<Vec<ScoredRow>> {
+    let rows = read_part_assets(dir, part_name)?;
+    Ok(rows
+        .iter()
+        .map(|row| ScoredRow {
+            id: row.id.clone(),
+            score: score(query, &row.vector),
+        })
+        .collect())
+}
+
+/// LSM-style search over a namespace's tiered storage: score the in-memory
+/// memtable (the WAL tail) and every on-disk part, then merge into a single
+/// top-k ranking. Historical data never has to live in the memtable.
+pub fn search_namespace(
+    query: &[f32],
+    top_k: usize,
+    memtable: &[Row],
+    dir: &Path,
+    part_names: &[String],
+) -> Result<Vec<ScoredRow>> {
+    search_namespace_with_options(query, top_k, memtable, dir, part_names, &SearchOptions::default())
+}
+
+/// [`search_namespace`], but scoring up to `options.max_parallelism` parts
+/// at once on scoped threads, and accumulating candidates under
+/// `options.mode`. Each part is read and scored independently, so there's
+/// no shared state to synchronize beyond collecting the per-part candidate
+/// lists to merge; the threads themselves never outlive this call.
+pub fn search_namespace_with_options(
+    query: &[f32],
+    top_k: usize,
+    memtable: &[Row],
+    dir: &Path,
+    part_names: &[String],
+    options: &SearchOptions,
+) -> Result<Vec<ScoredRow>> {
+    match options.mode {
+        SearchMode::Collect => search_collect(query, top_k, memtable, dir, part_names, options),
+        SearchMode::Streamed => search_streamed(query, top_k, memtable, dir, part_names, options),
+    }
+}
+
+fn search_collect(
+    query: &[f32],
+    top_k: usize,
+    memtable: &[Row],
+    dir: &Path,
+    part_names: &[String],
+    options: &SearchOptions,
+) -> Result<Vec<ScoredRow>> {
+    let mut candidates: Vec<ScoredRow> = memtable
+        .iter()
+        .map(|row| ScoredRow {
+            id: row.id.clone(),
+            score: score(query, &row.vector),
+        })
+        .collect();
+
+    let parallelism = options.effective_parallelism();
+    for chunk in part_names.chunks(parallelism) {
+        let chunk_results: Vec<Result<Vec<ScoredRow>>> = thread::scope(|scope| {
+            chunk
+                .iter()
+                .map(|part_name| scope.spawn(|| score_part(query, dir, part_name)))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().expect("part-search thread panicked"))
+                .collect()
+        });
+        for result in chunk_results {
+            candidates.extend(result?);
+        }
+    }
+
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(top_k);
+    Ok(candidates)
+}
+
+/// A candidate wrapper whose [`Ord`] is reversed by score, so a
+/// [`BinaryHeap`] (a max-heap) of these keeps the *worst* scored candidate
+/// on top — the one [`search_streamed`] needs to evict first.
+struct HeapItem(ScoredRow);
+
+impl PartialEq for HeapItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.score == other.0.score
+    }
+}
+
+impl Eq for HeapItem {}
+
+impl PartialOrd for HeapItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.score.total_cmp(&self.0.score)
+    }
+}
+
+/// [`search_collect`], but keeping only a bounded `top_k`-sized min-heap of
+/// candidates instead of collecting and sorting all of them — see
+/// [`SearchMode::Streamed`].
+fn search_streamed(
+    query: &[f32],
+    top_k: usize,
+    memtable: &[Row],
+    dir: &Path,
+    part_names: &[String],
+    options: &SearchOptions,
+) -> Result<Vec<ScoredRow>> {
+    let mut heap: BinaryHeap<HeapItem> = BinaryHeap::with_capacity(top_k.saturating_add(1));
+    let mut offer = |row: ScoredRow| {
+        if top_k == 0 {
+            return;
+        }
+        if heap.len() < top_k {
+            heap.push(HeapItem(row));
+        } else if heap.peek().is_some_and(|worst| row.score > worst.0.score) {
+            heap.pop();
+            heap.push(HeapItem(row));
+        }
+    };
+
+    for row in memtable {
+        offer(ScoredRow { id: row.id.clone(), score: score(query, &row.vector) });
+    }
+
+    let parallelism = options.effective_parallelism();
+    for chunk in part_names.chunks(parallelism) {
+        let chunk_results: Vec<Result<Vec<ScoredRow>>> = thread::scope(|scope| {
+            chunk
+                .iter()
+                .map(|part_name| scope.spawn(|| score_part(query, dir, part_name)))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().expect("part-search thread panicked"))
+                .collect()
+        });
+        for result in chunk_results {
+            for row in result? {
+                offer(row);
+            }
+        }
+    }
+
+    let mut rows: Vec<ScoredRow> = heap.into_iter().map(|item| item.0).collect();
+    rows.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_builder::build_part;
+
+    fn tmp_dir() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-index-search-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn merges_memtable_and_part_results() {
+        let dir = tmp_dir();
+        build_part(
+            &dir,
+            "part-0",
+            &[Row::new("historical", vec![1.0, 0.0])],
+            crate::precision::VectorPrecision::F32,
+        )
+        .unwrap();
+
+        let memtable = vec![Row::new("fresh", vec![0.0, 1.0])];
+        let results = search_namespace(
+            &[1.0, 0.0],
+            10,
+            &memtable,
+            &dir,
+            &["part-0".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "historical");
+    }
+
+    #[test]
+    fn respects_top_k() {
+        let memtable = vec![
+            Row::new("a", vec![1.0]),
+            Row::new("b", vec![2.0]),
+            Row::new("c", vec![3.0]),
+        ];
+        let results = search_namespace(&[1.0], 2, &memtable, Path::new("."), &[]).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "c");
+    }
+
+    #[test]
+    fn parallel_search_merges_the_same_results_as_sequential_search() {
+        let dir = tmp_dir();
+        build_part(
+            &dir,
+            "part-0",
+            &[Row::new("a", vec![1.0, 0.0])],
+            crate::precision::VectorPrecision::F32,
+        )
+        .unwrap();
+        build_part(
+            &dir,
+            "part-1",
+            &[Row::new("b", vec![0.9, 0.1])],
+            crate::precision::VectorPrecision::F32,
+        )
+        .unwrap();
+        build_part(
+            &dir,
+            "part-2",
+            &[Row::new("c", vec![0.0, 1.0])],
+            crate::precision::VectorPrecision::F32,
+        )
+        .unwrap();
+        let parts = vec!["part-0".to_string(), "part-1".to_string(), "part-2".to_string()];
+
+        let sequential = search_namespace(&[1.0, 0.0], 3, &[], &dir, &parts).unwrap();
+        let parallel = search_namespace_with_options(
+            &[1.0, 0.0],
+            3,
+            &[],
+            &dir,
+            &parts,
+            &SearchOptions { max_parallelism: 4, ..Default::default() },
+        )
+        .unwrap();
+
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn streamed_mode_matches_collect_mode() {
+        let memtable = vec![
+            Row::new("a", vec![1.0]),
+            Row::new("b", vec![2.0]),
+            Row::new("c", vec![3.0]),
+            Row::new("d", vec![0.5]),
+        ];
+        let collect = search_namespace_with_options(
+            &[1.0],
+            2,
+            &memtable,
+            Path::new("."),
+            &[],
+            &SearchOptions { mode: SearchMode::Collect, ..Default::default() },
+        )
+        .unwrap();
+        let streamed = search_namespace_with_options(
+            &[1.0],
+            2,
+            &memtable,
+            Path::new("."),
+            &[],
+            &SearchOptions { mode: SearchMode::Streamed, ..Default::default() },
+        )
+        .unwrap();
+
+        assert_eq!(collect, streamed);
+        assert_eq!(streamed[0].id, "c");
+        assert_eq!(streamed[1].id, "b");
+    }
+
+    #[test]
+    fn streamed_mode_with_top_k_zero_returns_nothing() {
+        let memtable = vec![Row::new("a", vec![1.0])];
+        let results = search_namespace_with_options(
+            &[1.0],
+            0,
+            &memtable,
+            Path::new("."),
+            &[],
+            &SearchOptions { mode: SearchMode::Streamed, ..Default::default() },
+        )
+        .unwrap();
+        assert!(results.is_empty());
+    }
+}
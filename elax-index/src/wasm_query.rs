@@ -0,0 +1,85 @@
+//! Brute-force scoring over a part whose bytes are already resident in
+//! memory — e.g. fetched by a browser over HTTP rather than read off
+//! disk. The desktop/server query path ([`crate::part_reader`],
+//! [`crate::search::search_namespace`]) goes through `std::fs` and, for
+//! [`crate::part_reader::PartMmapReader`], `memmap2`; neither compiles to
+//! `wasm32-unknown-unknown`. This module itself has no such dependency,
+//! so `--no-default-features --features wasm` drops the `memmap2`
+//! blocker from this crate's own source. That does not yet prove a
+//! clean `wasm32-unknown-unknown` build end to end: `elax-index` also
+//! depends unconditionally on `elax-store` (`calibration.rs`,
+//! `error::IndexError`'s `Store` variant), which in turn depends on
+//! `arrow`/`aes-gcm`/`getrandom` — none confirmed wasm32-compatible.
+//! Getting a real client-only build will also mean making `elax-store`
+//! optional for this crate the same way `mmap` makes `memmap2` optional.
+
+use crate::part_builder::PartMeta;
+use crate::search::{score, ScoredRow};
+
+/// Brute-force top-`top_k` search over `meta`/`fp32_bytes` — the same
+/// fp32 page [`crate::part_reader::read_part_assets`] decodes, just
+/// already in a caller-supplied buffer instead of read from a file. No
+/// I/O and no threads: every row is scored sequentially on the calling
+/// thread, which is the only thread a browser's wasm runtime gives this
+/// code by default.
+pub fn search_bundle(meta: &PartMeta, fp32_bytes: &[u8], query: &[f32], top_k: usize) -> Vec<ScoredRow> {
+    let elem_size = meta.precision.bytes_per_element();
+    let mut candidates: Vec<ScoredRow> = meta
+        .ids
+        .iter()
+        .enumerate()
+        .map(|(row, id)| {
+            let start = row * meta.dim * elem_size;
+            let end = start + meta.dim * elem_size;
+            let vector: Vec<f32> = fp32_bytes[start..end]
+                .chunks_exact(elem_size)
+                .map(|c| meta.precision.decode(c))
+                .collect();
+            ScoredRow { id: id.clone(), score: score(query, &vector) }
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(top_k);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precision::VectorPrecision;
+
+    fn bundle(vectors: &[(&str, Vec<f32>)]) -> (PartMeta, Vec<u8>) {
+        let dim = vectors.first().map(|(_, v)| v.len()).unwrap_or(0);
+        let mut fp32_bytes = Vec::new();
+        let mut ids = Vec::new();
+        for (id, vector) in vectors {
+            ids.push(id.to_string());
+            for &v in vector {
+                fp32_bytes.extend_from_slice(&VectorPrecision::F32.encode(v));
+            }
+        }
+        (PartMeta { ids, dim, int8_scale: 1.0, precision: VectorPrecision::F32 }, fp32_bytes)
+    }
+
+    #[test]
+    fn search_bundle_ranks_the_closest_row_first() {
+        let (meta, fp32_bytes) = bundle(&[
+            ("a", vec![1.0, 0.0]),
+            ("b", vec![0.0, 1.0]),
+            ("c", vec![0.9, 0.1]),
+        ]);
+
+        let results = search_bundle(&meta, &fp32_bytes, &[1.0, 0.0], 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "a");
+        assert_eq!(results[1].id, "c");
+    }
+
+    #[test]
+    fn search_bundle_respects_top_k() {
+        let (meta, fp32_bytes) = bundle(&[("a", vec![1.0]), ("b", vec![2.0]), ("c", vec![3.0])]);
+        let results = search_bundle(&meta, &fp32_bytes, &[1.0], 1);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "c");
+    }
+}
@@ -0,0 +1,112 @@
+//! Streaming ingestion from Kafka/Redpanda into a namespace's write path.
+//! Gated behind the `kafka` feature, since it pulls in `rdkafka` (and the
+//! native librdkafka it links against) only for deployments that actually
+//! run a connector — every other consumer of this crate pays nothing.
+
+use elax_core::Document;
+
+/// Decodes one Kafka record into a namespace write. Implementations decide
+/// how a message's key/payload map to a [`Document`] — e.g. the payload is
+/// the `attributes` JSON and the key becomes `doc.id`.
+pub trait RecordDecoder: Send + Sync {
+    fn decode(&self, key: Option<&[u8]>, payload: &[u8]) -> Option<Document>;
+}
+
+/// A [`RecordDecoder`] for messages whose payload is already a JSON object
+/// shaped like `{"id": ..., "vector": [...], "attributes": {...}}` — the
+/// same shape [`Document`] serializes to.
+#[derive(Debug, Clone, Default)]
+pub struct JsonDocumentDecoder;
+
+impl RecordDecoder for JsonDocumentDecoder {
+    fn decode(&self, _key: Option<&[u8]>, payload: &[u8]) -> Option<Document> {
+        serde_json::from_slice(payload).ok()
+    }
+}
+
+#[cfg(feature = "kafka")]
+pub mod kafka {
+    //! The `rdkafka`-backed connector. Only compiled when the `kafka`
+    //! feature is enabled.
+
+    use rdkafka::config::ClientConfig;
+    use rdkafka::consumer::{BaseConsumer, Consumer};
+    use rdkafka::error::KafkaResult;
+    use rdkafka::message::Message;
+
+    use super::RecordDecoder;
+    use elax_core::{NamespaceRegistry, Result};
+
+    /// A consumer bound to one topic, pulling records into a
+    /// [`NamespaceRegistry`] write via a [`RecordDecoder`].
+    pub struct KafkaConnector {
+        consumer: BaseConsumer,
+        namespace: String,
+    }
+
+    impl KafkaConnector {
+        pub fn new(brokers: &str, group_id: &str, topic: &str, namespace: impl Into<String>) -> KafkaResult<Self> {
+            let consumer: BaseConsumer = ClientConfig::new()
+                .set("bootstrap.servers", brokers)
+                .set("group.id", group_id)
+                .set("enable.auto.commit", "true")
+                .create()?;
+            consumer.subscribe(&[topic])?;
+            Ok(Self {
+                consumer,
+                namespace: namespace.into(),
+            })
+        }
+
+        /// Poll for up to `max_records` available messages (non-blocking
+        /// past the first empty poll), decoding and applying each to
+        /// `registry`. Returns how many records were actually applied —
+        /// a record the decoder can't parse is skipped, not an error, so
+        /// one malformed message doesn't stall the whole batch.
+        pub fn poll_batch(
+            &self,
+            registry: &mut NamespaceRegistry,
+            decoder: &dyn RecordDecoder,
+            max_records: usize,
+        ) -> Result<usize> {
+            let mut applied = 0;
+            for _ in 0..max_records {
+                let message = match self.consumer.poll(std::time::Duration::from_millis(0)) {
+                    Some(Ok(message)) => message,
+                    Some(Err(_)) | None => break,
+                };
+                let Some(payload) = message.payload() else { continue };
+                let Some(doc) = decoder.decode(message.key(), payload) else { continue };
+                registry.apply_write(&self.namespace, doc)?;
+                applied += 1;
+            }
+            Ok(applied)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_document_decoder_parses_a_well_formed_record() {
+        let decoder = JsonDocumentDecoder;
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "id": "a",
+            "vector": [1.0, 0.0],
+            "attributes": {"source": "kafka"}
+        }))
+        .unwrap();
+
+        let doc = decoder.decode(None, &payload).unwrap();
+        assert_eq!(doc.id, "a");
+        assert_eq!(doc.vector, vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn json_document_decoder_skips_malformed_payloads() {
+        let decoder = JsonDocumentDecoder;
+        assert!(decoder.decode(None, b"not json").is_none());
+    }
+}
@@ -0,0 +1,229 @@
+//! Optional AES-256-GCM encryption at rest for values written through an
+//! [`ObjectStore`]. Layered as a decorator ([`EncryptingStore`]) rather
+//! than baked into [`crate::local::LocalStore`] or a future S3 backend, so
+//! a deployment opts in per store instance and the backend itself stays
+//! oblivious to whether the bytes it's persisting are plaintext or
+//! ciphertext — the same split local and S3 storage already get for free.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+use crate::error::{Result, StoreError};
+use crate::object_store::{Generation, ObjectStore};
+
+pub const KEY_BYTES: usize = 32;
+const NONCE_BYTES: usize = 12;
+
+/// A raw AES-256-GCM key paired with the id a deployment uses to name it —
+/// a KMS key ARN, a version tag, whatever its [`KeyProvider`] understands.
+#[derive(Clone)]
+pub struct EncryptionKey {
+    pub id: String,
+    bytes: [u8; KEY_BYTES],
+}
+
+impl EncryptionKey {
+    pub fn new(id: impl Into<String>, bytes: [u8; KEY_BYTES]) -> Self {
+        Self { id: id.into(), bytes }
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&Key::<Aes256Gcm>::try_from(self.bytes.as_slice()).expect("key is exactly KEY_BYTES long"))
+    }
+}
+
+/// Resolves a key id to key material — the hook a deployment implements
+/// against its own KMS. [`StaticKeyProvider`] is the config-file
+/// equivalent: one fixed key, known up front, for deployments that don't
+/// run a KMS at all.
+pub trait KeyProvider: Send + Sync {
+    /// The key new values should be encrypted under.
+    fn active_key(&self) -> Result<EncryptionKey>;
+    /// The key that encrypted a value tagged with `key_id`, for decryption.
+    fn key(&self, key_id: &str) -> Result<EncryptionKey>;
+}
+
+/// A single statically configured key, supplied directly by config rather
+/// than fetched from a KMS. Every value is encrypted and decrypted under
+/// the same key id; there is no rotation support here beyond swapping the
+/// key and accepting that old values become unreadable.
+pub struct StaticKeyProvider {
+    key: EncryptionKey,
+}
+
+impl StaticKeyProvider {
+    pub fn new(key: EncryptionKey) -> Self {
+        Self { key }
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn active_key(&self) -> Result<EncryptionKey> {
+        Ok(self.key.clone())
+    }
+
+    fn key(&self, key_id: &str) -> Result<EncryptionKey> {
+        if key_id == self.key.id {
+            Ok(self.key.clone())
+        } else {
+            Err(StoreError::UnknownEncryptionKey(key_id.to_string()))
+        }
+    }
+}
+
+/// Wraps any [`ObjectStore`] to transparently encrypt values on
+/// `put_if_match` and decrypt them on `get`, resolving keys through
+/// `provider`. Generation/CAS semantics pass straight through to `inner` —
+/// only the value bytes are transformed.
+pub struct EncryptingStore<'a> {
+    inner: &'a dyn ObjectStore,
+    provider: &'a dyn KeyProvider,
+}
+
+impl<'a> EncryptingStore<'a> {
+    pub fn new(inner: &'a dyn ObjectStore, provider: &'a dyn KeyProvider) -> Self {
+        Self { inner, provider }
+    }
+}
+
+impl ObjectStore for EncryptingStore<'_> {
+    fn get(&self, key: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+        match self.inner.get(key)? {
+            Some((envelope, generation)) => Ok(Some((decrypt(&envelope, self.provider)?, generation))),
+            None => Ok(None),
+        }
+    }
+
+    fn put_if_match(&self, key: &str, expected: Option<Generation>, value: Vec<u8>) -> Result<Generation> {
+        let envelope = encrypt(&value, &self.provider.active_key()?);
+        self.inner.put_if_match(key, expected, envelope)
+    }
+}
+
+/// Envelope layout: `[key_id_len: u8][key_id][nonce: 12 bytes][ciphertext]`.
+/// Carrying the key id inline means [`decrypt`] can resolve the right key
+/// from a [`KeyProvider`] without any out-of-band state — a namespace
+/// [manifest's](crate) `key_id` field is a surfaced hint for operators and
+/// rotation tooling, not something decryption itself depends on.
+fn random_nonce() -> [u8; NONCE_BYTES] {
+    let mut nonce = [0u8; NONCE_BYTES];
+    getrandom::fill(&mut nonce).expect("OS RNG unavailable");
+    nonce
+}
+
+fn encrypt(plaintext: &[u8], key: &EncryptionKey) -> Vec<u8> {
+    let nonce_bytes = random_nonce();
+    let ciphertext = key
+        .cipher()
+        .encrypt(
+            &Nonce::try_from(nonce_bytes.as_slice()).expect("nonce is exactly NONCE_BYTES long"),
+            plaintext,
+        )
+        .expect("AES-GCM encryption of an in-memory buffer cannot fail");
+
+    let key_id = key.id.as_bytes();
+    let mut envelope = Vec::with_capacity(1 + key_id.len() + NONCE_BYTES + ciphertext.len());
+    envelope.push(key_id.len() as u8);
+    envelope.extend_from_slice(key_id);
+    envelope.extend_from_slice(&nonce_bytes);
+    envelope.extend_from_slice(&ciphertext);
+    envelope
+}
+
+fn decrypt(envelope: &[u8], provider: &dyn KeyProvider) -> Result<Vec<u8>> {
+    let key_id_len = *envelope.first().ok_or(StoreError::MalformedEnvelope)? as usize;
+    let rest = envelope.get(1..).ok_or(StoreError::MalformedEnvelope)?;
+    let key_id = rest.get(..key_id_len).ok_or(StoreError::MalformedEnvelope)?;
+    let key_id = std::str::from_utf8(key_id).map_err(|_| StoreError::MalformedEnvelope)?;
+    let rest = rest.get(key_id_len..).ok_or(StoreError::MalformedEnvelope)?;
+
+    let nonce_bytes = rest.get(..NONCE_BYTES).ok_or(StoreError::MalformedEnvelope)?;
+    let ciphertext = rest.get(NONCE_BYTES..).ok_or(StoreError::MalformedEnvelope)?;
+
+    let key = provider.key(key_id)?;
+    let nonce = Nonce::try_from(nonce_bytes).map_err(|_| StoreError::MalformedEnvelope)?;
+    key.cipher()
+        .decrypt(&nonce, ciphertext)
+        .map_err(|_| StoreError::DecryptionFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local::LocalStore;
+
+    fn tmp_dir(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "elax-store-crypto-{name}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    fn key(id: &str, fill: u8) -> EncryptionKey {
+        EncryptionKey::new(id, [fill; KEY_BYTES])
+    }
+
+    #[test]
+    fn a_value_round_trips_through_encrypt_and_decrypt() {
+        let store = LocalStore::new(tmp_dir("round-trip")).unwrap();
+        let provider = StaticKeyProvider::new(key("k1", 7));
+        let encrypted = EncryptingStore::new(&store, &provider);
+
+        encrypted.put_if_match("doc.json", None, b"top secret".to_vec()).unwrap();
+        let (plaintext, _) = encrypted.get("doc.json").unwrap().unwrap();
+        assert_eq!(plaintext, b"top secret");
+    }
+
+    #[test]
+    fn the_value_at_rest_is_not_the_plaintext() {
+        let store = LocalStore::new(tmp_dir("at-rest")).unwrap();
+        let provider = StaticKeyProvider::new(key("k1", 7));
+        let encrypted = EncryptingStore::new(&store, &provider);
+
+        encrypted.put_if_match("doc.json", None, b"top secret".to_vec()).unwrap();
+        let (raw, _) = store.get("doc.json").unwrap().unwrap();
+        assert_ne!(raw, b"top secret");
+    }
+
+    #[test]
+    fn decrypting_with_the_wrong_key_id_fails() {
+        let store = LocalStore::new(tmp_dir("wrong-key")).unwrap();
+        let writer_provider = StaticKeyProvider::new(key("k1", 7));
+        EncryptingStore::new(&store, &writer_provider)
+            .put_if_match("doc.json", None, b"top secret".to_vec())
+            .unwrap();
+
+        let reader_provider = StaticKeyProvider::new(key("k2", 9));
+        let err = EncryptingStore::new(&store, &reader_provider).get("doc.json").unwrap_err();
+        assert!(matches!(err, StoreError::UnknownEncryptionKey(id) if id == "k1"));
+    }
+
+    #[test]
+    fn a_tampered_ciphertext_fails_to_decrypt() {
+        let store = LocalStore::new(tmp_dir("tampered")).unwrap();
+        let provider = StaticKeyProvider::new(key("k1", 7));
+        EncryptingStore::new(&store, &provider)
+            .put_if_match("doc.json", None, b"top secret".to_vec())
+            .unwrap();
+
+        let (mut raw, _) = store.get("doc.json").unwrap().unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 0xFF;
+        store.put_if_match("doc.json", Some(1), raw).unwrap();
+
+        let err = EncryptingStore::new(&store, &provider).get("doc.json").unwrap_err();
+        assert!(matches!(err, StoreError::DecryptionFailed));
+    }
+
+    #[test]
+    fn plaintext_read_through_the_unwrapped_store_is_unaffected() {
+        let store = LocalStore::new(tmp_dir("passthrough")).unwrap();
+        store.put_if_match("plain.json", None, b"never encrypted".to_vec()).unwrap();
+        let (plaintext, _) = store.get("plain.json").unwrap().unwrap();
+        assert_eq!(plaintext, b"never encrypted");
+    }
+}
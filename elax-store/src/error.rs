@@ -0,0 +1,39 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("conditional write failed: expected generation {expected:?}, found {found:?}")]
+    GenerationMismatch {
+        expected: Option<u64>,
+        found: Option<u64>,
+    },
+
+    #[error("arrow error: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    #[error("unknown encryption key id: {0}")]
+    UnknownEncryptionKey(String),
+
+    #[error("malformed encryption envelope")]
+    MalformedEnvelope,
+
+    #[error("decryption failed")]
+    DecryptionFailed,
+
+    #[error("unsupported storage backend: {0}")]
+    UnsupportedBackend(String),
+
+    #[error("invalid tenant/namespace path segment: {0:?}")]
+    InvalidPathSegment(String),
+
+    #[error("object store operation timed out")]
+    Timeout,
+}
+
+pub type Result<T> = std::result::Result<T, StoreError>;
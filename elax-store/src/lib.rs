@@ -0,0 +1,21 @@
+//! Storage abstractions shared by the indexer and query paths.
+
+pub mod arrow_ipc;
+pub mod crypto;
+pub mod error;
+pub mod local;
+pub mod multipart;
+pub mod object_store;
+pub mod resilience;
+pub mod storage_config;
+pub mod wal;
+
+pub use arrow_ipc::{decode_ipc, encode_ipc, ResponseRow};
+pub use crypto::{EncryptingStore, EncryptionKey, KeyProvider, StaticKeyProvider};
+pub use error::StoreError;
+pub use local::{LocalStore, NamespaceHandle, TenantHandle};
+pub use multipart::{download_multipart, upload_assets, upload_multipart, Asset, MultipartConfig};
+pub use object_store::{Generation, ObjectStore};
+pub use resilience::{ResilientStore, RetryMetrics};
+pub use storage_config::{build_object_store, Credentials, RetryConfig, StorageBackend, StorageConfig};
+pub use wal::{Durability, FaultInjector, GroupCommitBuffer, WalReader, WalRecord, WalWriter};
@@ -0,0 +1,384 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::{Result, StoreError};
+use crate::object_store::{Generation, ObjectStore};
+
+/// Filesystem-backed [`ObjectStore`]. Conditional writes are serialized
+/// through an in-process mutex, which is sufficient for a single indexer
+/// process; remote backends instead rely on the store's native CAS
+/// primitive (e.g. S3 conditional PUT).
+pub struct LocalStore {
+    root: PathBuf,
+    cas_lock: Mutex<()>,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self {
+            root,
+            cas_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Scope every key under this store to `tenant`'s prefix, so its
+    /// namespaces live at `tenants/<tenant>/<namespace>/...` and can be
+    /// enumerated or deleted as a unit. Errors if `tenant` isn't a single
+    /// plain path segment (see [`validate_segment`]) — otherwise a tenant
+    /// of `".."` would resolve outside `tenants/` entirely and
+    /// [`TenantHandle::delete_all`] would wipe every other tenant's data.
+    pub fn tenant(&self, tenant: impl Into<String>) -> Result<TenantHandle<'_>> {
+        let tenant = tenant.into();
+        validate_segment(&tenant)?;
+        Ok(TenantHandle { store: self, tenant })
+    }
+
+    fn data_path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+
+    fn gen_path(&self, key: &str) -> PathBuf {
+        self.root.join(format!("{key}.gen"))
+    }
+
+    fn read_generation(path: &Path) -> Result<Option<Generation>> {
+        match fs::read_to_string(path) {
+            Ok(s) => Ok(Some(s.trim().parse().unwrap_or(0))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Reject anything that isn't a single plain path segment: empty, `.`,
+/// `..`, or containing a path separator. Tenant and namespace names are
+/// joined straight into filesystem paths ([`TenantHandle::prefix`],
+/// [`NamespaceHandle::key`]), so a segment like `".."` or `"a/../b"` would
+/// otherwise let a caller escape the tenant/namespace directory it was
+/// meant to be confined to.
+fn validate_segment(segment: &str) -> Result<()> {
+    if segment.is_empty() || segment == "." || segment == ".." || segment.contains('/') || segment.contains('\\') {
+        return Err(StoreError::InvalidPathSegment(segment.to_string()));
+    }
+    Ok(())
+}
+
+impl ObjectStore for LocalStore {
+    fn get(&self, key: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+        let data_path = self.data_path(key);
+        let data = match fs::read(&data_path) {
+            Ok(d) => d,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let generation = Self::read_generation(&self.gen_path(key))?.unwrap_or(0);
+        Ok(Some((data, generation)))
+    }
+
+    fn put_if_match(
+        &self,
+        key: &str,
+        expected: Option<Generation>,
+        value: Vec<u8>,
+    ) -> Result<Generation> {
+        let _guard = self.cas_lock.lock().unwrap();
+
+        let current = Self::read_generation(&self.gen_path(key))?;
+        if current != expected {
+            return Err(StoreError::GenerationMismatch {
+                expected,
+                found: current,
+            });
+        }
+
+        let next = current.unwrap_or(0) + 1;
+        let data_path = self.data_path(key);
+        if let Some(parent) = data_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = self.root.join(format!("{key}.tmp"));
+        fs::write(&tmp_path, &value)?;
+        fs::rename(&tmp_path, &data_path)?;
+        fs::write(self.gen_path(key), next.to_string())?;
+        Ok(next)
+    }
+}
+
+/// A tenant-scoped view over a [`LocalStore`]: every key this hands out is
+/// prefixed with `tenants/<tenant>/`, so operators can enumerate or wipe a
+/// tenant's namespaces without touching any other tenant's data.
+pub struct TenantHandle<'a> {
+    store: &'a LocalStore,
+    tenant: String,
+}
+
+impl<'a> TenantHandle<'a> {
+    fn prefix(&self) -> PathBuf {
+        self.store.root.join("tenants").join(&self.tenant)
+    }
+
+    /// Scope further to one namespace within this tenant. Errors if
+    /// `namespace` isn't a single plain path segment (see
+    /// [`validate_segment`]) — otherwise a namespace of `"../other/docs"`
+    /// would resolve into a sibling tenant's directory.
+    pub fn namespace(&self, namespace: impl Into<String>) -> Result<NamespaceHandle<'a>> {
+        let namespace = namespace.into();
+        validate_segment(&namespace)?;
+        Ok(NamespaceHandle {
+            store: self.store,
+            prefix: format!("tenants/{}/{namespace}", self.tenant),
+        })
+    }
+
+    /// List the namespaces that currently have at least one object under
+    /// this tenant's prefix.
+    pub fn namespaces(&self) -> Result<Vec<String>> {
+        let dir = self.prefix();
+        match fs::read_dir(&dir) {
+            Ok(entries) => {
+                let mut names = Vec::new();
+                for entry in entries {
+                    let entry = entry?;
+                    if entry.file_type()?.is_dir() {
+                        names.push(entry.file_name().to_string_lossy().into_owned());
+                    }
+                }
+                Ok(names)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Delete one namespace's directory within this tenant.
+    pub fn delete_namespace(&self, namespace: &str) -> Result<()> {
+        validate_segment(namespace)?;
+        let dir = self.prefix().join(namespace);
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Delete every namespace belonging to this tenant.
+    pub fn delete_all(&self) -> Result<()> {
+        match fs::remove_dir_all(self.prefix()) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// A namespace within a tenant, scoped to `tenants/<tenant>/<namespace>/`.
+/// Keys passed to [`Self::get`]/[`Self::put_if_match`] are relative to this
+/// prefix — e.g. `"manifest.json"` resolves to
+/// `tenants/<tenant>/<namespace>/manifest.json`.
+pub struct NamespaceHandle<'a> {
+    store: &'a LocalStore,
+    prefix: String,
+}
+
+impl<'a> NamespaceHandle<'a> {
+    fn key(&self, suffix: &str) -> String {
+        format!("{}/{suffix}", self.prefix)
+    }
+
+    pub fn get(&self, suffix: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+        self.store.get(&self.key(suffix))
+    }
+
+    pub fn put_if_match(
+        &self,
+        suffix: &str,
+        expected: Option<Generation>,
+        value: Vec<u8>,
+    ) -> Result<Generation> {
+        self.store.put_if_match(&self.key(suffix), expected, value)
+    }
+
+    /// Total size, in bytes, of everything this namespace has written
+    /// locally — manifests, parts, and any WAL file a caller has pointed at
+    /// this same directory — so capacity planning doesn't need `du -sh`.
+    /// Remote object-store backends have no equivalent today: [`ObjectStore`]
+    /// exposes no listing primitive to sum sizes from, only point
+    /// `get`/`put_if_match`.
+    pub fn disk_bytes(&self) -> Result<u64> {
+        directory_bytes(&self.store.root.join(&self.prefix))
+    }
+}
+
+fn directory_bytes(dir: &Path) -> Result<u64> {
+    let mut total = 0u64;
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            total += directory_bytes(&entry.path())?;
+        } else {
+            total += entry.metadata()?.len();
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_write_requires_none_expected() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+        assert!(store.put_if_match("router.json", None, b"v1".to_vec()).is_ok());
+        assert!(store
+            .put_if_match("router.json", None, b"v2".to_vec())
+            .is_err());
+    }
+
+    #[test]
+    fn conditional_write_advances_generation() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+        let gen1 = store.put_if_match("router.json", None, b"v1".to_vec()).unwrap();
+        assert_eq!(gen1, 1);
+        let gen2 = store
+            .put_if_match("router.json", Some(gen1), b"v2".to_vec())
+            .unwrap();
+        assert_eq!(gen2, 2);
+        let (data, gen) = store.get("router.json").unwrap().unwrap();
+        assert_eq!(data, b"v2");
+        assert_eq!(gen, 2);
+    }
+
+    #[test]
+    fn tenants_are_isolated_and_enumerable() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+
+        store
+            .tenant("acme").unwrap()
+            .namespace("docs").unwrap()
+            .put_if_match("manifest.json", None, b"v1".to_vec())
+            .unwrap();
+        store
+            .tenant("acme").unwrap()
+            .namespace("images").unwrap()
+            .put_if_match("manifest.json", None, b"v1".to_vec())
+            .unwrap();
+        store
+            .tenant("globex").unwrap()
+            .namespace("docs").unwrap()
+            .put_if_match("manifest.json", None, b"other".to_vec())
+            .unwrap();
+
+        let mut namespaces = store.tenant("acme").unwrap().namespaces().unwrap();
+        namespaces.sort();
+        assert_eq!(namespaces, vec!["docs".to_string(), "images".to_string()]);
+
+        let (acme_docs, _) = store
+            .tenant("acme").unwrap()
+            .namespace("docs").unwrap()
+            .get("manifest.json")
+            .unwrap()
+            .unwrap();
+        assert_eq!(acme_docs, b"v1");
+    }
+
+    #[test]
+    fn deleting_a_tenant_namespace_does_not_touch_siblings() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+
+        store
+            .tenant("acme").unwrap()
+            .namespace("docs").unwrap()
+            .put_if_match("manifest.json", None, b"v1".to_vec())
+            .unwrap();
+        store
+            .tenant("acme").unwrap()
+            .namespace("images").unwrap()
+            .put_if_match("manifest.json", None, b"v1".to_vec())
+            .unwrap();
+
+        store.tenant("acme").unwrap().delete_namespace("docs").unwrap();
+
+        let namespaces = store.tenant("acme").unwrap().namespaces().unwrap();
+        assert_eq!(namespaces, vec!["images".to_string()]);
+    }
+
+    #[test]
+    fn disk_bytes_counts_everything_written_under_the_namespace_and_nothing_else() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+
+        let docs = store.tenant("acme").unwrap().namespace("docs").unwrap();
+        docs.put_if_match("manifest.json", None, b"0123456789".to_vec()).unwrap();
+        let bytes_after_one_write = docs.disk_bytes().unwrap();
+        assert!(bytes_after_one_write >= 10);
+
+        store
+            .tenant("acme").unwrap()
+            .namespace("images").unwrap()
+            .put_if_match("manifest.json", None, b"hello".to_vec())
+            .unwrap();
+        assert_eq!(docs.disk_bytes().unwrap(), bytes_after_one_write);
+    }
+
+    #[test]
+    fn disk_bytes_on_a_namespace_with_no_writes_is_zero() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+        assert_eq!(store.tenant("acme").unwrap().namespace("docs").unwrap().disk_bytes().unwrap(), 0);
+    }
+
+    #[test]
+    fn a_tenant_of_dotdot_is_rejected_instead_of_escaping_the_tenants_directory() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+        assert!(matches!(store.tenant(".."), Err(StoreError::InvalidPathSegment(_))));
+    }
+
+    #[test]
+    fn a_namespace_that_traverses_into_a_sibling_tenant_is_rejected() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+        let tenant = store.tenant("acme").unwrap();
+        assert!(matches!(tenant.namespace("../globex/docs"), Err(StoreError::InvalidPathSegment(_))));
+        assert!(matches!(tenant.delete_namespace("../globex/docs"), Err(StoreError::InvalidPathSegment(_))));
+    }
+
+    #[test]
+    fn empty_dot_and_backslash_segments_are_all_rejected() {
+        let dir = tempfile_dir();
+        let store = LocalStore::new(&dir).unwrap();
+        for bad in ["", ".", "..", "a\\b"] {
+            assert!(store.tenant(bad).is_err(), "expected {bad:?} to be rejected");
+        }
+    }
+
+    fn tempfile_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "elax-store-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        dir
+    }
+}
@@ -0,0 +1,221 @@
+//! Concurrent, chunked upload of large objects to an [`ObjectStore`], and
+//! concurrent upload of several independent objects (e.g. a part's rows,
+//! IVF, and filter pages) as one unit. `ObjectStore` has no native
+//! multipart primitive — chunks are just uploaded as their own keys under
+//! `<key>.partN`, committed by a `<key>.manifest` key written only once
+//! every chunk has landed, so a reader never sees a partially-uploaded
+//! object: it waits for the manifest, not the chunks.
+
+use std::sync::Arc;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::object_store::{Generation, ObjectStore};
+
+/// Chunk size and fan-out for [`upload_multipart`] and [`upload_assets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipartConfig {
+    pub part_size: usize,
+    pub parallelism: usize,
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self { part_size: 8 * 1024 * 1024, parallelism: 4 }
+    }
+}
+
+impl MultipartConfig {
+    fn effective_part_size(&self) -> usize {
+        self.part_size.max(1)
+    }
+
+    fn effective_parallelism(&self) -> usize {
+        self.parallelism.max(1)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    chunk_count: usize,
+    total_bytes: usize,
+}
+
+/// One independently-uploadable named object, e.g. a part's `"rows"`,
+/// `"ivf"`, or `"filters"` page.
+pub struct Asset {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Upload `bytes` to `key` as one or more `<key>.partN` chunks of at most
+/// `config.part_size`, `config.parallelism` chunks in flight at a time,
+/// then commit `<key>.manifest` once every chunk has succeeded.
+pub fn upload_multipart(store: &dyn ObjectStore, key: &str, bytes: &[u8], config: MultipartConfig) -> Result<()> {
+    let chunks: Vec<&[u8]> = if bytes.is_empty() {
+        vec![&[]]
+    } else {
+        bytes.chunks(config.effective_part_size()).collect()
+    };
+
+    for (batch_start, batch) in chunks.chunks(config.effective_parallelism()).enumerate() {
+        let offset = batch_start * config.effective_parallelism();
+        let results: Vec<Result<Generation>> = thread::scope(|scope| {
+            batch
+                .iter()
+                .enumerate()
+                .map(|(i, chunk)| {
+                    let part_key = format!("{key}.part{}", offset + i);
+                    scope.spawn(move || store.put_if_match(&part_key, None, chunk.to_vec()))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().expect("multipart upload thread panicked"))
+                .collect()
+        });
+        for result in results {
+            result?;
+        }
+    }
+
+    let manifest = Manifest { chunk_count: chunks.len(), total_bytes: bytes.len() };
+    store.put_if_match(&format!("{key}.manifest"), None, serde_json::to_vec(&manifest)?)?;
+    Ok(())
+}
+
+/// Upload every asset in `assets` concurrently (each via
+/// [`upload_multipart`] under `<key_prefix>.<asset.name>`), so independent
+/// pages of the same part don't serialize behind each other. If any asset
+/// fails, the whole call fails and later batches are not started — but
+/// since `ObjectStore` has no delete primitive, whatever was already
+/// uploaded is left in place rather than reclaimed. A failed asset has no
+/// committed manifest, so a reader enumerating complete objects never sees
+/// it; an asset that finished before the failure (in an earlier batch or
+/// alongside it in the same batch) keeps its committed manifest. The
+/// leftovers are orphaned storage for a GC pass to reclaim later.
+pub fn upload_assets(store: &dyn ObjectStore, key_prefix: &str, assets: &[Asset], config: MultipartConfig) -> Result<()> {
+    for batch in assets.chunks(config.effective_parallelism()) {
+        let results: Vec<Result<()>> = thread::scope(|scope| {
+            batch
+                .iter()
+                .map(|asset| {
+                    let key = format!("{key_prefix}.{}", asset.name);
+                    scope.spawn(move || upload_multipart(store, &key, &asset.bytes, config))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().expect("asset upload thread panicked"))
+                .collect()
+        });
+        for result in results {
+            result?;
+        }
+    }
+    Ok(())
+}
+
+/// Download and reassemble an object written by [`upload_multipart`].
+pub fn download_multipart(store: &Arc<dyn ObjectStore>, key: &str) -> Result<Vec<u8>> {
+    let (manifest_bytes, _) = store
+        .get(&format!("{key}.manifest"))?
+        .ok_or_else(|| crate::error::StoreError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, key)))?;
+    let manifest: Manifest = serde_json::from_slice(&manifest_bytes)?;
+
+    let mut result = Vec::with_capacity(manifest.total_bytes);
+    for i in 0..manifest.chunk_count {
+        let (chunk, _) = store
+            .get(&format!("{key}.part{i}"))?
+            .ok_or_else(|| crate::error::StoreError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, key)))?;
+        result.extend_from_slice(&chunk);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local::LocalStore;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn tmp_store() -> LocalStore {
+        let dir = std::env::temp_dir().join(format!(
+            "elax-store-multipart-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()
+        ));
+        LocalStore::new(dir).unwrap()
+    }
+
+    #[test]
+    fn a_large_object_round_trips_through_several_chunks() {
+        let store = tmp_store();
+        let bytes: Vec<u8> = (0..1000).map(|i| (i % 256) as u8).collect();
+        let config = MultipartConfig { part_size: 64, parallelism: 3 };
+
+        upload_multipart(&store, "rows", &bytes, config).unwrap();
+
+        let store: Arc<dyn ObjectStore> = Arc::new(store);
+        let downloaded = download_multipart(&store, "rows").unwrap();
+        assert_eq!(downloaded, bytes);
+    }
+
+    #[test]
+    fn an_empty_object_still_produces_a_readable_manifest() {
+        let store = tmp_store();
+        upload_multipart(&store, "empty", &[], MultipartConfig::default()).unwrap();
+
+        let store: Arc<dyn ObjectStore> = Arc::new(store);
+        assert_eq!(download_multipart(&store, "empty").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn independent_assets_upload_concurrently_and_all_round_trip() {
+        let store = tmp_store();
+        let assets = vec![
+            Asset { name: "rows".to_string(), bytes: vec![1, 2, 3, 4, 5] },
+            Asset { name: "ivf".to_string(), bytes: vec![9, 8, 7] },
+            Asset { name: "filters".to_string(), bytes: vec![0; 200] },
+        ];
+
+        upload_assets(&store, "part-0", &assets, MultipartConfig { part_size: 32, parallelism: 2 }).unwrap();
+
+        let store: Arc<dyn ObjectStore> = Arc::new(store);
+        assert_eq!(download_multipart(&store, "part-0.rows").unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(download_multipart(&store, "part-0.ivf").unwrap(), vec![9, 8, 7]);
+        assert_eq!(download_multipart(&store, "part-0.filters").unwrap(), vec![0; 200]);
+    }
+
+    struct FailingStore {
+        calls: AtomicUsize,
+        fail_on_call: usize,
+    }
+
+    impl ObjectStore for FailingStore {
+        fn get(&self, _key: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+            unimplemented!()
+        }
+
+        fn put_if_match(&self, _key: &str, _expected: Option<Generation>, _value: Vec<u8>) -> Result<Generation> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call == self.fail_on_call {
+                Err(crate::error::StoreError::Io(std::io::Error::other("disk full")))
+            } else {
+                Ok(1)
+            }
+        }
+    }
+
+    #[test]
+    fn one_failing_asset_fails_the_whole_upload() {
+        let store = FailingStore { calls: AtomicUsize::new(0), fail_on_call: 1 };
+        let assets = vec![
+            Asset { name: "rows".to_string(), bytes: vec![1; 10] },
+            Asset { name: "ivf".to_string(), bytes: vec![2; 10] },
+        ];
+
+        let err = upload_assets(&store, "part-0", &assets, MultipartConfig { part_size: 4, parallelism: 1 });
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use crate::error::Result;
+
+/// Opaque version stamp used for optimistic concurrency control.
+pub type Generation = u64;
+
+/// A key-value object store with conditional-write support, implemented by
+/// both the local filesystem and (eventually) remote backends such as S3.
+pub trait ObjectStore: Send + Sync {
+    /// Fetch the current value and generation for `key`, if it exists.
+    fn get(&self, key: &str) -> Result<Option<(Vec<u8>, Generation)>>;
+
+    /// Write `value` to `key`, but only if the object's current generation
+    /// matches `expected` (`None` means "key must not exist"). Returns the
+    /// new generation on success, or `StoreError::GenerationMismatch` if
+    /// another writer won the race.
+    fn put_if_match(
+        &self,
+        key: &str,
+        expected: Option<Generation>,
+        value: Vec<u8>,
+    ) -> Result<Generation>;
+}
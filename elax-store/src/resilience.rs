@@ -0,0 +1,286 @@
+//! Wraps any [`ObjectStore`] with retries, per-op timeouts, and optional
+//! hedged reads, the same decorator shape [`crate::crypto::EncryptingStore`]
+//! uses — so a flaky or slow remote backend doesn't have to fail a query or
+//! part publish outright. There's no cancellation primitive for a blocking
+//! call, so a timed-out attempt keeps running on its own thread in the
+//! background; it just stops being waited on.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{Result, StoreError};
+use crate::object_store::{Generation, ObjectStore};
+
+/// Counts of retry/timeout/hedge events, so an operator can tell a
+/// genuinely flaky backend from a well-behaved one without digging through
+/// logs.
+#[derive(Debug, Default)]
+pub struct RetryMetrics {
+    retries: AtomicU64,
+    timeouts: AtomicU64,
+    hedge_wins: AtomicU64,
+}
+
+impl RetryMetrics {
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    pub fn timeouts(&self) -> u64 {
+        self.timeouts.load(Ordering::Relaxed)
+    }
+
+    /// How many hedged reads were answered by the hedge request rather
+    /// than the primary one.
+    pub fn hedge_wins(&self) -> u64 {
+        self.hedge_wins.load(Ordering::Relaxed)
+    }
+}
+
+/// Retries with exponential backoff (`base_delay_ms * 2^attempt`) and
+/// times out individual attempts against `inner`.
+pub struct ResilientStore {
+    inner: Arc<dyn ObjectStore>,
+    retry: crate::storage_config::RetryConfig,
+    timeout: Duration,
+    pub metrics: RetryMetrics,
+}
+
+impl ResilientStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, retry: crate::storage_config::RetryConfig, timeout: Duration) -> Self {
+        Self { inner, retry, timeout, metrics: RetryMetrics::default() }
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        Duration::from_millis(self.retry.base_delay_ms.saturating_mul(1u64 << attempt.min(16)))
+    }
+
+    fn run_with_timeout<T, F>(&self, op: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(op());
+        });
+        rx.recv_timeout(self.timeout).unwrap_or_else(|_| {
+            self.metrics.timeouts.fetch_add(1, Ordering::Relaxed);
+            Err(StoreError::Timeout)
+        })
+    }
+
+    /// Fetch `key`, retrying transient failures with exponential backoff
+    /// up to `retry.max_attempts`, each attempt bounded by `timeout`.
+    pub fn get(&self, key: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+        let mut attempt = 0;
+        loop {
+            let inner = self.inner.clone();
+            let owned_key = key.to_string();
+            match self.run_with_timeout(move || inner.get(&owned_key)) {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    attempt += 1;
+                    if attempt >= self.retry.max_attempts {
+                        return Err(err);
+                    }
+                    self.metrics.retries.fetch_add(1, Ordering::Relaxed);
+                    thread::sleep(self.backoff_delay(attempt));
+                }
+            }
+        }
+    }
+
+    /// Conditionally write `key`, retrying transient failures the same way
+    /// as [`Self::get`]. A genuine `GenerationMismatch` is not transient —
+    /// retrying it would just observe the same loser result — so it's
+    /// returned immediately without consuming a retry attempt.
+    pub fn put_if_match(&self, key: &str, expected: Option<Generation>, value: Vec<u8>) -> Result<Generation> {
+        let mut attempt = 0;
+        loop {
+            let inner = self.inner.clone();
+            let owned_key = key.to_string();
+            let owned_value = value.clone();
+            match self.run_with_timeout(move || inner.put_if_match(&owned_key, expected, owned_value)) {
+                Ok(generation) => return Ok(generation),
+                Err(StoreError::GenerationMismatch { expected, found }) => {
+                    return Err(StoreError::GenerationMismatch { expected, found })
+                }
+                Err(err) => {
+                    attempt += 1;
+                    if attempt >= self.retry.max_attempts {
+                        return Err(err);
+                    }
+                    self.metrics.retries.fetch_add(1, Ordering::Relaxed);
+                    thread::sleep(self.backoff_delay(attempt));
+                }
+            }
+        }
+    }
+
+    /// Fetch `key`, and if the primary attempt hasn't answered within
+    /// `hedge_delay`, fire a second request at the same key and serve
+    /// whichever comes back first — latency insurance for a part fetch
+    /// sitting on a query's critical path, at the cost of a duplicate read
+    /// against the backend. Unlike [`Self::get`], a failed attempt is not
+    /// retried: the first result to arrive, error or not, is returned.
+    pub fn get_hedged(&self, key: &str, hedge_delay: Duration) -> Result<Option<(Vec<u8>, Generation)>> {
+        let (tx, rx) = mpsc::channel();
+
+        let inner = self.inner.clone();
+        let owned_key = key.to_string();
+        let primary_tx = tx.clone();
+        thread::spawn(move || {
+            let _ = primary_tx.send((false, inner.get(&owned_key)));
+        });
+
+        if let Ok((_, result)) = rx.recv_timeout(hedge_delay) {
+            return result;
+        }
+
+        let inner = self.inner.clone();
+        let owned_key = key.to_string();
+        thread::spawn(move || {
+            let _ = tx.send((true, inner.get(&owned_key)));
+        });
+
+        match rx.recv_timeout(self.timeout) {
+            Ok((was_hedge, result)) => {
+                if was_hedge {
+                    self.metrics.hedge_wins.fetch_add(1, Ordering::Relaxed);
+                }
+                result
+            }
+            Err(_) => {
+                self.metrics.timeouts.fetch_add(1, Ordering::Relaxed);
+                Err(StoreError::Timeout)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct FlakyStore {
+        attempts: AtomicUsize,
+        fail_until: usize,
+    }
+
+    impl ObjectStore for FlakyStore {
+        fn get(&self, _key: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+            let attempt = self.attempts.fetch_add(1, Ordering::Relaxed);
+            if attempt < self.fail_until {
+                Err(StoreError::Io(std::io::Error::other("flaky")))
+            } else {
+                Ok(Some((b"v".to_vec(), 1)))
+            }
+        }
+
+        fn put_if_match(&self, _key: &str, _expected: Option<Generation>, _value: Vec<u8>) -> Result<Generation> {
+            unimplemented!()
+        }
+    }
+
+    struct SlowStore {
+        delay: Duration,
+    }
+
+    impl ObjectStore for SlowStore {
+        fn get(&self, _key: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+            thread::sleep(self.delay);
+            Ok(Some((b"slow".to_vec(), 1)))
+        }
+
+        fn put_if_match(&self, _key: &str, _expected: Option<Generation>, _value: Vec<u8>) -> Result<Generation> {
+            unimplemented!()
+        }
+    }
+
+    /// A straggler: the first call is slow, every call after it is instant —
+    /// modeling the kind of one-off tail latency hedging is meant to hide.
+    struct StragglerStore {
+        calls: AtomicUsize,
+        first_call_delay: Duration,
+    }
+
+    impl ObjectStore for StragglerStore {
+        fn get(&self, _key: &str) -> Result<Option<(Vec<u8>, Generation)>> {
+            if self.calls.fetch_add(1, Ordering::Relaxed) == 0 {
+                thread::sleep(self.first_call_delay);
+            }
+            Ok(Some((b"v".to_vec(), 1)))
+        }
+
+        fn put_if_match(&self, _key: &str, _expected: Option<Generation>, _value: Vec<u8>) -> Result<Generation> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn retries_a_transient_failure_and_succeeds() {
+        let store = ResilientStore::new(
+            Arc::new(FlakyStore { attempts: AtomicUsize::new(0), fail_until: 2 }),
+            crate::storage_config::RetryConfig { max_attempts: 5, base_delay_ms: 1 },
+            Duration::from_secs(1),
+        );
+
+        let result = store.get("k").unwrap();
+        assert_eq!(result, Some((b"v".to_vec(), 1)));
+        assert_eq!(store.metrics.retries(), 2);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let store = ResilientStore::new(
+            Arc::new(FlakyStore { attempts: AtomicUsize::new(0), fail_until: 100 }),
+            crate::storage_config::RetryConfig { max_attempts: 3, base_delay_ms: 1 },
+            Duration::from_secs(1),
+        );
+
+        assert!(store.get("k").is_err());
+        assert_eq!(store.metrics.retries(), 2);
+    }
+
+    #[test]
+    fn a_slow_attempt_times_out_without_retrying_past_max_attempts() {
+        let store = ResilientStore::new(
+            Arc::new(SlowStore { delay: Duration::from_millis(50) }),
+            crate::storage_config::RetryConfig { max_attempts: 2, base_delay_ms: 1 },
+            Duration::from_millis(5),
+        );
+
+        assert!(matches!(store.get("k"), Err(StoreError::Timeout)));
+        assert_eq!(store.metrics.timeouts(), 2);
+    }
+
+    #[test]
+    fn a_hedged_read_is_served_by_the_hedge_when_the_primary_is_slow() {
+        let store = ResilientStore::new(
+            Arc::new(StragglerStore { calls: AtomicUsize::new(0), first_call_delay: Duration::from_millis(200) }),
+            crate::storage_config::RetryConfig::default(),
+            Duration::from_secs(1),
+        );
+
+        let result = store.get_hedged("k", Duration::from_millis(5)).unwrap();
+        assert_eq!(result, Some((b"v".to_vec(), 1)));
+        assert_eq!(store.metrics.hedge_wins(), 1);
+    }
+
+    #[test]
+    fn a_hedged_read_served_by_a_fast_primary_never_fires_the_hedge() {
+        let store = ResilientStore::new(
+            Arc::new(SlowStore { delay: Duration::from_millis(1) }),
+            crate::storage_config::RetryConfig::default(),
+            Duration::from_secs(1),
+        );
+
+        let result = store.get_hedged("k", Duration::from_millis(200)).unwrap();
+        assert_eq!(result, Some((b"slow".to_vec(), 1)));
+        assert_eq!(store.metrics.hedge_wins(), 0);
+    }
+}
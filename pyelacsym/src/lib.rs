@@ -0,0 +1,72 @@
+//! Python bindings for the embedded engine, built with PyO3. Wraps a
+//! single [`elacsym::TieredNamespace`] behind a `Store` class so a data
+//! scientist can upsert numpy vectors and query with a numpy query vector
+//! to test relevance locally, without running `elax-api`'s HTTP server.
+//! Vector search releases the GIL (see [`Store::query`]) since it's pure
+//! Rust/CPU work with no need to touch Python objects mid-search.
+
+use numpy::{PyArray1, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use elacsym::{Document, TieredNamespace};
+
+/// A single namespace's worth of vectors, held entirely in memory (no
+/// parts are ever flushed to `parts_dir` unless the embedder calls
+/// something that does so explicitly) — the `elax_core::TieredNamespace`
+/// this wraps doesn't require its rows to be on disk to be searchable.
+#[pyclass]
+struct Store {
+    inner: TieredNamespace,
+}
+
+#[pymethods]
+impl Store {
+    /// Create an empty store whose parts directory is `parts_dir`. Parts
+    /// already in that directory are not loaded.
+    #[new]
+    fn new(parts_dir: &str) -> Self {
+        Self { inner: TieredNamespace::new("embedded", parts_dir) }
+    }
+
+    /// Upsert `ids[i]` with the vector in row `i` of `vectors`. `ids` and
+    /// `vectors` must have the same length.
+    fn upsert(&mut self, ids: Vec<String>, vectors: PyReadonlyArray2<f32>) -> PyResult<()> {
+        let vectors = vectors.as_array();
+        if vectors.nrows() != ids.len() {
+            return Err(PyValueError::new_err(format!(
+                "ids has {} entries but vectors has {} rows",
+                ids.len(),
+                vectors.nrows()
+            )));
+        }
+        for (id, row) in ids.into_iter().zip(vectors.rows()) {
+            self.inner.upsert(Document::new(id, row.to_vec()));
+        }
+        Ok(())
+    }
+
+    /// The `top_k` nearest rows to `vector` by the namespace's scoring
+    /// metric, as `(ids, scores)` — a numpy array for `scores` so the
+    /// caller can feed it straight into further numpy computation.
+    fn query<'py>(
+        &self,
+        py: Python<'py>,
+        vector: PyReadonlyArray1<f32>,
+        top_k: usize,
+    ) -> PyResult<(Vec<String>, Bound<'py, PyArray1<f32>>)> {
+        let query = vector.as_slice()?.to_vec();
+        let hits = py
+            .detach(|| self.inner.search(&query, top_k))
+            .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+        let ids = hits.iter().map(|hit| hit.id.clone()).collect();
+        let scores: Vec<f32> = hits.iter().map(|hit| hit.score).collect();
+        Ok((ids, PyArray1::from_vec(py, scores)))
+    }
+}
+
+#[pymodule]
+fn pyelacsym(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Store>()?;
+    Ok(())
+}